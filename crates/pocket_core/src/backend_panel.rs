@@ -1,5 +1,8 @@
+use crate::{is_mobile, Command, FrameHistory};
+
 /// How often we repaint the demo app by default
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 enum RunMode {
     /// This is the default for the demo.
     ///
@@ -27,8 +30,21 @@ enum RunMode {
     /// For games or other interactive apps, this is probably what you want to do.
     /// It will guarantee that egui is always up-to-date.
     Continuous,
+
+    /// Repaint at most [`BackendPanel::target_fps`] times a second, using
+    /// `egui::Context::request_repaint_after`.
+    ///
+    /// Input and animations still repaint right away, so moving the mouse can exceed the cap.
+    Capped,
+
+    /// Like [`Capped`](RunMode::Capped) while the window has focus, but drops to
+    /// [`BATTERY_SAVER_FPS`] when it is unfocused or minimized.
+    BatterySaver,
 }
 
+/// How often [`RunMode::BatterySaver`] repaints in the background.
+const BATTERY_SAVER_FPS: f32 = 5.0;
+
 /// Default for demo is Reactive since
 /// 1) We want to use minimal CPU
 /// 2) There are no external events that could invalidate the UI
@@ -41,23 +57,41 @@ impl Default for RunMode {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct BackendPanel {
     pub open: bool,
 
-    #[cfg_attr(feature = "serde", serde(skip))]
-    // go back to [`RunMode::Reactive`] mode each time we start
     run_mode: RunMode,
 
+    /// The rate of [`RunMode::Capped`], and of [`RunMode::BatterySaver`] in the foreground.
+    target_fps: u32,
+
     #[cfg_attr(feature = "serde", serde(skip))]
-    frame_history: crate::frame_history::FrameHistory,
+    frame_history: FrameHistory,
 
     egui_windows: EguiWindows,
 }
 
+impl Default for BackendPanel {
+    fn default() -> Self {
+        Self {
+            open: false,
+            run_mode: RunMode::default(),
+            target_fps: 30,
+            frame_history: Default::default(),
+            egui_windows: Default::default(),
+        }
+    }
+}
+
 impl BackendPanel {
+    /// Save frame time recordings in `dir`, see [`FrameHistory::set_recording_dir`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_recording_dir(&mut self, dir: Option<std::path::PathBuf>) {
+        self.frame_history.set_recording_dir(dir);
+    }
+
     pub fn update(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
         self.frame_history
             .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
@@ -70,6 +104,30 @@ impl BackendPanel {
             RunMode::Reactive => {
                 // let the computer rest for a bit
             }
+            RunMode::Capped | RunMode::BatterySaver => {
+                if let Some(fps) = self.target_fps(ctx) {
+                    ctx.request_repaint_after(std::time::Duration::from_secs_f32(1.0 / fps));
+                }
+            }
+        }
+    }
+
+    /// The rate we are repainting at, if we are aiming for one.
+    fn target_fps(&self, ctx: &egui::Context) -> Option<f32> {
+        match self.run_mode {
+            RunMode::Reactive | RunMode::Continuous => None,
+            RunMode::Capped => Some(self.target_fps.max(1) as f32),
+            RunMode::BatterySaver => {
+                let in_background = ctx.input(|i| {
+                    let viewport = i.viewport();
+                    viewport.focused == Some(false) || viewport.minimized == Some(true)
+                });
+                Some(if in_background {
+                    BATTERY_SAVER_FPS
+                } else {
+                    self.target_fps.max(1) as f32
+                })
+            }
         }
     }
 
@@ -77,6 +135,73 @@ impl BackendPanel {
         self.egui_windows.windows(ctx);
     }
 
+    /// Show the panel on `side` of the window when it is open.
+    ///
+    /// Nothing is shown on mobile, where the panel is a menu in the top bar instead,
+    /// see [`Self::top_bar_ui`].
+    pub fn side_panel(
+        &mut self,
+        ctx: &egui::Context,
+        frame: &mut eframe::Frame,
+        side: egui::panel::Side,
+        cmd: &mut Command,
+    ) {
+        if is_mobile(ctx) {
+            return;
+        }
+
+        // The backend-panel can be toggled on/off.
+        // We show a little animation when the user switches it.
+        let is_open = self.open || ctx.memory(|mem| mem.everything_is_visible());
+
+        egui::SidePanel::new(side, "backend_panel")
+            .resizable(false)
+            .show_animated(ctx, is_open, |ui| {
+                ui.add_space(4.0);
+                ui.vertical_centered(|ui| {
+                    ui.heading("💻 Backend");
+                });
+
+                ui.separator();
+                self.contents_ui(ui, frame, cmd);
+            });
+    }
+
+    /// The "💻 Backend" button in the top bar, which toggles the side panel,
+    /// or opens a menu with its contents on mobile.
+    pub fn top_bar_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, cmd: &mut Command) {
+        if is_mobile(ui.ctx()) {
+            ui.menu_button("💻 Backend", |ui| {
+                ui.set_style(ui.ctx().style()); // ignore the "menu" style set by `menu_button`.
+                self.contents_ui(ui, frame, cmd);
+            });
+        } else {
+            ui.toggle_value(&mut self.open, "💻 Backend");
+        }
+    }
+
+    fn contents_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, cmd: &mut Command) {
+        self.ui(ui, frame);
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Reset egui")
+                .on_hover_text("Forget scroll, positions, sizes etc")
+                .clicked()
+            {
+                ui.ctx().memory_mut(|mem| *mem = Default::default());
+                ui.close_menu();
+            }
+
+            if ui.button("Reset everything").clicked() {
+                *cmd = Command::ResetEverything;
+                ui.close_menu();
+            }
+        });
+    }
+
     pub fn ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
         integration_ui(ui, frame);
 
@@ -86,7 +211,8 @@ impl BackendPanel {
 
         ui.separator();
 
-        self.frame_history.ui(ui);
+        let target_fps = self.target_fps(ui.ctx());
+        self.frame_history.ui(ui, target_fps);
 
         ui.separator();
 
@@ -133,12 +259,34 @@ impl BackendPanel {
             ui.radio_value(run_mode, RunMode::Continuous, "Continuous")
                 .on_hover_text("Repaint everything each frame");
         });
+        ui.horizontal(|ui| {
+            let run_mode = &mut self.run_mode;
+            ui.radio_value(run_mode, RunMode::Capped, "Capped")
+                .on_hover_text("Repaint at a fixed rate");
+            ui.radio_value(run_mode, RunMode::BatterySaver, "Battery saver")
+                .on_hover_text(format!(
+                    "Repaint at a fixed rate, but only {BATTERY_SAVER_FPS} times a second \
+                    when the window is unfocused or minimized"
+                ));
+        });
 
         if self.run_mode == RunMode::Continuous {
             ui.label(format!(
                 "Repainting the UI each frame. FPS: {:.1}",
                 self.frame_history.fps()
             ));
+        } else if matches!(self.run_mode, RunMode::Capped | RunMode::BatterySaver) {
+            ui.horizontal(|ui| {
+                ui.label("Repainting the UI");
+                ui.add(
+                    egui::DragValue::new(&mut self.target_fps)
+                        .range(1..=240)
+                        .suffix(" fps"),
+                );
+                if self.run_mode == RunMode::BatterySaver {
+                    ui.label("while focused.");
+                }
+            });
         } else {
             ui.label("Only running UI code when there are animations or input.");
 
@@ -207,7 +355,7 @@ fn integration_ui(ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
         let wgpu_adapter_details_ui = |ui: &mut egui::Ui, adapter: &eframe::wgpu::Adapter| {
             let info = &adapter.get_info();
 
-            let wgpu::AdapterInfo {
+            let eframe::wgpu::AdapterInfo {
                 name,
                 vendor,
                 device,
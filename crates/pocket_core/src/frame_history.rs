@@ -0,0 +1,462 @@
+use egui::util::History;
+
+/// The most frames per second the history keeps room for.
+const MAX_FPS: f32 = 300.0;
+
+/// The name a recording is saved as, or downloaded as on the web.
+const RECORDING_FILE_NAME: &str = "frame_times.csv";
+
+/// The most frames a recording keeps: over ten minutes at [`MAX_FPS`], and almost an hour
+/// at 60 fps. Later frames are not captured.
+pub const MAX_RECORDED_FRAMES: usize = 200_000;
+
+/// Frame times at a few percentiles over the window, in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameTimes {
+    pub p50: f32,
+    pub p95: f32,
+    pub p99: f32,
+    pub max: f32,
+}
+
+impl FrameTimes {
+    /// `None` without any frames.
+    pub fn new(frame_times: impl Iterator<Item = f32>) -> Option<Self> {
+        let mut sorted: Vec<f32> = frame_times.collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f32::total_cmp);
+        // Nearest rank, so every percentile is a frame time that happened
+        let percentile = |p: f32| {
+            let rank = (p * sorted.len() as f32).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Some(Self {
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+pub struct FrameHistory {
+    frame_times: History<f32>,
+
+    /// The start time and CPU usage of every frame since recording started.
+    recording: Option<Vec<(f64, f32)>>,
+
+    /// What became of the last recording.
+    recording_status: Option<String>,
+
+    /// Where recordings are saved, see [`Self::set_recording_dir`].
+    #[cfg(not(target_arch = "wasm32"))]
+    recording_dir: Option<std::path::PathBuf>,
+}
+
+impl Default for FrameHistory {
+    fn default() -> Self {
+        Self {
+            frame_times: history(1.0),
+            recording: None,
+            recording_status: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            recording_dir: None,
+        }
+    }
+}
+
+fn history(max_age: f32) -> History<f32> {
+    let max_len = (max_age * MAX_FPS).round() as usize;
+    History::new(0..max_len, max_age)
+}
+
+impl FrameHistory {
+    // Called first
+    pub fn on_new_frame(&mut self, now: f64, previous_frame_time: Option<f32>) {
+        let previous_frame_time = previous_frame_time.unwrap_or_default();
+        if let Some(latest) = self.frame_times.latest_mut() {
+            *latest = previous_frame_time; // rewrite history now that we know
+        }
+        self.frame_times.add(now, previous_frame_time); // projected
+
+        // One more than the most frames kept, as the current one is left out when stopping
+        if let Some(recording) = self
+            .recording
+            .as_mut()
+            .filter(|recording| recording.len() <= MAX_RECORDED_FRAMES)
+        {
+            if let Some(latest) = recording.last_mut() {
+                latest.1 = previous_frame_time;
+            }
+            recording.push((now, previous_frame_time));
+        }
+    }
+
+    pub fn mean_frame_time(&self) -> f32 {
+        self.frame_times.average().unwrap_or_default()
+    }
+
+    pub fn fps(&self) -> f32 {
+        1.0 / self.frame_times.mean_time_interval().unwrap_or_default()
+    }
+
+    /// How many seconds of frames the statistics are over.
+    pub fn window(&self) -> f32 {
+        self.frame_times.max_age()
+    }
+
+    /// Keep `seconds` of frames, keeping those already seen that fit.
+    pub fn set_window(&mut self, seconds: f32) {
+        if seconds == self.window() {
+            return;
+        }
+        let mut frame_times = history(seconds);
+        for (time, frame_time) in self.frame_times.iter() {
+            frame_times.add(time, frame_time);
+        }
+        self.frame_times = frame_times;
+    }
+
+    /// The percentiles of the frame times in the window.
+    pub fn frame_times(&self) -> Option<FrameTimes> {
+        FrameTimes::new(self.frame_times.values())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Has the recording captured [`MAX_RECORDED_FRAMES`]?
+    pub fn is_recording_full(&self) -> bool {
+        self.recording
+            .as_ref()
+            .is_some_and(|recording| recording.len() > MAX_RECORDED_FRAMES)
+    }
+
+    /// Save recordings in `dir`, such as [`eframe::storage_dir`], instead of the
+    /// directory the app was started in.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_recording_dir(&mut self, dir: Option<std::path::PathBuf>) {
+        self.recording_dir = dir;
+    }
+
+    /// Capture the time of every frame from now on, until [`Self::stop_recording`],
+    /// or until [`MAX_RECORDED_FRAMES`] are.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(vec![]);
+    }
+
+    /// The frames captured since [`Self::start_recording`] as CSV, one per line.
+    ///
+    /// The current frame isn't over yet, so it's left out.
+    pub fn stop_recording(&mut self) -> String {
+        let mut recording = self.recording.take().unwrap_or_default();
+        recording.pop();
+
+        let mut csv = "frame,time_s,cpu_ms,interval_ms\n".to_owned();
+        let mut previous_time = None;
+        for (frame, (time, cpu_usage)) in recording.into_iter().enumerate() {
+            let interval = previous_time.map_or(String::new(), |previous| {
+                format!("{:.3}", 1e3 * (time - previous))
+            });
+            csv += &format!("{frame},{time:.6},{:.3},{interval}\n", 1e3 * cpu_usage);
+            previous_time = Some(time);
+        }
+        csv
+    }
+
+    /// `target_fps` is the rate we are trying to repaint at, if any.
+    pub fn ui(&mut self, ui: &mut egui::Ui, target_fps: Option<f32>) {
+        if let Some(interval) = self.frame_times.mean_time_interval() {
+            let target = target_fps.map_or(String::new(), |fps| format!(" (target {fps:.0})"));
+            ui.label(format!("Effective rate: {:.1} fps{target}", 1.0 / interval))
+                .on_hover_text("How many frames were painted per second over the window");
+        }
+        ui.label(format!(
+            "Mean CPU usage: {:.2} ms / frame",
+            1e3 * self.mean_frame_time()
+        ))
+        .on_hover_text(
+            "Includes all app logic, egui layout, tessellation, and rendering.\n\
+            Does not include waiting for vsync.",
+        );
+        if let Some(frame_times) = self.frame_times() {
+            ui.label(format!(
+                "p50 {:.2} · p95 {:.2} · p99 {:.2} · max {:.2} ms",
+                1e3 * frame_times.p50,
+                1e3 * frame_times.p95,
+                1e3 * frame_times.p99,
+                1e3 * frame_times.max,
+            ))
+            .on_hover_text("CPU usage per frame at these percentiles of the window");
+        }
+        ui.horizontal(|ui| {
+            let mut window = self.window();
+            ui.label("Window:");
+            if ui
+                .add(
+                    egui::Slider::new(&mut window, 1.0..=30.0)
+                        .suffix(" s")
+                        .step_by(1.0),
+                )
+                .on_hover_text("How many seconds of frames the statistics are over")
+                .changed()
+            {
+                self.set_window(window);
+            }
+        });
+        self.recording_ui(ui);
+        egui::warn_if_debug_build(ui);
+
+        if !cfg!(target_arch = "wasm32") {
+            egui::CollapsingHeader::new("📊 CPU usage history")
+                .default_open(false)
+                .show(ui, |ui| {
+                    self.graph(ui);
+                });
+        }
+        egui::CollapsingHeader::new("📊 Frame time histogram")
+            .default_open(false)
+            .show(ui, |ui| {
+                self.histogram(ui);
+            });
+    }
+
+    fn recording_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if self.is_recording() {
+                let frames = self.recording.as_ref().map_or(0, |recording| {
+                    recording.len().saturating_sub(1) // the current frame isn't over
+                });
+                let (label, hover) = if self.is_recording_full() {
+                    (
+                        format!("⏹ Stop ({frames} frames, full)"),
+                        format!(
+                            "Save the frame times to {RECORDING_FILE_NAME}.\n\
+                            No more than {MAX_RECORDED_FRAMES} frames are captured."
+                        ),
+                    )
+                } else {
+                    (
+                        format!("⏹ Stop ({frames} frames)"),
+                        format!("Save the frame times to {RECORDING_FILE_NAME}"),
+                    )
+                };
+                if ui.button(label).on_hover_text(hover).clicked() {
+                    let csv = self.stop_recording();
+                    self.recording_status = Some(self.save_recording(&csv));
+                }
+            } else if ui
+                .button("⏺ Record")
+                .on_hover_text("Capture the time of every frame as CSV for offline analysis")
+                .clicked()
+            {
+                self.start_recording();
+                self.recording_status = None;
+            }
+            if let Some(status) = &self.recording_status {
+                ui.weak(status);
+            }
+        });
+    }
+
+    /// Save a recording: in the recording directory on native, and as a download on the web.
+    #[cfg_attr(target_arch = "wasm32", allow(clippy::unused_self))] // Only native has a directory
+    fn save_recording(&self, csv: &str) -> String {
+        #[cfg(target_arch = "wasm32")]
+        {
+            match crate::web::download(RECORDING_FILE_NAME, "text/csv", csv.as_bytes()) {
+                Ok(()) => format!("Downloaded {RECORDING_FILE_NAME}"),
+                Err(err) => format!("Download failed: {err:?}"),
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // With no directory, the one we were started in
+            let dir = self.recording_dir.clone().unwrap_or_default();
+            let path = dir.join(RECORDING_FILE_NAME);
+            let written = std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, csv));
+            match written {
+                Ok(()) => format!("Saved {}", path.display()),
+                Err(err) => format!("Failed to save {}: {err}", path.display()),
+            }
+        }
+    }
+
+    fn graph(&self, ui: &mut egui::Ui) -> egui::Response {
+        use egui::{emath, epaint, pos2, vec2, Pos2, Rect, Sense, Shape, Stroke, TextStyle};
+
+        ui.label("egui CPU usage history");
+
+        let history = &self.frame_times;
+
+        // TODO(emilk): we should not use `slider_width` as default graph width.
+        let height = ui.spacing().slider_width;
+        let size = vec2(ui.available_size_before_wrap().x, height);
+        let (rect, response) = ui.allocate_at_least(size, Sense::hover());
+        let style = ui.style().noninteractive();
+
+        let graph_top_cpu_usage = 0.010;
+        let graph_rect = Rect::from_x_y_ranges(history.max_age()..=0.0, graph_top_cpu_usage..=0.0);
+        let to_screen = emath::RectTransform::from_to(graph_rect, rect);
+
+        let mut shapes = Vec::with_capacity(3 + 2 * history.len());
+        shapes.push(Shape::Rect(epaint::RectShape::new(
+            rect,
+            style.rounding,
+            ui.visuals().extreme_bg_color,
+            ui.style().noninteractive().bg_stroke,
+        )));
+
+        let rect = rect.shrink(4.0);
+        let color = ui.visuals().text_color();
+        let line_stroke = Stroke::new(1.0, color);
+
+        if let Some(pointer_pos) = response.hover_pos() {
+            let y = pointer_pos.y;
+            shapes.push(Shape::line_segment(
+                [pos2(rect.left(), y), pos2(rect.right(), y)],
+                line_stroke,
+            ));
+            let cpu_usage = to_screen.inverse().transform_pos(pointer_pos).y;
+            let text = format!("{:.1} ms", 1e3 * cpu_usage);
+            shapes.push(ui.fonts(|f| {
+                Shape::text(
+                    f,
+                    pos2(rect.left(), y),
+                    egui::Align2::LEFT_BOTTOM,
+                    text,
+                    TextStyle::Monospace.resolve(ui.style()),
+                    color,
+                )
+            }));
+        }
+
+        let circle_color = color;
+        let radius = 2.0;
+        let right_side_time = ui.input(|i| i.time); // Time at right side of screen
+
+        for (time, cpu_usage) in history.iter() {
+            let age = (right_side_time - time) as f32;
+            let pos = to_screen.transform_pos_clamped(Pos2::new(age, cpu_usage));
+
+            shapes.push(Shape::line_segment(
+                [pos2(pos.x, rect.bottom()), pos],
+                line_stroke,
+            ));
+
+            if cpu_usage < graph_top_cpu_usage {
+                shapes.push(Shape::circle_filled(pos, radius, circle_color));
+            }
+        }
+
+        ui.painter().extend(shapes);
+
+        response
+    }
+
+    fn histogram(&self, ui: &mut egui::Ui) -> egui::Response {
+        use egui::{emath, epaint, pos2, vec2, Rect, Sense, Shape, Stroke, TextStyle};
+
+        const BINS: usize = 40;
+
+        ui.label(format!(
+            "CPU usage per frame over the last {:.0} s",
+            self.window()
+        ));
+
+        let height = ui.spacing().slider_width;
+        let size = vec2(ui.available_size_before_wrap().x, height);
+        let (rect, response) = ui.allocate_at_least(size, Sense::hover());
+        let style = ui.style().noninteractive();
+
+        let mut shapes = vec![Shape::Rect(epaint::RectShape::new(
+            rect,
+            style.rounding,
+            ui.visuals().extreme_bg_color,
+            ui.style().noninteractive().bg_stroke,
+        ))];
+
+        let Some(frame_times) = self.frame_times() else {
+            ui.painter().extend(shapes);
+            return response;
+        };
+
+        // Whole milliseconds, and at least enough for a 50 fps frame
+        let graph_top_cpu_usage = ((1e3 * frame_times.max).ceil() / 1e3).max(0.020);
+        let bin_width = graph_top_cpu_usage / BINS as f32;
+        let mut counts = [0_usize; BINS];
+        for cpu_usage in self.frame_times.values() {
+            counts[((cpu_usage / bin_width) as usize).min(BINS - 1)] += 1;
+        }
+        let max_count = counts.iter().copied().max().unwrap_or_default().max(1);
+
+        let rect = rect.shrink(4.0);
+        let graph_rect = Rect::from_x_y_ranges(0.0..=graph_top_cpu_usage, max_count as f32..=0.0);
+        let to_screen = emath::RectTransform::from_to(graph_rect, rect);
+        let color = ui.visuals().text_color();
+
+        for (bin, &count) in counts.iter().enumerate() {
+            if count > 0 {
+                let left = bin as f32 * bin_width;
+                let bar = Rect::from_two_pos(
+                    to_screen.transform_pos(pos2(left, count as f32)),
+                    to_screen.transform_pos(pos2(left + bin_width, 0.0)),
+                );
+                shapes.push(Shape::rect_filled(bar.shrink2(vec2(0.5, 0.0)), 0.0, color));
+            }
+        }
+
+        let font_id = TextStyle::Small.resolve(ui.style());
+        let marker_color = ui.visuals().warn_fg_color;
+        for (name, cpu_usage) in [
+            ("p50", frame_times.p50),
+            ("p95", frame_times.p95),
+            ("p99", frame_times.p99),
+        ] {
+            let x = to_screen.transform_pos(pos2(cpu_usage, 0.0)).x;
+            shapes.push(Shape::line_segment(
+                [pos2(x, rect.top()), pos2(x, rect.bottom())],
+                Stroke::new(1.0, marker_color),
+            ));
+            shapes.push(ui.fonts(|f| {
+                Shape::text(
+                    f,
+                    pos2(x + 2.0, rect.top()),
+                    egui::Align2::LEFT_TOP,
+                    name,
+                    font_id.clone(),
+                    marker_color,
+                )
+            }));
+        }
+
+        if let Some(pointer_pos) = response.hover_pos() {
+            let cpu_usage = to_screen.inverse().transform_pos(pointer_pos).x;
+            let bin = ((cpu_usage / bin_width).max(0.0) as usize).min(BINS - 1);
+            let text = format!(
+                "{:.1}–{:.1} ms: {} frames",
+                1e3 * bin as f32 * bin_width,
+                1e3 * (bin + 1) as f32 * bin_width,
+                counts[bin]
+            );
+            shapes.push(ui.fonts(|f| {
+                Shape::text(
+                    f,
+                    pos2(rect.right(), rect.top()),
+                    egui::Align2::RIGHT_TOP,
+                    text,
+                    TextStyle::Monospace.resolve(ui.style()),
+                    color,
+                )
+            }));
+        }
+
+        ui.painter().extend(shapes);
+
+        response
+    }
+}
@@ -0,0 +1,25 @@
+//! The parts shared by the pocket apps: the backend panel with its frame history,
+//! the top bar, and the web boilerplate.
+
+mod backend_panel;
+#[doc(inline)]
+pub use backend_panel::BackendPanel;
+
+mod frame_history;
+pub use frame_history::{FrameHistory, FrameTimes, MAX_RECORDED_FRAMES};
+
+#[cfg(feature = "persistence")]
+pub mod persistence;
+
+mod top_bar;
+pub use top_bar::{top_bar, Command};
+
+#[cfg(target_arch = "wasm32")]
+pub mod web;
+
+/// Detect narrow screens. This is used to show a simpler UI on mobile devices,
+/// especially for the web demo at <https://egui.rs>.
+pub fn is_mobile(ctx: &egui::Context) -> bool {
+    let screen_size = ctx.screen_rect().size();
+    screen_size.x < 550.0
+}
@@ -0,0 +1,140 @@
+//! The state of an app, persisted with a schema version so it outlives changes to its shape.
+//!
+//! The state is saved as RON under a key, usually [`eframe::APP_KEY`], and its version
+//! under [`version_key`]. Parts of the app, such as its views, can keep their own state
+//! under their own keys, each with its own version.
+//! State saved by an older version is read through a chain of [`Migration`]s, one per
+//! version since. State that can't be read is backed up under [`backup_key`] instead of
+//! being lost, so it can be rescued by hand, or under [`unknown_version_backup_key`]
+//! when its version can't be read.
+
+use std::collections::BTreeMap;
+
+/// Where the version of the state under [`eframe::APP_KEY`] is saved.
+pub const VERSION_KEY: &str = "app_version";
+
+/// Where the version of the state under `key` is saved, e.g. [`VERSION_KEY`] for
+/// [`eframe::APP_KEY`].
+pub fn version_key(key: &str) -> String {
+    format!("{key}_version")
+}
+
+/// Where the state under `key` saved by `version` is backed up when it can't be read,
+/// e.g. `app_backup_v1`.
+pub fn backup_key(key: &str, version: u32) -> String {
+    format!("{key}_backup_v{version}")
+}
+
+/// Where the state under `key` is backed up when its version can't be read,
+/// e.g. `app_backup_unknown_version`.
+pub fn unknown_version_backup_key(key: &str) -> String {
+    format!("{key}_backup_unknown_version")
+}
+
+/// Turns the RON of state saved by one version into the RON of the next version.
+///
+/// Usually this reads the old shape into types kept just for it, and writes them
+/// reshaped as the next version.
+pub type Migration = fn(&str) -> Result<String, String>;
+
+/// State persisted with a schema version.
+pub trait Versioned: serde::Serialize + serde::de::DeserializeOwned {
+    /// The migrations from each older version to the next, oldest first, so
+    /// `MIGRATIONS[0]` turns state from before there were versions into version 1.
+    ///
+    /// When the shape of the state changes, add a migration to read the previous shape.
+    const MIGRATIONS: &'static [Migration];
+
+    /// The version the state is saved as.
+    fn version() -> u32 {
+        Self::MIGRATIONS.len() as u32
+    }
+
+    /// Read the RON of state saved by `version`, migrating it through every version since.
+    ///
+    /// # Errors
+    /// If `version` is newer than [`Self::version`], or the state or a migration of it
+    /// can't be read.
+    fn read(version: u32, ron: &str) -> Result<Self, String> {
+        let Some(migrations) = Self::MIGRATIONS.get(version as usize..) else {
+            return Err(format!(
+                "saved by version {version}, newer than {}",
+                Self::version()
+            ));
+        };
+        let mut ron = ron.to_owned();
+        for (from, migrate) in (version..).zip(migrations) {
+            ron = migrate(&ron).map_err(|err| format!("migrating from version {from}: {err}"))?;
+        }
+        ron::from_str(&ron).map_err(|err| err.to_string())
+    }
+}
+
+/// Loads and saves the [`Versioned`] state of an app, and of its parts.
+#[derive(Default)]
+pub struct Persistence {
+    /// The backup key and RON of state that couldn't be read, by key, to back up on the
+    /// next save.
+    unreadable: BTreeMap<String, (String, String)>,
+}
+
+impl Persistence {
+    /// The state saved in `storage` under [`eframe::APP_KEY`], migrated from older versions.
+    ///
+    /// `None` if there is none, or it can't be read. Then it is backed up on the next
+    /// [`Self::save`], before it is overwritten.
+    pub fn load<T: Versioned>(&mut self, storage: &dyn eframe::Storage) -> Option<T> {
+        self.load_key(storage, eframe::APP_KEY)
+    }
+
+    /// Save `state` under [`eframe::APP_KEY`] as the current version, after backing up
+    /// any state [`Self::load`] couldn't read.
+    pub fn save<T: Versioned>(&mut self, storage: &mut dyn eframe::Storage, state: &T) {
+        self.save_key(storage, eframe::APP_KEY, state);
+    }
+
+    /// Like [`Self::load`], for the state saved under `key`.
+    pub fn load_key<T: Versioned>(
+        &mut self,
+        storage: &dyn eframe::Storage,
+        key: &str,
+    ) -> Option<T> {
+        let ron = storage.get_string(key)?;
+        let (read, backup) = match storage.get_string(&version_key(key)) {
+            // State saved before there were versions is version 0
+            None => (T::read(0, &ron), backup_key(key, 0)),
+            Some(version) => match version.trim().parse() {
+                Ok(version) => (T::read(version, &ron), backup_key(key, version)),
+                Err(err) => (
+                    Err(format!("unreadable version {version:?}: {err}")),
+                    unknown_version_backup_key(key),
+                ),
+            },
+        };
+
+        match read {
+            Ok(state) => Some(state),
+            Err(err) => {
+                log::warn!(
+                    "Failed to read the state saved as {key:?} ({err}), backing it up as {backup:?}"
+                );
+                self.unreadable.insert(key.to_owned(), (backup, ron));
+                None
+            }
+        }
+    }
+
+    /// Like [`Self::save`], for the state saved under `key`.
+    pub fn save_key<T: Versioned>(
+        &mut self,
+        storage: &mut dyn eframe::Storage,
+        key: &str,
+        state: &T,
+    ) {
+        if let Some((backup, ron)) = self.unreadable.remove(key) {
+            storage.set_string(&backup, ron);
+        }
+        storage.set_string(&version_key(key), T::version().to_string());
+        eframe::set_value(storage, key, state);
+    }
+}
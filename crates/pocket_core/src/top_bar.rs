@@ -0,0 +1,23 @@
+//! The bar along the top of each app, and the commands given from it.
+
+/// Something for the app to do once the frame is laid out,
+/// e.g. from a button in the [`BackendPanel`](crate::BackendPanel).
+#[derive(Clone, Copy, Debug)]
+#[must_use]
+pub enum Command {
+    Nothing,
+    ResetEverything,
+}
+
+/// Show the bar along the top of the window, with frameless buttons laid out
+/// left to right, wrapping on narrow screens.
+pub fn top_bar(ctx: &egui::Context, add_contents: impl FnOnce(&mut egui::Ui)) {
+    egui::TopBottomPanel::top("wrap_app_top_bar")
+        .frame(egui::Frame::none().inner_margin(4.0))
+        .show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.visuals_mut().button_frame = false;
+                add_contents(ui);
+            });
+        });
+}
@@ -0,0 +1,111 @@
+//! What the apps need to run in a browser.
+
+use eframe::wasm_bindgen::{JsCast as _, JsValue};
+
+/// Redirect [`log`] messages to `console.log` and friends.
+pub fn init_logger() {
+    let log_level = if cfg!(debug_assertions) {
+        log::LevelFilter::Trace
+    } else {
+        log::LevelFilter::Debug
+    };
+    eframe::WebLogger::init(log_level).ok();
+}
+
+/// Let the browser download `bytes` as a file called `file_name`.
+///
+/// # Errors
+/// If the browser refuses to make the file or the link to it.
+pub fn download(file_name: &str, mime: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Define `WebHandle`, our handle to the web app from JavaScript, running `$app`.
+///
+/// `$app` is an [`eframe::App`] with a `new(&eframe::CreationContext<'_>) -> Self`.
+/// The calling crate needs `eframe` and `web-sys` as dependencies.
+#[macro_export]
+macro_rules! web_handle {
+    ($app:ty) => {
+        use eframe::wasm_bindgen::{self, prelude::*};
+
+        /// Our handle to the web app from JavaScript.
+        #[derive(Clone)]
+        #[wasm_bindgen]
+        pub struct WebHandle {
+            runner: eframe::WebRunner,
+        }
+
+        #[wasm_bindgen]
+        impl WebHandle {
+            /// Installs a panic hook, then returns.
+            #[allow(clippy::new_without_default)]
+            #[wasm_bindgen(constructor)]
+            pub fn new() -> Self {
+                $crate::web::init_logger();
+
+                Self {
+                    runner: eframe::WebRunner::new(),
+                }
+            }
+
+            /// Call this once from JavaScript to start your app.
+            #[wasm_bindgen]
+            pub async fn start(
+                &self,
+                canvas: web_sys::HtmlCanvasElement,
+            ) -> Result<(), wasm_bindgen::JsValue> {
+                self.runner
+                    .start(
+                        canvas,
+                        eframe::WebOptions::default(),
+                        Box::new(|cc| Ok(Box::new(<$app>::new(cc)))),
+                    )
+                    .await
+            }
+
+            #[wasm_bindgen]
+            pub fn destroy(&self) {
+                self.runner.destroy();
+            }
+
+            /// Example on how to call into your app from JavaScript.
+            #[wasm_bindgen]
+            pub fn example(&self) {
+                if let Some(_app) = self.runner.app_mut::<$app>() {
+                    // _app.example();
+                }
+            }
+
+            /// The JavaScript can check whether or not your app has crashed:
+            #[wasm_bindgen]
+            pub fn has_panicked(&self) -> bool {
+                self.runner.has_panicked()
+            }
+
+            #[wasm_bindgen]
+            pub fn panic_message(&self) -> Option<String> {
+                self.runner.panic_summary().map(|s| s.message())
+            }
+
+            #[wasm_bindgen]
+            pub fn panic_callstack(&self) -> Option<String> {
+                self.runner.panic_summary().map(|s| s.callstack())
+            }
+        }
+    };
+}
@@ -0,0 +1,19 @@
+//! The run mode is remembered between runs.
+#![cfg(feature = "persistence")]
+
+use pocket_core::BackendPanel;
+
+#[test]
+fn run_mode_is_persisted() {
+    let ron = ron::to_string(&BackendPanel::default()).unwrap();
+    assert!(ron.contains("run_mode:Reactive"), "{ron}");
+    assert!(ron.contains("target_fps:30"), "{ron}");
+
+    let ron = ron
+        .replace("run_mode:Reactive", "run_mode:BatterySaver")
+        .replace("target_fps:30", "target_fps:60");
+    let panel: BackendPanel = ron::from_str(&ron).unwrap();
+    let ron = ron::to_string(&panel).unwrap();
+    assert!(ron.contains("run_mode:BatterySaver"), "{ron}");
+    assert!(ron.contains("target_fps:60"), "{ron}");
+}
@@ -0,0 +1,86 @@
+//! Frame times are summarized by percentiles and can be recorded as CSV.
+
+use pocket_core::{FrameHistory, FrameTimes, MAX_RECORDED_FRAMES};
+
+#[test]
+fn percentiles_are_nearest_rank() {
+    assert_eq!(FrameTimes::new(std::iter::empty()), None);
+
+    // 1 ms to 100 ms
+    let frame_times = FrameTimes::new((1..=100).rev().map(|ms| ms as f32 / 1e3)).unwrap();
+    assert_eq!(frame_times.p50, 0.050);
+    assert_eq!(frame_times.p95, 0.095);
+    assert_eq!(frame_times.p99, 0.099);
+    assert_eq!(frame_times.max, 0.100);
+
+    let frame_times = FrameTimes::new(std::iter::once(0.004)).unwrap();
+    assert_eq!(frame_times.p50, 0.004);
+    assert_eq!(frame_times.max, 0.004);
+}
+
+#[test]
+fn the_window_keeps_recent_frames() {
+    let mut history = FrameHistory::default();
+    assert_eq!(history.window(), 1.0);
+    history.set_window(5.0);
+    assert_eq!(history.window(), 5.0);
+
+    // A stutter, then ten smooth seconds at 10 fps
+    history.on_new_frame(0.0, None);
+    history.on_new_frame(0.1, Some(0.5));
+    for frame in 2..=100 {
+        history.on_new_frame(frame as f64 / 10.0, Some(0.010));
+    }
+    let frame_times = history.frame_times().unwrap();
+    assert_eq!(frame_times.max, 0.010);
+
+    // Shrinking the window keeps the frames that still fit
+    history.set_window(1.0);
+    assert!(history.frame_times().is_some());
+}
+
+#[test]
+fn recordings_are_csv() {
+    let mut history = FrameHistory::default();
+    history.on_new_frame(0.0, None);
+    assert!(!history.is_recording());
+
+    history.start_recording();
+    assert!(history.is_recording());
+    history.on_new_frame(1.0, Some(0.002));
+    history.on_new_frame(1.016, Some(0.004));
+    history.on_new_frame(1.048, Some(0.030));
+    let csv = history.stop_recording();
+    assert!(!history.is_recording());
+
+    // The last frame isn't over when recording stops
+    assert_eq!(
+        csv,
+        "frame,time_s,cpu_ms,interval_ms\n\
+         0,1.000000,4.000,\n\
+         1,1.016000,30.000,16.000\n"
+    );
+}
+
+#[test]
+fn recordings_stop_growing_when_full() {
+    let mut history = FrameHistory::default();
+    history.start_recording();
+    // The frame after the last one kept ends it
+    for frame in 0..=MAX_RECORDED_FRAMES {
+        assert!(!history.is_recording_full(), "frame {frame}");
+        history.on_new_frame(frame as f64 / 60.0, Some(0.001));
+    }
+    assert!(history.is_recording_full());
+    for frame in 0..10 {
+        history.on_new_frame(1e4 + f64::from(frame), Some(0.5));
+    }
+
+    let csv = history.stop_recording();
+    assert_eq!(csv.lines().count(), 1 + MAX_RECORDED_FRAMES);
+    assert!(csv.ends_with(&format!(
+        "{},{:.6},1.000,16.667\n",
+        MAX_RECORDED_FRAMES - 1,
+        (MAX_RECORDED_FRAMES - 1) as f64 / 60.0
+    )));
+}
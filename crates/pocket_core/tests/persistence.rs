@@ -0,0 +1,164 @@
+//! Saved state is read through migrations from older versions, and backed up when it can't be.
+#![cfg(feature = "persistence")]
+
+use std::collections::HashMap;
+
+use pocket_core::persistence::{
+    backup_key, unknown_version_backup_key, Migration, Persistence, Versioned, VERSION_KEY,
+};
+use serde::{Deserialize, Serialize};
+
+/// Version 2: the volume became a fraction, and there is a theme.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Settings {
+    user: String,
+    volume: f32,
+    theme: Theme,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+enum Theme {
+    Light,
+    Dark,
+}
+
+/// Version 1 renamed `name` to `user`.
+#[derive(Deserialize, Serialize)]
+struct SettingsV1 {
+    user: String,
+    volume: u8,
+}
+
+/// Version 0, from before there were versions.
+#[derive(Deserialize)]
+struct SettingsV0 {
+    name: String,
+    volume: u8,
+}
+
+fn migrate<Old: serde::de::DeserializeOwned, New: Serialize>(
+    ron: &str,
+    reshape: impl FnOnce(Old) -> New,
+) -> Result<String, String> {
+    let old = ron::from_str(ron).map_err(|err| err.to_string())?;
+    ron::to_string(&reshape(old)).map_err(|err| err.to_string())
+}
+
+impl Versioned for Settings {
+    const MIGRATIONS: &'static [Migration] = &[
+        |ron| {
+            migrate(ron, |old: SettingsV0| SettingsV1 {
+                user: old.name,
+                volume: old.volume,
+            })
+        },
+        |ron| {
+            migrate(ron, |old: SettingsV1| Self {
+                user: old.user,
+                volume: f32::from(old.volume) / 10.0,
+                theme: Theme::Dark,
+            })
+        },
+    ];
+}
+
+fn ada() -> Settings {
+    Settings {
+        user: "Ada".to_owned(),
+        volume: 0.7,
+        theme: Theme::Dark,
+    }
+}
+
+#[derive(Default)]
+struct Storage(HashMap<String, String>);
+
+impl Storage {
+    fn with(entries: &[(&str, &str)]) -> Self {
+        Self(
+            entries
+                .iter()
+                .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                .collect(),
+        )
+    }
+}
+
+impl eframe::Storage for Storage {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_owned(), value);
+    }
+
+    fn flush(&mut self) {}
+}
+
+#[test]
+fn old_versions_are_migrated() {
+    assert_eq!(Settings::version(), 2);
+
+    let v0 = include_str!("fixtures/settings_v0.ron");
+    let v1 = include_str!("fixtures/settings_v1.ron");
+    assert_eq!(Settings::read(0, v0), Ok(ada()));
+    assert_eq!(Settings::read(1, v1), Ok(ada()));
+    assert_eq!(
+        Settings::read(2, "(user:\"Ada\",volume:0.7,theme:Dark)"),
+        Ok(ada())
+    );
+
+    // Without a version, the state is from before there were versions
+    let storage = Storage::with(&[(eframe::APP_KEY, v0)]);
+    let settings: Option<Settings> = Persistence::default().load(&storage);
+    assert_eq!(settings, Some(ada()));
+}
+
+#[test]
+fn the_version_is_saved() {
+    let mut storage = Storage::default();
+    let mut persistence = Persistence::default();
+    persistence.save(&mut storage, &ada());
+    assert_eq!(storage.0[VERSION_KEY], "2");
+
+    let settings: Option<Settings> = persistence.load(&storage);
+    assert_eq!(settings, Some(ada()));
+}
+
+#[test]
+fn unreadable_state_is_backed_up() {
+    for (version, ron) in [
+        ("1", "(name:\"Ada\",volume:7)"),
+        ("2", "not ron at all"),
+        ("3", "(user:\"Ada\",volume:0.7,theme:Dark,font:Big)"),
+    ] {
+        let mut storage = Storage::with(&[(eframe::APP_KEY, ron), (VERSION_KEY, version)]);
+        let mut persistence = Persistence::default();
+        let settings: Option<Settings> = persistence.load(&storage);
+        assert_eq!(settings, None, "version {version}: {ron}");
+
+        // Kept until the state is saved over it
+        persistence.save(&mut storage, &ada());
+        let version = version.parse().unwrap();
+        assert_eq!(storage.0[&backup_key(eframe::APP_KEY, version)], ron);
+        assert_eq!(persistence.load(&storage), Some(ada()));
+    }
+}
+
+#[test]
+fn state_of_an_unreadable_version_is_backed_up() {
+    let v1 = include_str!("fixtures/settings_v1.ron");
+    for version in ["", "two", "-1"] {
+        let mut storage = Storage::with(&[(eframe::APP_KEY, v1), (VERSION_KEY, version)]);
+        let mut persistence = Persistence::default();
+        // Not mistaken for state from before there were versions
+        let settings: Option<Settings> = persistence.load(&storage);
+        assert_eq!(settings, None, "version {version:?}");
+
+        persistence.save(&mut storage, &ada());
+        assert_eq!(storage.0[&unknown_version_backup_key(eframe::APP_KEY)], v1);
+        assert!(!storage.0.contains_key(&backup_key(eframe::APP_KEY, 0)));
+        assert_eq!(persistence.load(&storage), Some(ada()));
+    }
+}
@@ -55,4 +55,4 @@ fn links(ui: &mut egui::Ui) {
         "https://github.com/ktmeaton/pocket",
     );
     ui.hyperlink_to("pocket documentation", "https://docs.rs/pocket");
-}
\ No newline at end of file
+}
@@ -5,9 +5,17 @@ use egui::ImageFit;
 use egui::Slider;
 use egui::Vec2;
 
+/// The image shown until another is picked.
+const DEFAULT_URI: &str = "https://picsum.photos/seed/1.759706314/1024";
+
+/// Dropped images are only kept in memory, under URIs starting with this.
+const DROPPED_URI: &str = "bytes://";
+
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ImageViewer {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_uri"))]
     current_uri: String,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_uri"))]
     uri_edit_text: String,
     image_options: egui::ImageOptions,
     chosen_fit: ChosenFit,
@@ -37,8 +45,8 @@ impl ChosenFit {
 impl Default for ImageViewer {
     fn default() -> Self {
         Self {
-            current_uri: "https://picsum.photos/seed/1.759706314/1024".to_owned(),
-            uri_edit_text: "https://picsum.photos/seed/1.759706314/1024".to_owned(),
+            current_uri: DEFAULT_URI.to_owned(),
+            uri_edit_text: DEFAULT_URI.to_owned(),
             image_options: egui::ImageOptions::default(),
             chosen_fit: ChosenFit::Fraction,
             fit: ImageFit::Fraction(Vec2::splat(1.0)),
@@ -48,6 +56,17 @@ impl Default for ImageViewer {
     }
 }
 
+/// A saved URI, or the default one for a dropped image, which is gone after a restart.
+#[cfg(feature = "serde")]
+fn deserialize_uri<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let uri = <String as serde::Deserialize>::deserialize(deserializer)?;
+    if uri.starts_with(DROPPED_URI) {
+        Ok(DEFAULT_URI.to_owned())
+    } else {
+        Ok(uri)
+    }
+}
+
 impl eframe::App for ImageViewer {
     fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
         egui::TopBottomPanel::new(TopBottomSide::Top, "url bar").show(ctx, |ui| {
@@ -217,3 +236,20 @@ impl eframe::App for ImageViewer {
         });
     }
 }
+
+impl crate::file_handler::FileHandler for ImageViewer {
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["image/png", "image/jpeg", "image/gif", "image/svg+xml"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["png", "jpg", "jpeg", "gif", "svg"]
+    }
+
+    fn open_file(&mut self, ctx: &egui::Context, name: &str, bytes: std::sync::Arc<[u8]>) {
+        ctx.forget_image(&self.current_uri);
+        self.current_uri = format!("{DROPPED_URI}{name}");
+        self.uri_edit_text = self.current_uri.clone();
+        ctx.include_bytes(self.current_uri.clone(), bytes);
+    }
+}
@@ -4,6 +4,7 @@ mod custom3d_glow;
 #[cfg(feature = "wgpu")]
 mod custom3d_wgpu;
 
+mod easy_mark_editor;
 mod fractal_clock;
 
 #[cfg(feature = "http")]
@@ -21,6 +22,7 @@ pub use custom3d_glow::Custom3d;
 #[cfg(feature = "wgpu")]
 pub use custom3d_wgpu::Custom3d;
 
+pub use easy_mark_editor::EasyMarkEditor;
 pub use fractal_clock::FractalClock;
 
 #[cfg(feature = "http")]
@@ -0,0 +1,711 @@
+//! The "Chart" window: plot the numbers in a table, and brush a region to filter it.
+
+use egui::{pos2, vec2, Align2, Pos2, Rect, Sense, Shape, Stroke, TextStyle, Ui};
+
+use super::table::Table;
+use super::table_window::{format_number, RangeFilter};
+use super::Datasets;
+
+/// Line and scatter charts draw at most this many points, evenly spread over the rows.
+const MAX_POINTS: usize = 20_000;
+
+/// How close the pointer must be to a point to show its row.
+pub const HOVER_DISTANCE: f32 = 8.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ChartKind {
+    Histogram,
+    BoxPlot,
+    /// The values of a column by row number.
+    Line,
+    /// The values of two columns against each other.
+    Scatter,
+}
+
+impl ChartKind {
+    pub const ALL: [Self; 4] = [Self::Histogram, Self::BoxPlot, Self::Line, Self::Scatter];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Histogram => "Histogram",
+            Self::BoxPlot => "Box plot",
+            Self::Line => "Line",
+            Self::Scatter => "Scatter",
+        }
+    }
+}
+
+/// What a chart is of; when it changes, the chart is computed again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Settings {
+    pub table: String,
+    pub kind: ChartKind,
+    /// The column plotted, or plotted across in a scatter.
+    pub x: String,
+    /// The column plotted up in a scatter.
+    pub y: String,
+    pub bins: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            table: String::new(),
+            kind: ChartKind::Histogram,
+            x: String::new(),
+            y: String::new(),
+            bins: 20,
+        }
+    }
+}
+
+/// Plot one or two numeric columns of a table.
+///
+/// Dragging over the chart shows only the rows in the brushed region in the table's window;
+/// double-clicking shows all of them again.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ChartWindow {
+    pub open: bool,
+    settings: Settings,
+    /// The chart of these settings.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    chart: Option<(Settings, Chart)>,
+    /// The brushed region, in plot coordinates.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    brush: Option<[[f64; 2]; 2]>,
+    /// Where the brush is being dragged from, in plot coordinates.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    drag_start: Option<[f64; 2]>,
+}
+
+impl ChartWindow {
+    pub(super) fn show(&mut self, ctx: &egui::Context, datasets: &mut Datasets) {
+        let mut open = self.open;
+        egui::Window::new("📊 Chart")
+            .open(&mut open)
+            .default_size([480.0, 360.0])
+            .resizable(true)
+            .show(ctx, |ui| self.ui(ui, datasets));
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut Ui, datasets: &mut Datasets) {
+        self.settings_ui(ui, datasets);
+
+        let settings = self.settings.clone();
+        if self.chart.as_ref().is_some_and(|(of, _)| *of != settings) {
+            // The brush was of the old chart
+            if let (Some((of, _)), Some(_)) = (self.chart.take(), self.brush.take()) {
+                if let Some(window) = datasets.window_mut(&of.table) {
+                    window.set_ranges(vec![]);
+                }
+            }
+        }
+
+        // Forget the brush if the table's window was told to show all rows
+        let ranges_cleared = datasets
+            .window_mut(&settings.table)
+            .map_or(true, |window| window.ranges().is_empty());
+        if self.drag_start.is_none() && ranges_cleared {
+            self.brush = None;
+        }
+
+        let Some(table) = datasets.table(&settings.table) else {
+            ui.weak("Pick a table to plot.");
+            return;
+        };
+        let numeric = |name: &str| {
+            table
+                .columns()
+                .iter()
+                .position(|column| column.name == name && column.data_type().is_numeric())
+        };
+        let (Some(x), y) = (numeric(&settings.x), numeric(&settings.y)) else {
+            ui.weak("This table has no numeric column to plot.");
+            return;
+        };
+        if settings.kind == ChartKind::Scatter && y.is_none() {
+            ui.weak("Pick a column to plot up.");
+            return;
+        }
+        let (_, chart) = self.chart.get_or_insert_with(|| {
+            let chart = Chart::new(table, &settings, x, y.unwrap_or(x));
+            (settings.clone(), chart)
+        });
+
+        ui.weak("Drag to show only those rows in the table, double-click to show all");
+        let brushed = plot_ui(ui, table, chart, &mut self.brush, &mut self.drag_start);
+
+        if brushed {
+            let ranges = self
+                .brush
+                .map_or_else(Vec::new, |brush| chart.ranges(x, y, brush));
+            if let Some(window) = datasets.window_mut(&settings.table) {
+                window.set_ranges(ranges);
+            }
+            datasets.open(&settings.table);
+        }
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui, datasets: &mut Datasets) {
+        let settings = &mut self.settings;
+        egui::Grid::new("chart_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Table:");
+                let mut picked = None;
+                egui::ComboBox::from_id_salt("chart_table")
+                    .selected_text(&settings.table)
+                    .width(200.0)
+                    .show_ui(ui, |ui| {
+                        for name in datasets.names() {
+                            if ui.selectable_label(settings.table == name, name).clicked() {
+                                picked = Some(name.to_owned());
+                            }
+                        }
+                    });
+                if let Some(name) = picked {
+                    settings.table = datasets.load(&name).unwrap_or(name);
+                }
+                ui.end_row();
+
+                ui.label("Chart:");
+                ui.horizontal(|ui| {
+                    for kind in ChartKind::ALL {
+                        ui.selectable_value(&mut settings.kind, kind, kind.name());
+                    }
+                });
+                ui.end_row();
+
+                // The tables are loaded when picked, but not after a restart
+                if !settings.table.is_empty() && datasets.table(&settings.table).is_none() {
+                    datasets.load(&settings.table);
+                }
+                let Some(table) = datasets.table(&settings.table) else {
+                    return;
+                };
+                let numeric: Vec<&str> = table
+                    .columns()
+                    .iter()
+                    .filter(|column| column.data_type().is_numeric())
+                    .map(|column| column.name.as_str())
+                    .collect();
+                // Default to the first numeric columns
+                for (column, default) in [(&mut settings.x, 0), (&mut settings.y, 1)] {
+                    if !numeric.contains(&column.as_str()) {
+                        if let Some(name) = numeric.get(default).or(numeric.first()) {
+                            (*name).clone_into(column);
+                        }
+                    }
+                }
+
+                if settings.kind == ChartKind::Scatter {
+                    ui.label("Across:");
+                    column_combo(ui, "chart_x", &numeric, &mut settings.x);
+                    ui.end_row();
+                    ui.label("Up:");
+                    column_combo(ui, "chart_y", &numeric, &mut settings.y);
+                } else {
+                    ui.label("Column:");
+                    column_combo(ui, "chart_x", &numeric, &mut settings.x);
+                }
+                ui.end_row();
+
+                if settings.kind == ChartKind::Histogram {
+                    ui.label("Bins:");
+                    ui.add(egui::Slider::new(&mut settings.bins, 2..=100));
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn column_combo(ui: &mut Ui, id: &str, names: &[&str], selected: &mut String) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected.as_str())
+        .show_ui(ui, |ui| {
+            for &name in names {
+                if ui.selectable_label(selected == name, name).clicked() {
+                    name.clone_into(selected);
+                }
+            }
+        });
+}
+
+// ----------------------------------------------------------------------------
+
+pub enum Plot {
+    Histogram {
+        /// The lower end of the first bin.
+        min: f64,
+        width: f64,
+        counts: Vec<usize>,
+    },
+    Box {
+        /// The lower whisker, the quartiles and the upper whisker.
+        stats: [f64; 5],
+        /// The rows beyond the whiskers, and their values.
+        outliers: Vec<(usize, f64)>,
+    },
+    /// Rows and where they are plotted.
+    Points {
+        points: Vec<(usize, [f64; 2])>,
+        lines: bool,
+    },
+}
+
+/// What is drawn of a table, computed once for its [`Settings`].
+pub struct Chart {
+    pub plot: Plot,
+    /// The plotted region, lower then upper corner.
+    pub bounds: [[f64; 2]; 2],
+    x_label: String,
+    y_label: String,
+}
+
+impl Chart {
+    /// A chart of column `x`, and of `y` in a scatter.
+    pub fn new(table: &Table, settings: &Settings, x: usize, y: usize) -> Self {
+        let column = |index: usize| {
+            let data = &table.columns()[index].data;
+            (0..table.num_rows())
+                .filter_map(|row| Some((row, data.number(row).filter(|n| n.is_finite())?)))
+        };
+        let x_name = table.columns()[x].name.clone();
+        let y_name = table.columns()[y].name.clone();
+        let stride = table.num_rows().div_ceil(MAX_POINTS).max(1);
+
+        let (plot, x_label, y_label) = match settings.kind {
+            ChartKind::Histogram => {
+                let values: Vec<f64> = column(x).map(|(_, value)| value).collect();
+                let (min, max) = min_max(values.iter().copied()).unwrap_or((0.0, 1.0));
+                let bins = settings.bins.max(1);
+                let width = if max > min { max - min } else { 1.0 } / bins as f64;
+                let mut counts = vec![0; bins];
+                for value in values {
+                    let bin = ((value - min) / width) as usize;
+                    counts[bin.min(bins - 1)] += 1;
+                }
+                let plot = Plot::Histogram { min, width, counts };
+                (plot, x_name, "rows".to_owned())
+            }
+            ChartKind::BoxPlot => {
+                let mut values: Vec<(usize, f64)> = column(x).collect();
+                values.sort_by(|a, b| a.1.total_cmp(&b.1));
+                let sorted: Vec<f64> = values.iter().map(|&(_, value)| value).collect();
+                let (q1, median, q3) = (
+                    quantile(&sorted, 0.25),
+                    quantile(&sorted, 0.5),
+                    quantile(&sorted, 0.75),
+                );
+                let reach = 1.5 * (q3 - q1);
+                let inside = |value: &&f64| (q1 - reach..=q3 + reach).contains(*value);
+                let lower = sorted.iter().find(inside).copied().unwrap_or(q1);
+                let upper = sorted.iter().rev().find(inside).copied().unwrap_or(q3);
+                let mut outliers: Vec<(usize, f64)> = values
+                    .into_iter()
+                    .filter(|&(_, value)| value < lower || value > upper)
+                    .collect();
+                let outlier_stride = outliers.len().div_ceil(MAX_POINTS).max(1);
+                if outlier_stride > 1 {
+                    outliers = outliers.into_iter().step_by(outlier_stride).collect();
+                }
+                let plot = Plot::Box {
+                    stats: [lower, q1, median, q3, upper],
+                    outliers,
+                };
+                (plot, String::new(), x_name)
+            }
+            ChartKind::Line => {
+                let points = column(x)
+                    .filter(|(row, _)| row % stride == 0)
+                    .map(|(row, value)| (row, [(row + 1) as f64, value]))
+                    .collect();
+                let plot = Plot::Points {
+                    points,
+                    lines: true,
+                };
+                (plot, "#".to_owned(), x_name)
+            }
+            ChartKind::Scatter => {
+                let y_data = &table.columns()[y].data;
+                let points = column(x)
+                    .filter(|(row, _)| row % stride == 0)
+                    .filter_map(|(row, across)| {
+                        let up = y_data.number(row).filter(|n| n.is_finite())?;
+                        Some((row, [across, up]))
+                    })
+                    .collect();
+                let plot = Plot::Points {
+                    points,
+                    lines: false,
+                };
+                (plot, x_name, y_name)
+            }
+        };
+
+        let bounds = match &plot {
+            Plot::Histogram { min, width, counts } => {
+                let highest = counts.iter().copied().max().unwrap_or_default().max(1);
+                [
+                    [*min, 0.0],
+                    [min + width * counts.len() as f64, highest as f64 * 1.05],
+                ]
+            }
+            Plot::Box { stats, outliers } => {
+                let values = stats.iter().copied().chain(outliers.iter().map(|o| o.1));
+                let (low, high) = pad(min_max(values));
+                [[0.0, low], [1.0, high]]
+            }
+            Plot::Points { points, .. } => {
+                let (x_low, x_high) = pad(min_max(points.iter().map(|p| p.1[0])));
+                let (y_low, y_high) = pad(min_max(points.iter().map(|p| p.1[1])));
+                [[x_low, y_low], [x_high, y_high]]
+            }
+        };
+
+        Self {
+            plot,
+            bounds,
+            x_label,
+            y_label,
+        }
+    }
+
+    /// The rows in the brushed region, between two corners in plot coordinates.
+    pub fn ranges(&self, x: usize, y: Option<usize>, brush: [[f64; 2]; 2]) -> Vec<RangeFilter> {
+        let range = |axis: usize| {
+            let (a, b) = (brush[0][axis], brush[1][axis]);
+            a.min(b)..=a.max(b)
+        };
+        match &self.plot {
+            Plot::Histogram { .. } => vec![RangeFilter {
+                column: Some(x),
+                range: range(0),
+            }],
+            Plot::Box { .. } => vec![RangeFilter {
+                column: Some(x),
+                range: range(1),
+            }],
+            Plot::Points { lines: true, .. } => vec![RangeFilter {
+                column: None,
+                range: range(0),
+            }],
+            Plot::Points { lines: false, .. } => vec![
+                RangeFilter {
+                    column: Some(x),
+                    range: range(0),
+                },
+                RangeFilter {
+                    column: y,
+                    range: range(1),
+                },
+            ],
+        }
+    }
+
+    /// Which axes a brush spans: histograms and lines across, box plots up.
+    fn brushes(&self) -> [bool; 2] {
+        match self.plot {
+            Plot::Histogram { .. } | Plot::Points { lines: true, .. } => [true, false],
+            Plot::Box { .. } => [false, true],
+            Plot::Points { lines: false, .. } => [true, true],
+        }
+    }
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values.fold(None, |range, value| match range {
+        None => Some((value, value)),
+        Some((min, max)) => Some((min.min(value), max.max(value))),
+    })
+}
+
+/// A range a little wider than `range`, so that nothing is drawn on the edges.
+fn pad(range: Option<(f64, f64)>) -> (f64, f64) {
+    match range {
+        None => (0.0, 1.0),
+        Some((min, max)) if min == max => (min - 1.0, max + 1.0),
+        Some((min, max)) => {
+            let margin = 0.05 * (max - min);
+            (min - margin, max + margin)
+        }
+    }
+}
+
+/// The `q` quantile of `sorted` values, interpolating between them.
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let position = q * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let above = (below + 1).min(sorted.len() - 1);
+    let fraction = position - below as f64;
+    sorted[below] + fraction * (sorted[above] - sorted[below])
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where plot coordinates go on the screen, and back.
+struct Transform {
+    bounds: [[f64; 2]; 2],
+    rect: Rect,
+}
+
+impl Transform {
+    fn to_screen(&self, [x, y]: [f64; 2]) -> Pos2 {
+        let [[x0, y0], [x1, y1]] = self.bounds;
+        pos2(
+            self.rect.left() + ((x - x0) / (x1 - x0)) as f32 * self.rect.width(),
+            self.rect.bottom() - ((y - y0) / (y1 - y0)) as f32 * self.rect.height(),
+        )
+    }
+
+    fn to_plot(&self, pos: Pos2) -> [f64; 2] {
+        let [[x0, y0], [x1, y1]] = self.bounds;
+        let pos = self.rect.clamp(pos);
+        [
+            x0 + f64::from((pos.x - self.rect.left()) / self.rect.width()) * (x1 - x0),
+            y0 + f64::from((self.rect.bottom() - pos.y) / self.rect.height()) * (y1 - y0),
+        ]
+    }
+}
+
+/// Draw `chart`, and let the user brush a region of it.
+///
+/// Returns whether a brush is done, or cleared.
+fn plot_ui(
+    ui: &mut Ui,
+    table: &Table,
+    chart: &Chart,
+    brush: &mut Option<[[f64; 2]; 2]>,
+    drag_start: &mut Option<[f64; 2]>,
+) -> bool {
+    let size = vec2(ui.available_width(), ui.available_height().max(200.0));
+    let (response, painter) = ui.allocate_painter(size, Sense::click_and_drag());
+    let font = TextStyle::Small.resolve(ui.style());
+    let text_color = ui.visuals().text_color();
+    let weak_color = ui.visuals().weak_text_color();
+    let fill = ui.visuals().selection.bg_fill;
+    let stroke = Stroke::new(1.0, text_color);
+
+    // Room for the tick labels
+    let rect = Rect::from_min_max(
+        response.rect.min + vec2(56.0, 4.0),
+        response.rect.max - vec2(8.0, 32.0),
+    );
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let transform = Transform {
+        bounds: chart.bounds,
+        rect,
+    };
+    let [[x0, y0], [x1, y1]] = chart.bounds;
+
+    // Ticks
+    for i in 0..=4 {
+        let t = f64::from(i) / 4.0;
+        let (x, y) = (x0 + t * (x1 - x0), y0 + t * (y1 - y0));
+        if !matches!(chart.plot, Plot::Box { .. }) {
+            let pos = transform.to_screen([x, y0]);
+            painter.line_segment([pos, pos + vec2(0.0, 4.0)], stroke);
+            painter.text(
+                pos + vec2(0.0, 4.0),
+                Align2::CENTER_TOP,
+                format_number(x),
+                font.clone(),
+                text_color,
+            );
+        }
+        let pos = transform.to_screen([x0, y]);
+        painter.line_segment([pos, pos - vec2(4.0, 0.0)], stroke);
+        painter.text(
+            pos - vec2(6.0, 0.0),
+            Align2::RIGHT_CENTER,
+            format_number(y),
+            font.clone(),
+            text_color,
+        );
+    }
+    painter.text(
+        pos2(rect.center().x, response.rect.bottom()),
+        Align2::CENTER_BOTTOM,
+        &chart.x_label,
+        font.clone(),
+        weak_color,
+    );
+    painter.text(
+        response.rect.left_top(),
+        Align2::LEFT_TOP,
+        &chart.y_label,
+        font,
+        weak_color,
+    );
+
+    // Brushing
+    let [brush_x, brush_y] = chart.brushes();
+    let mut brushed = false;
+    if let Some(pos) = response.interact_pointer_pos() {
+        if response.drag_started() {
+            *drag_start = Some(transform.to_plot(pos));
+        }
+        if let Some(start) = *drag_start {
+            *brush = Some([start, transform.to_plot(pos)]);
+        }
+    }
+    if response.drag_stopped() {
+        if drag_start.take().is_some() {
+            brushed = true;
+        }
+    } else if response.double_clicked() {
+        *brush = None;
+        brushed = true;
+    }
+    if let Some([start, end]) = *brush {
+        let (start, end) = (transform.to_screen(start), transform.to_screen(end));
+        let region = Rect::from_two_pos(
+            pos2(
+                if brush_x { start.x } else { rect.left() },
+                if brush_y { start.y } else { rect.top() },
+            ),
+            pos2(
+                if brush_x { end.x } else { rect.right() },
+                if brush_y { end.y } else { rect.bottom() },
+            ),
+        );
+        painter.rect_filled(region, 0.0, fill.gamma_multiply(0.3));
+    }
+
+    let hover = response.hover_pos().filter(|pos| rect.contains(*pos));
+    let mut hovered_row = None;
+    let mut hover_text = None;
+    match &chart.plot {
+        Plot::Histogram { min, width, counts } => {
+            for (bin, &count) in counts.iter().enumerate() {
+                let low = min + width * bin as f64;
+                let bar = Rect::from_two_pos(
+                    transform.to_screen([low, 0.0]),
+                    transform.to_screen([low + width, count as f64]),
+                );
+                let is_hovered =
+                    hover.is_some_and(|pos| (bar.left()..bar.right()).contains(&pos.x));
+                let color = if is_hovered {
+                    ui.visuals().widgets.hovered.fg_stroke.color
+                } else {
+                    fill
+                };
+                painter.rect(
+                    bar,
+                    0.0,
+                    color,
+                    Stroke::new(1.0, text_color.gamma_multiply(0.5)),
+                );
+                if is_hovered {
+                    hover_text = Some(format!(
+                        "{} to {}: {count} rows",
+                        format_number(low),
+                        format_number(low + width)
+                    ));
+                }
+            }
+        }
+        Plot::Box { stats, outliers } => {
+            let [lower, q1, median, q3, upper] = *stats;
+            let left = transform.to_screen([0.3, q3]);
+            let right = transform.to_screen([0.7, q1]);
+            let middle = transform.to_screen([0.5, median]).x;
+            let y = |value: f64| transform.to_screen([0.5, value]).y;
+            painter.rect(Rect::from_two_pos(left, right), 0.0, fill, stroke);
+            painter.line_segment(
+                [pos2(left.x, y(median)), pos2(right.x, y(median))],
+                Stroke::new(2.0, text_color),
+            );
+            for (from, to) in [(q3, upper), (q1, lower)] {
+                painter.line_segment([pos2(middle, y(from)), pos2(middle, y(to))], stroke);
+                painter.line_segment(
+                    [pos2(middle - 12.0, y(to)), pos2(middle + 12.0, y(to))],
+                    stroke,
+                );
+            }
+            let points: Vec<(usize, Pos2)> = outliers
+                .iter()
+                .map(|&(row, value)| (row, pos2(middle, y(value))))
+                .collect();
+            painter.extend(
+                points
+                    .iter()
+                    .map(|&(_, pos)| Shape::circle_stroke(pos, 2.5, stroke)),
+            );
+            hovered_row = hover.and_then(|pos| nearest(&points, pos));
+            if hovered_row.is_none() && hover.is_some_and(|pos| (left.x..=right.x).contains(&pos.x))
+            {
+                hover_text = Some(format!(
+                    "Upper whisker: {}\nThird quartile: {}\nMedian: {}\nFirst quartile: {}\nLower whisker: {}",
+                    format_number(upper),
+                    format_number(q3),
+                    format_number(median),
+                    format_number(q1),
+                    format_number(lower)
+                ));
+            }
+        }
+        Plot::Points { points, lines } => {
+            let points: Vec<(usize, Pos2)> = points
+                .iter()
+                .map(|&(row, point)| (row, transform.to_screen(point)))
+                .collect();
+            if *lines {
+                let line = points.iter().map(|&(_, pos)| pos).collect();
+                painter.add(Shape::line(line, Stroke::new(1.5, fill)));
+            } else {
+                let color = fill.gamma_multiply(0.8);
+                painter.extend(
+                    points
+                        .iter()
+                        .map(|&(_, pos)| Shape::circle_filled(pos, 2.0, color)),
+                );
+            }
+            hovered_row = hover.and_then(|pos| nearest(&points, pos));
+            if let Some((_, pos)) = hovered_row.and_then(|row| points.iter().find(|p| p.0 == row)) {
+                painter.circle_stroke(*pos, 4.0, Stroke::new(2.0, text_color));
+            }
+        }
+    }
+
+    if drag_start.is_none() {
+        if let Some(row) = hovered_row {
+            response.on_hover_ui_at_pointer(|ui| row_ui(ui, table, row));
+        } else if let Some(text) = hover_text {
+            response.on_hover_text_at_pointer(text);
+        }
+    }
+
+    brushed
+}
+
+/// The row of the point closest to `pos`, if close enough to hover.
+pub fn nearest(points: &[(usize, Pos2)], pos: Pos2) -> Option<usize> {
+    points
+        .iter()
+        .map(|&(row, point)| (row, point.distance_sq(pos)))
+        .filter(|&(_, distance)| distance < HOVER_DISTANCE * HOVER_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(row, _)| row)
+}
+
+/// Every value in `row`.
+fn row_ui(ui: &mut Ui, table: &Table, row: usize) {
+    ui.strong(format!("Row {}", row + 1));
+    egui::Grid::new("chart_row").num_columns(2).show(ui, |ui| {
+        for column in table.columns() {
+            ui.weak(&column.name);
+            ui.label(column.data.get(row).to_string());
+            ui.end_row();
+        }
+    });
+}
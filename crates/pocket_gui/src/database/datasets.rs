@@ -0,0 +1,142 @@
+//! The tables that come with the app.
+
+use chrono::{Days, NaiveDate};
+
+use super::import::{Encoding, Format, Options, Records};
+use super::table::{Column, ColumnData, Table};
+
+/// Where the rows of a [`Dataset`] come from.
+#[derive(Clone, Copy, Debug)]
+pub enum Source {
+    /// Tab-separated text, with a header row.
+    Tsv(&'static str),
+    /// Made-up rows, for trying out large tables.
+    Generated { rows: usize },
+}
+
+/// Which list a dataset is shown in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Category {
+    /// Real data.
+    Database,
+    /// Synthetic data, e.g. for testing performance.
+    Test,
+    /// Read from a file by the user.
+    Imported,
+    /// The result of a query.
+    Result,
+}
+
+/// A table that can be opened from the menu.
+#[derive(Clone, Copy, Debug)]
+pub struct Dataset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub category: Category,
+    pub source: Source,
+}
+
+impl Dataset {
+    pub fn load(&self) -> Table {
+        match self.source {
+            Source::Tsv(text) => parse_tsv(self.name, text),
+            Source::Generated { rows } => generate(self.name, rows),
+        }
+    }
+}
+
+pub const DATASETS: [Dataset; 4] = [
+    Dataset {
+        name: "Samples",
+        description: "Sequenced samples, with where and when they were collected",
+        category: Category::Database,
+        source: Source::Tsv(include_str!("../../data/samples.tsv")),
+    },
+    Dataset {
+        name: "Generated (10k rows)",
+        description: "Ten thousand rows of random values",
+        category: Category::Test,
+        source: Source::Generated { rows: 10_000 },
+    },
+    Dataset {
+        name: "Generated (100k rows)",
+        description: "A hundred thousand rows of random values",
+        category: Category::Test,
+        source: Source::Generated { rows: 100_000 },
+    },
+    Dataset {
+        name: "Generated (1M rows)",
+        description:
+            "A million rows of random values, to check that scrolling and sorting stay smooth",
+        category: Category::Test,
+        source: Source::Generated { rows: 1_000_000 },
+    },
+];
+
+/// Tab-separated text, the first line being the header.
+fn parse_tsv(name: &str, text: &str) -> Table {
+    let options = Options {
+        format: Format::Delimited('\t'),
+        has_header: true,
+        encoding: Encoding::Utf8,
+    };
+    match Records::parse(text.as_bytes(), &options) {
+        Ok(records) => records.to_table(name, &records.infer_types()),
+        Err(err) => {
+            log::warn!("Failed to read {name:?}: {err}");
+            Table::new(name, vec![])
+        }
+    }
+}
+
+/// A table of `rows` random rows, one column of each type, with some missing values.
+///
+/// The values are built already typed, as inferring the types of a million
+/// rows of text would only measure the parser.
+fn generate(name: &str, rows: usize) -> Table {
+    const GROUPS: [&str; 5] = ["alpha", "beta", "gamma", "delta", "epsilon"];
+    let epoch = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default();
+
+    // SplitMix64, so every run gets the same table
+    let mut state = rows as u64;
+    let mut next = move || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+
+    let mut id = Vec::with_capacity(rows);
+    let mut group = Vec::with_capacity(rows);
+    let mut count = Vec::with_capacity(rows);
+    let mut value = Vec::with_capacity(rows);
+    let mut date = Vec::with_capacity(rows);
+    let mut flag = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let random = next();
+        // One value in 50 is missing
+        let missing = random % 50 == 0;
+        id.push(Some(row as i64 + 1));
+        group.push(Some(
+            GROUPS[(random >> 8) as usize % GROUPS.len()].to_owned(),
+        ));
+        count.push((!missing).then_some((random >> 16) as i64 % 1000));
+        value.push(Some((random >> 11) as f64 / (1_u64 << 53) as f64 * 100.0));
+        date.push(epoch.checked_add_days(Days::new((random >> 32) % 9000)));
+        flag.push((!missing).then_some(random & 1 == 1));
+    }
+
+    Table::new(
+        name,
+        vec![
+            Column::new("id", ColumnData::Integer(id)),
+            Column::new("group", ColumnData::String(group)),
+            Column::new("count", ColumnData::Integer(count)),
+            Column::new("value", ColumnData::Float(value)),
+            Column::new("date", ColumnData::Date(date)),
+            Column::new("flag", ColumnData::Boolean(flag)),
+        ],
+    )
+}
@@ -0,0 +1,321 @@
+//! Writing the rows of a [`Table`] to files.
+//!
+//! Every writer takes the rows to write in order, so that exports keep the sort and filter
+//! of the window they come from.
+
+use super::table::{Table, Value};
+
+/// A file format a [`Table`] can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Tsv,
+    Json,
+    JsonLines,
+    /// Apache Parquet, which keeps the type of every column.
+    Parquet,
+}
+
+impl Format {
+    pub const ALL: &'static [Self] = &[
+        Self::Csv,
+        Self::Tsv,
+        Self::Json,
+        Self::JsonLines,
+        #[cfg(feature = "parquet")]
+        Self::Parquet,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Tsv => "TSV",
+            Self::Json => "JSON",
+            Self::JsonLines => "JSON Lines",
+            Self::Parquet => "Parquet",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Json => "json",
+            Self::JsonLines => "jsonl",
+            Self::Parquet => "parquet",
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Tsv => "text/tab-separated-values",
+            Self::Json => "application/json",
+            Self::JsonLines => "application/jsonl",
+            Self::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+/// Why a table could not be exported.
+#[derive(Debug)]
+pub enum ExportError {
+    /// This build can't write the format, as it was built without its feature.
+    Unsupported(Format),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(format) => write!(f, "This build can't write {}", format.name()),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => write!(f, "Failed to write the Parquet file: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for ExportError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        Self::Parquet(err)
+    }
+}
+
+/// `rows` of `table`, in this order, as a file in `format`.
+pub fn export(table: &Table, rows: &[usize], format: Format) -> Result<Vec<u8>, ExportError> {
+    Ok(match format {
+        Format::Csv => to_csv(table, rows).into_bytes(),
+        Format::Tsv => to_tsv(table, rows).into_bytes(),
+        Format::Json => to_json(table, rows).into_bytes(),
+        Format::JsonLines => to_json_lines(table, rows).into_bytes(),
+        Format::Parquet => to_parquet(table, rows)?,
+    })
+}
+
+/// A file name for `table` exported to `format`, e.g. `Generated_10k_rows.csv`.
+pub fn file_name(table: &Table, format: Format) -> String {
+    let stem: String = table
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let stem = stem.trim_matches('_');
+    let stem = if stem.is_empty() { "table" } else { stem };
+    format!("{stem}.{}", format.extension())
+}
+
+/// Comma-separated values, quoted where needed as in RFC 4180.
+pub fn to_csv(table: &Table, rows: &[usize]) -> String {
+    fn quote(text: &str) -> String {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_owned()
+        }
+    }
+    delimited(table, rows, ",", |text| quote(&text), "\r\n")
+}
+
+/// Tab-separated values.
+///
+/// Tabs and newlines in values become spaces, so every row stays on one line.
+pub fn to_tsv(table: &Table, rows: &[usize]) -> String {
+    let clean = |text: String| text.replace(['\t', '\n', '\r'], " ");
+    delimited(table, rows, "\t", clean, "\n")
+}
+
+fn delimited(
+    table: &Table,
+    rows: &[usize],
+    separator: &str,
+    escape: impl Fn(String) -> String,
+    newline: &str,
+) -> String {
+    let mut text = String::new();
+    let names: Vec<String> = table
+        .columns()
+        .iter()
+        .map(|column| escape(column.name.clone()))
+        .collect();
+    text += &names.join(separator);
+    text += newline;
+    for &row in rows {
+        let values: Vec<String> = table
+            .columns()
+            .iter()
+            .map(|column| escape(column.data.get(row).to_string()))
+            .collect();
+        text += &values.join(separator);
+        text += newline;
+    }
+    text
+}
+
+/// An array of objects, one per row, keyed by column name.
+pub fn to_json(table: &Table, rows: &[usize]) -> String {
+    let objects = rows.iter().map(|&row| json_row(table, row)).collect();
+    let mut text =
+        serde_json::to_string_pretty(&serde_json::Value::Array(objects)).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// One object per line, keyed by column name.
+pub fn to_json_lines(table: &Table, rows: &[usize]) -> String {
+    let mut text = String::new();
+    for &row in rows {
+        text += &json_row(table, row).to_string();
+        text.push('\n');
+    }
+    text
+}
+
+/// Numbers and booleans stay JSON numbers and booleans, dates become `YYYY-MM-DD` text,
+/// and missing values (and floats JSON cannot hold) `null`.
+fn json_row(table: &Table, row: usize) -> serde_json::Value {
+    let object = table
+        .columns()
+        .iter()
+        .map(|column| {
+            let value = match column.data.get(row) {
+                Value::Null => serde_json::Value::Null,
+                Value::String(value) => value.into(),
+                Value::Integer(value) => value.into(),
+                Value::Float(value) => value.into(),
+                Value::Date(value) => value.to_string().into(),
+                Value::Boolean(value) => value.into(),
+            };
+            (column.name.clone(), value)
+        })
+        .collect();
+    serde_json::Value::Object(object)
+}
+
+// ----------------------------------------------------------------------------
+// Parquet
+
+/// A Parquet file of one row group.
+///
+/// Every column is optional, so that missing values stay missing:
+/// strings are UTF-8 byte arrays, integers 64-bit, floats doubles,
+/// dates days since 1970 and booleans booleans.
+#[cfg(feature = "parquet")]
+pub fn to_parquet(table: &Table, rows: &[usize]) -> Result<Vec<u8>, ExportError> {
+    use std::sync::Arc;
+
+    use chrono::NaiveDate;
+    use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+    use parquet::data_type::{
+        BoolType, ByteArray, ByteArrayType, DoubleType, Int32Type, Int64Type,
+    };
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::types::Type;
+
+    use super::table::ColumnData;
+
+    let fields = table
+        .columns()
+        .iter()
+        .map(|column| {
+            let (physical_type, logical_type) = match column.data {
+                ColumnData::String(_) => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                ColumnData::Integer(_) => (PhysicalType::INT64, None),
+                ColumnData::Float(_) => (PhysicalType::DOUBLE, None),
+                ColumnData::Date(_) => (PhysicalType::INT32, Some(LogicalType::Date)),
+                ColumnData::Boolean(_) => (PhysicalType::BOOLEAN, None),
+            };
+            Type::primitive_type_builder(&column.name, physical_type)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical_type)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<_, _>>()?;
+    let schema = Type::group_type_builder("schema")
+        .with_fields(fields)
+        .build()?;
+    let properties = WriterProperties::builder()
+        .set_created_by(concat!("pocket_gui ", env!("CARGO_PKG_VERSION")).to_owned())
+        .build();
+    let mut writer = SerializedFileWriter::new(vec![], Arc::new(schema), Arc::new(properties))?;
+
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+    let mut row_group = writer.next_row_group()?;
+    for column in table.columns() {
+        let Some(mut writer) = row_group.next_column()? else {
+            break;
+        };
+        match &column.data {
+            ColumnData::String(values) => {
+                let (values, levels) =
+                    defined(values, rows, |value| ByteArray::from(value.as_str()));
+                writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            ColumnData::Integer(values) => {
+                let (values, levels) = defined(values, rows, |&value| value);
+                writer
+                    .typed::<Int64Type>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            ColumnData::Float(values) => {
+                let (values, levels) = defined(values, rows, |&value| value);
+                writer
+                    .typed::<DoubleType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            ColumnData::Date(values) => {
+                let (values, levels) =
+                    defined(values, rows, |&value| (value - epoch).num_days() as i32);
+                writer
+                    .typed::<Int32Type>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            ColumnData::Boolean(values) => {
+                let (values, levels) = defined(values, rows, |&value| value);
+                writer
+                    .typed::<BoolType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+        }
+        writer.close()?;
+    }
+    row_group.close()?;
+    Ok(writer.into_inner()?)
+}
+
+/// Without the parquet feature, no Parquet file can be written.
+#[cfg(not(feature = "parquet"))]
+pub fn to_parquet(_table: &Table, _rows: &[usize]) -> Result<Vec<u8>, ExportError> {
+    Err(ExportError::Unsupported(Format::Parquet))
+}
+
+/// The values of `rows` that are there, converted by `convert`, and the definition level
+/// of every row: 1 where there is a value, 0 where it is missing.
+#[cfg(feature = "parquet")]
+fn defined<T, U>(
+    values: &[Option<T>],
+    rows: &[usize],
+    convert: impl Fn(&T) -> U,
+) -> (Vec<U>, Vec<i16>) {
+    let mut defined = vec![];
+    let levels = rows
+        .iter()
+        .map(|&row| {
+            if let Some(value) = &values[row] {
+                defined.push(convert(value));
+                1
+            } else {
+                0
+            }
+        })
+        .collect();
+    (defined, levels)
+}
@@ -0,0 +1,329 @@
+//! Reading CSV, TSV and JSON Lines files into rows of text.
+
+use super::infer::{infer_type, parse_column};
+use super::table::{Column, DataType, Table};
+
+/// How the rows of a file are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One row per line, cells separated by a delimiter, e.g. CSV or TSV.
+    ///
+    /// Cells may be quoted with `"`, and then contain delimiters, newlines
+    /// and doubled `""` quotes.
+    Delimited(char),
+    /// One JSON object per line; its keys are the columns.
+    JsonLines,
+}
+
+impl Format {
+    /// The usual delimiters, with their names.
+    pub const DELIMITERS: [(char, &'static str); 4] = [
+        (',', "Comma"),
+        ('\t', "Tab"),
+        (';', "Semicolon"),
+        ('|', "Pipe"),
+    ];
+
+    /// Guess from the file name, and if that does not tell, from the first line.
+    pub fn sniff(file_name: &str, text: &str) -> Self {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("jsonl" | "ndjson") => return Self::JsonLines,
+            Some("tsv" | "tab") => return Self::Delimited('\t'),
+            _ => {}
+        }
+        let first_line = text.lines().next().unwrap_or_default();
+        if first_line.trim_start().starts_with('{') {
+            return Self::JsonLines;
+        }
+        // The delimiter used most on the first line, preferring commas
+        let delimiter = Self::DELIMITERS
+            .iter()
+            .map(|&(delimiter, _)| delimiter)
+            .max_by_key(|&delimiter| {
+                (
+                    first_line.matches(delimiter).count(),
+                    std::cmp::Reverse(delimiter != ','),
+                )
+            })
+            .unwrap_or(',');
+        Self::Delimited(delimiter)
+    }
+}
+
+/// How the bytes of a file are turned into text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1, where every byte is a character.
+    Latin1,
+}
+
+impl Encoding {
+    pub const ALL: [Self; 4] = [Self::Utf8, Self::Utf16Le, Self::Utf16Be, Self::Latin1];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16 LE",
+            Self::Utf16Be => "UTF-16 BE",
+            Self::Latin1 => "Latin-1",
+        }
+    }
+
+    /// Guess from the byte order mark, or else whether the bytes are valid UTF-8.
+    pub fn sniff(bytes: &[u8]) -> Self {
+        match bytes {
+            [0xFF, 0xFE, ..] => Self::Utf16Le,
+            [0xFE, 0xFF, ..] => Self::Utf16Be,
+            _ if std::str::from_utf8(bytes).is_ok() => Self::Utf8,
+            _ => Self::Latin1,
+        }
+    }
+
+    /// The text of `bytes`, without byte order mark, replacing invalid sequences.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        let utf16 = |to_u16: fn([u8; 2]) -> u16| {
+            let units = bytes.chunks_exact(2).map(|pair| to_u16([pair[0], pair[1]]));
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect::<String>()
+        };
+        let text = match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Utf16Le => utf16(u16::from_le_bytes),
+            Self::Utf16Be => utf16(u16::from_be_bytes),
+            Self::Latin1 => bytes.iter().map(|&byte| char::from(byte)).collect(),
+        };
+        match text.strip_prefix('\u{FEFF}') {
+            Some(text) => text.to_owned(),
+            None => text,
+        }
+    }
+}
+
+/// How to read a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub format: Format,
+    /// Is the first row the column names? Ignored for JSON Lines.
+    pub has_header: bool,
+    pub encoding: Encoding,
+}
+
+impl Options {
+    /// The most likely options for a file.
+    pub fn sniff(file_name: &str, bytes: &[u8]) -> Self {
+        let encoding = Encoding::sniff(bytes);
+        let text = encoding.decode(&bytes[..bytes.len().min(4096)]);
+        Self {
+            format: Format::sniff(file_name, &text),
+            has_header: true,
+            encoding,
+        }
+    }
+}
+
+/// Why a file could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImportError {
+    Empty,
+    /// A quoted cell that starts on this line (from 1) is never closed.
+    UnterminatedQuote {
+        line: usize,
+    },
+    /// This line (from 1) is not a JSON object.
+    Json {
+        line: usize,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "There are no rows"),
+            Self::UnterminatedQuote { line } => {
+                write!(f, "Line {line}: the quoted cell is never closed")
+            }
+            Self::Json { line, message } => write!(f, "Line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// The column names and rows of text of a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Records {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Records {
+    /// Read `bytes` as described by `options`.
+    ///
+    /// # Errors
+    /// If there are no rows, or they are malformed.
+    pub fn parse(bytes: &[u8], options: &Options) -> Result<Self, ImportError> {
+        let text = options.encoding.decode(bytes);
+        let mut records = match options.format {
+            Format::Delimited(delimiter) => {
+                let mut rows = parse_delimited(&text, delimiter)?;
+                let header = if options.has_header && !rows.is_empty() {
+                    rows.remove(0)
+                } else {
+                    vec![]
+                };
+                Self { header, rows }
+            }
+            Format::JsonLines => parse_json_lines(&text)?,
+        };
+        if records.header.is_empty() && records.rows.is_empty() {
+            return Err(ImportError::Empty);
+        }
+
+        // Name the columns that have no name
+        let width = records
+            .rows
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(records.header.len());
+        for i in 0..width {
+            match records.header.get_mut(i) {
+                Some(name) if name.trim().is_empty() => *name = format!("column_{}", i + 1),
+                Some(name) => *name = name.trim().to_owned(),
+                None => records.header.push(format!("column_{}", i + 1)),
+            }
+        }
+        Ok(records)
+    }
+
+    /// The values of column `i`, with missing cells as empty text.
+    fn column(&self, i: usize) -> Vec<&str> {
+        self.rows
+            .iter()
+            .map(|row| row.get(i).map_or("", String::as_str))
+            .collect()
+    }
+
+    /// The inferred type of every column.
+    pub fn infer_types(&self) -> Vec<DataType> {
+        (0..self.header.len())
+            .map(|i| infer_type(&self.column(i)))
+            .collect()
+    }
+
+    /// A table with the given column types; values that do not parse are missing.
+    pub fn to_table(&self, name: impl Into<String>, types: &[DataType]) -> Table {
+        let columns = self
+            .header
+            .iter()
+            .zip(types)
+            .enumerate()
+            .map(|(i, (name, &data_type))| {
+                Column::new(name.clone(), parse_column(&self.column(i), data_type))
+            })
+            .collect();
+        Table::new(name, columns)
+    }
+}
+
+/// Split delimited text into rows of cells, following RFC 4180 quoting.
+fn parse_delimited(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, ImportError> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    // The line the open quote is on
+    let mut quoted_since = None;
+
+    while let Some(c) = chars.next() {
+        if quoted_since.is_some() {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => quoted_since = None,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    cell.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if cell.is_empty() => quoted_since = Some(line),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                row.push(std::mem::take(&mut cell));
+                // Skip blank lines
+                if row.len() > 1 || !row[0].is_empty() {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    if let Some(line) = quoted_since {
+        return Err(ImportError::UnterminatedQuote { line });
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// One JSON object per line: the keys, in order of first appearance, are the header.
+///
+/// Nested arrays and objects are kept as JSON text.
+fn parse_json_lines(text: &str) -> Result<Records, ImportError> {
+    let mut records = Records::default();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let error = |message: String| ImportError::Json {
+            line: i + 1,
+            message,
+        };
+        let object = match serde_json::from_str(line) {
+            Ok(serde_json::Value::Object(object)) => object,
+            Ok(_) => return Err(error("expected an object".to_owned())),
+            Err(err) => return Err(error(err.to_string())),
+        };
+
+        let mut row = vec![String::new(); records.header.len()];
+        for (key, value) in object {
+            let column = if let Some(column) = records.header.iter().position(|name| *name == key) {
+                column
+            } else {
+                records.header.push(key);
+                row.push(String::new());
+                records.header.len() - 1
+            };
+            row[column] = match value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(text) => text,
+                value => value.to_string(),
+            };
+        }
+        records.rows.push(row);
+    }
+    Ok(records)
+}
@@ -0,0 +1,365 @@
+//! Getting files into the database: from a path or URL, a file dialog or a drop,
+//! then through a preview where the user checks how the file is read.
+
+#[cfg(feature = "http")]
+use poll_promise::Promise;
+
+use egui::{Context, Ui};
+
+use super::import::{Encoding, Format, ImportError, Options, Records};
+use super::table::{DataType, Table};
+
+/// The file extensions that can be imported.
+pub const EXTENSIONS: [&str; 6] = ["csv", "tsv", "tab", "txt", "jsonl", "ndjson"];
+
+/// The number of rows shown in the preview.
+const PREVIEW_ROWS: usize = 20;
+
+/// The "Import" window.
+#[derive(Default)]
+pub struct ImportWindow {
+    pub open: bool,
+    /// A URL, or on native also a path.
+    location: String,
+    #[cfg(feature = "http")]
+    download: Option<Promise<Result<(String, Vec<u8>), String>>>,
+    preview: Option<Preview>,
+    error: Option<String>,
+}
+
+impl ImportWindow {
+    /// Preview the file `file_name` with these contents.
+    pub fn load(&mut self, file_name: &str, bytes: Vec<u8>) {
+        self.preview = Some(Preview::new(file_name, bytes));
+        self.error = None;
+        self.open = true;
+    }
+
+    /// Show the window, returning the table once the user has confirmed the import.
+    pub fn show(&mut self, ctx: &Context) -> Option<Table> {
+        let mut open = self.open;
+        let table = egui::Window::new("📥 Import")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| self.ui(ui))
+            .and_then(|response| response.inner)
+            .flatten();
+        self.open = open && table.is_none();
+        table
+    }
+
+    fn ui(&mut self, ui: &mut Ui) -> Option<Table> {
+        if let Some(preview) = &mut self.preview {
+            return match preview.ui(ui) {
+                Some(true) => {
+                    let table = preview.table();
+                    self.preview = None;
+                    table
+                }
+                Some(false) => {
+                    self.preview = None;
+                    None
+                }
+                None => None,
+            };
+        }
+
+        self.source_ui(ui);
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        None
+    }
+
+    /// Where to get a file from.
+    fn source_ui(&mut self, ui: &mut Ui) {
+        ui.label(format!(
+            "Import a table from a {} file.",
+            EXTENSIONS
+                .map(|extension| format!(".{extension}"))
+                .join(", ")
+        ));
+
+        #[cfg(all(feature = "rfd", not(target_arch = "wasm32")))]
+        if ui.button("📂 Open file…").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Tables", &EXTENSIONS)
+                .pick_file()
+            {
+                self.read_path(&path);
+            }
+        }
+
+        let hint = if cfg!(target_arch = "wasm32") {
+            "https://…"
+        } else {
+            "Path or https://…"
+        };
+        let submit = ui
+            .horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.location)
+                        .hint_text(hint)
+                        .desired_width(ui.available_width() - 60.0),
+                );
+                let entered =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.add_enabled(!self.location.trim().is_empty(), egui::Button::new("Load"))
+                    .clicked()
+                    || entered
+            })
+            .inner;
+        if submit && !self.location.trim().is_empty() {
+            self.open_location(ui.ctx());
+        }
+
+        #[cfg(feature = "http")]
+        if let Some(download) = self.download.take() {
+            match download.try_take() {
+                Ok(Ok((url, bytes))) => {
+                    let file_name = url.rsplit('/').next().unwrap_or(&url).to_owned();
+                    self.load(&file_name, bytes);
+                }
+                Ok(Err(err)) => self.error = Some(err),
+                Err(download) => {
+                    self.download = Some(download);
+                    ui.spinner();
+                }
+            }
+        }
+
+        ui.weak("Or drop a file anywhere in the window.");
+    }
+
+    fn open_location(&mut self, ctx: &Context) {
+        let location = self.location.trim().to_owned();
+        self.error = None;
+        if location.starts_with("http://") || location.starts_with("https://") {
+            self.fetch(ctx, &location);
+            return;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.read_path(std::path::Path::new(&location));
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = ctx;
+            self.error = Some("Only http:// and https:// URLs can be loaded".to_owned());
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_path(&mut self, path: &std::path::Path) {
+        let file_name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        match std::fs::read(path) {
+            Ok(bytes) => self.load(&file_name, bytes),
+            Err(err) => self.error = Some(format!("Failed to read {}: {err}", path.display())),
+        }
+    }
+
+    #[cfg(feature = "http")]
+    fn fetch(&mut self, ctx: &Context, url: &str) {
+        let ctx = ctx.clone();
+        let (sender, promise) = Promise::new();
+        ehttp::fetch(ehttp::Request::get(url), move |response| {
+            ctx.request_repaint(); // wake up UI thread
+            sender.send(match response {
+                Ok(response) if response.ok => Ok((response.url, response.bytes)),
+                Ok(response) => Err(format!(
+                    "{}: {} {}",
+                    response.url, response.status, response.status_text
+                )),
+                Err(err) => Err(err),
+            });
+        });
+        self.download = Some(promise);
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn fetch(&mut self, _ctx: &Context, _url: &str) {
+        self.error = Some("Loading URLs needs the \"http\" feature".to_owned());
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A file about to be imported, and how to read it.
+struct Preview {
+    /// The name of the table.
+    name: String,
+    bytes: Vec<u8>,
+    options: Options,
+    records: Result<Records, ImportError>,
+    /// The type of each column, inferred until the user picks another.
+    types: Vec<DataType>,
+}
+
+impl Preview {
+    fn new(file_name: &str, bytes: Vec<u8>) -> Self {
+        let name = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem)
+            .to_owned();
+        let options = Options::sniff(file_name, &bytes);
+        let mut preview = Self {
+            name,
+            bytes,
+            options,
+            records: Err(ImportError::Empty),
+            types: vec![],
+        };
+        preview.parse();
+        preview
+    }
+
+    fn parse(&mut self) {
+        self.records = Records::parse(&self.bytes, &self.options);
+        self.types = self
+            .records
+            .as_ref()
+            .map(Records::infer_types)
+            .unwrap_or_default();
+    }
+
+    fn table(&self) -> Option<Table> {
+        let records = self.records.as_ref().ok()?;
+        Some(records.to_table(self.name.trim(), &self.types))
+    }
+
+    /// `Some(true)` to import, `Some(false)` to cancel.
+    fn ui(&mut self, ui: &mut Ui) -> Option<bool> {
+        let before = self.options;
+        egui::Grid::new("import_options")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Table name:");
+                ui.text_edit_singleline(&mut self.name);
+                ui.end_row();
+
+                ui.label("Format:");
+                format_ui(ui, &mut self.options.format);
+                ui.end_row();
+
+                ui.label("Header row:");
+                ui.add_enabled(
+                    matches!(self.options.format, Format::Delimited(_)),
+                    egui::Checkbox::new(&mut self.options.has_header, "First row is column names"),
+                );
+                ui.end_row();
+
+                ui.label("Encoding:");
+                egui::ComboBox::from_id_salt("import_encoding")
+                    .selected_text(self.options.encoding.name())
+                    .show_ui(ui, |ui| {
+                        for encoding in Encoding::ALL {
+                            ui.selectable_value(
+                                &mut self.options.encoding,
+                                encoding,
+                                encoding.name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+            });
+        if self.options != before {
+            self.parse();
+        }
+
+        ui.separator();
+        match &self.records {
+            Ok(records) => {
+                ui.label(format!(
+                    "{} rows × {} columns. Check the column types:",
+                    records.rows.len(),
+                    records.header.len()
+                ));
+                egui::ScrollArea::both()
+                    .max_height(320.0)
+                    .show(ui, |ui| records_ui(ui, records, &mut self.types));
+            }
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            let ready = self.records.is_ok() && !self.name.trim().is_empty();
+            if ui
+                .add_enabled(ready, egui::Button::new("✔ Import"))
+                .clicked()
+            {
+                Some(true)
+            } else if ui.button("Cancel").clicked() {
+                Some(false)
+            } else {
+                None
+            }
+        })
+        .inner
+    }
+}
+
+fn format_ui(ui: &mut Ui, format: &mut Format) {
+    let selected = match format {
+        Format::Delimited(delimiter) => Format::DELIMITERS
+            .iter()
+            .find(|(d, _)| d == delimiter)
+            .map_or_else(
+                || format!("{delimiter:?} separated"),
+                |(_, name)| format!("{name} separated"),
+            ),
+        Format::JsonLines => "JSON Lines".to_owned(),
+    };
+    egui::ComboBox::from_id_salt("import_format")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for (delimiter, name) in Format::DELIMITERS {
+                ui.selectable_value(
+                    format,
+                    Format::Delimited(delimiter),
+                    format!("{name} separated"),
+                );
+            }
+            ui.selectable_value(format, Format::JsonLines, "JSON Lines");
+        });
+}
+
+/// The first rows, with a type picker above each column.
+fn records_ui(ui: &mut Ui, records: &Records, types: &mut [DataType]) {
+    egui::Grid::new("import_preview")
+        .striped(true)
+        .show(ui, |ui| {
+            for (i, (name, data_type)) in records.header.iter().zip(types.iter_mut()).enumerate() {
+                ui.vertical(|ui| {
+                    ui.strong(name);
+                    egui::ComboBox::from_id_salt(("import_type", i))
+                        .selected_text(data_type.name())
+                        .width(80.0)
+                        .show_ui(ui, |ui| {
+                            for t in DataType::ALL {
+                                ui.selectable_value(data_type, t, t.name());
+                            }
+                        });
+                });
+            }
+            ui.end_row();
+
+            for row in records.rows.iter().take(PREVIEW_ROWS) {
+                for i in 0..records.header.len() {
+                    ui.label(row.get(i).map_or("", String::as_str));
+                }
+                ui.end_row();
+            }
+        });
+    if records.rows.len() > PREVIEW_ROWS {
+        ui.weak(format!(
+            "… and {} more rows",
+            records.rows.len() - PREVIEW_ROWS
+        ));
+    }
+}
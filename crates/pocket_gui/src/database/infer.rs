@@ -0,0 +1,80 @@
+//! Guess the type of columns of text, e.g. from a CSV file.
+
+use chrono::NaiveDate;
+
+use super::table::{ColumnData, DataType};
+
+/// Text that stands for a missing value.
+const NULLS: [&str; 7] = ["", "NA", "N/A", "NaN", "null", "NULL", "None"];
+
+/// Date formats that are recognized, tried in order.
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"];
+
+fn is_null(text: &str) -> bool {
+    NULLS.contains(&text)
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_ascii_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+/// The narrowest type that every non-missing value parses as.
+///
+/// Booleans are tried first, then integers, floats and dates; anything else
+/// is a string. A column with no values at all is a string column.
+pub fn infer_type(values: &[&str]) -> DataType {
+    let present = || {
+        values
+            .iter()
+            .copied()
+            .map(str::trim)
+            .filter(|v| !is_null(v))
+    };
+    if present().next().is_none() {
+        DataType::String
+    } else if present().all(|v| parse_bool(v).is_some()) {
+        DataType::Boolean
+    } else if present().all(|v| v.parse::<i64>().is_ok()) {
+        DataType::Integer
+    } else if present().all(|v| v.parse::<f64>().is_ok()) {
+        DataType::Float
+    } else if present().all(|v| parse_date(v).is_some()) {
+        DataType::Date
+    } else {
+        DataType::String
+    }
+}
+
+/// Parse `values` as `data_type`, with missing and unparsable values as `None`.
+pub fn parse_column(values: &[&str], data_type: DataType) -> ColumnData {
+    fn parse<T>(values: &[&str], f: impl Fn(&str) -> Option<T>) -> Vec<Option<T>> {
+        values
+            .iter()
+            .map(|v| {
+                let v = v.trim();
+                if is_null(v) {
+                    None
+                } else {
+                    f(v)
+                }
+            })
+            .collect()
+    }
+    match data_type {
+        DataType::String => ColumnData::String(parse(values, |v| Some(v.to_owned()))),
+        DataType::Integer => ColumnData::Integer(parse(values, |v| v.parse().ok())),
+        DataType::Float => ColumnData::Float(parse(values, |v| v.parse().ok())),
+        DataType::Date => ColumnData::Date(parse(values, parse_date)),
+        DataType::Boolean => ColumnData::Boolean(parse(values, parse_bool)),
+    }
+}
@@ -0,0 +1,582 @@
+//! Browse tables of data, each in its own window.
+
+pub mod chart_window;
+mod datasets;
+pub mod export;
+pub mod import;
+mod import_window;
+pub mod infer;
+pub mod query;
+mod query_window;
+#[cfg(feature = "persistence")]
+pub mod storage;
+pub mod table;
+pub mod table_window;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use egui::{Context, Modifiers, NumExt as _, ScrollArea, Ui};
+use egui_demo_lib::{Demo, View};
+use pocket_core::is_mobile;
+
+use crate::about::About;
+
+use chart_window::ChartWindow;
+use datasets::{Category, Dataset, DATASETS};
+use import_window::ImportWindow;
+pub use import_window::EXTENSIONS;
+use query_window::QueryWindow;
+#[cfg(feature = "persistence")]
+use storage::{Event, Storage};
+use table::Table;
+use table_window::TableWindow;
+
+// ----------------------------------------------------------------------------
+
+/// The tables that can be opened, and the windows of those that are.
+///
+/// Imported tables and query results are stored, and loaded again when next opened.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+struct Datasets {
+    /// Loaded when first opened, and kept for when they are opened again.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    windows: BTreeMap<String, TableWindow>,
+    /// The tables imported or queried, in the order they were added.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    added: Vec<(Category, String)>,
+    /// Saved queries by name.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    queries: BTreeMap<String, String>,
+    open: BTreeSet<String>,
+
+    #[cfg(feature = "persistence")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    storage: Option<Storage>,
+    /// The stored tables being loaded.
+    #[cfg(feature = "persistence")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    loading: BTreeSet<String>,
+    /// The last thing that went wrong with the storage.
+    #[cfg(feature = "persistence")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    storage_error: Option<String>,
+}
+
+impl Datasets {
+    pub fn checkboxes(&mut self, ui: &mut Ui, category: Category) {
+        for dataset in DATASETS.iter().filter(|d| d.category == category) {
+            let mut is_open = self.open.contains(dataset.name);
+            ui.toggle_value(&mut is_open, dataset.name)
+                .on_hover_text(dataset.description);
+            set_open(&mut self.open, dataset.name, is_open);
+        }
+        let mut remove = None;
+        for (_, name) in self.added.iter().filter(|(c, _)| *c == category) {
+            let mut is_open = self.open.contains(name);
+            ui.toggle_value(&mut is_open, name).context_menu(|ui| {
+                if ui.button("🗑 Delete").clicked() {
+                    remove = Some(name.clone());
+                    ui.close_menu();
+                }
+            });
+            set_open(&mut self.open, name, is_open);
+        }
+        if let Some(name) = remove {
+            self.remove(&name);
+        }
+    }
+
+    /// Are there any tables of `category`?
+    pub fn has(&self, category: Category) -> bool {
+        DATASETS.iter().any(|d| d.category == category)
+            || self.added.iter().any(|(c, _)| *c == category)
+    }
+
+    /// The names of all the tables, loaded or not.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        DATASETS
+            .iter()
+            .map(|dataset| dataset.name)
+            .chain(self.added.iter().map(|(_, name)| name.as_str()))
+    }
+
+    /// Add a table and open it, renaming it if the name is taken.
+    pub fn add(&mut self, mut table: Table, category: Category) {
+        let base = table.name.clone();
+        let mut n = 1;
+        while self.names().any(|name| name == table.name) {
+            n += 1;
+            table.name = format!("{base} ({n})");
+        }
+        let name = table.name.clone();
+        log::debug!("Added {name:?}");
+        #[cfg(feature = "persistence")]
+        if let Some(storage) = &mut self.storage {
+            storage.save_table(&table, category);
+        }
+        self.windows.insert(name.clone(), TableWindow::new(table));
+        self.added.push((category, name.clone()));
+        self.open.insert(name);
+    }
+
+    /// Forget an imported table or query result, and delete it from storage.
+    pub fn remove(&mut self, name: &str) {
+        log::debug!("Removed {name:?}");
+        self.windows.remove(name);
+        self.added.retain(|(_, added)| added != name);
+        self.open.remove(name);
+        #[cfg(feature = "persistence")]
+        if let Some(storage) = &mut self.storage {
+            storage.remove_table(name);
+        }
+    }
+
+    pub fn queries(&self) -> &BTreeMap<String, String> {
+        &self.queries
+    }
+
+    /// Save `text` as the query called `name`, replacing any of that name.
+    pub fn save_query(&mut self, name: &str, text: &str) {
+        self.queries.insert(name.to_owned(), text.to_owned());
+        #[cfg(feature = "persistence")]
+        if let Some(storage) = &mut self.storage {
+            storage.save_query(name, text);
+        }
+    }
+
+    pub fn remove_query(&mut self, name: &str) {
+        self.queries.remove(name);
+        #[cfg(feature = "persistence")]
+        if let Some(storage) = &mut self.storage {
+            storage.remove_query(name);
+        }
+    }
+
+    /// Load the table called `name`, ignoring ASCII case, and return its exact name.
+    pub fn load(&mut self, name: &str) -> Option<String> {
+        let found = self
+            .names()
+            .find(|other| *other == name)
+            .or_else(|| self.names().find(|other| other.eq_ignore_ascii_case(name)))?
+            .to_owned();
+        if let Some(dataset) = DATASETS.iter().find(|dataset| dataset.name == found) {
+            window(&mut self.windows, dataset);
+        } else {
+            self.load_stored(&found);
+        }
+        Some(found)
+    }
+
+    /// Start loading the stored table called `name`, unless it is loaded or loading.
+    ///
+    /// On native it is loaded right away, on the web in a moment.
+    #[cfg(feature = "persistence")]
+    fn load_stored(&mut self, name: &str) {
+        if self.windows.contains_key(name) || self.loading.contains(name) {
+            return;
+        }
+        if let Some(storage) = &mut self.storage {
+            self.loading.insert(name.to_owned());
+            storage.load(name);
+            self.handle_events();
+        }
+    }
+
+    #[cfg(not(feature = "persistence"))]
+    #[allow(clippy::unused_self)]
+    fn load_stored(&mut self, _name: &str) {}
+
+    /// Is the table called `name` being loaded?
+    #[cfg(feature = "persistence")]
+    pub fn is_loading(&self, name: &str) -> bool {
+        self.loading.contains(name)
+    }
+
+    #[cfg(not(feature = "persistence"))]
+    #[allow(clippy::unused_self)]
+    pub fn is_loading(&self, _name: &str) -> bool {
+        false
+    }
+
+    /// Open the storage the first time, and take in what it is done with.
+    #[cfg(feature = "persistence")]
+    pub fn update(&mut self, ctx: &Context) {
+        if self.storage.is_none() {
+            self.storage = Some(Storage::open(ctx));
+        }
+        self.handle_events();
+    }
+
+    #[cfg(not(feature = "persistence"))]
+    #[allow(clippy::unused_self)]
+    pub fn update(&mut self, _ctx: &Context) {}
+
+    #[cfg(feature = "persistence")]
+    fn handle_events(&mut self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let events: Vec<Event> = storage.events().collect();
+        for event in events {
+            match event {
+                Event::Listed { tables, queries } => {
+                    for (category, name) in tables {
+                        if !self.names().any(|other| other == name) {
+                            self.added.push((category, name));
+                        }
+                    }
+                    for (name, text) in queries {
+                        self.queries.entry(name).or_insert(text);
+                    }
+                }
+                Event::Loaded(table) => {
+                    self.loading.remove(&table.name);
+                    log::debug!("Loaded {:?} from storage", table.name);
+                    self.windows
+                        .insert(table.name.clone(), TableWindow::new(table));
+                }
+                Event::NotLoaded { name, error } => {
+                    // Not to try again every frame
+                    self.loading.remove(&name);
+                    self.added.retain(|(_, added)| *added != name);
+                    self.storage_error = Some(error);
+                }
+                Event::Failed(error) => {
+                    self.storage_error = Some(error);
+                }
+            }
+        }
+    }
+
+    /// What last went wrong storing tables, if anything.
+    #[cfg(feature = "persistence")]
+    pub fn storage_error(&self) -> Option<&str> {
+        self.storage_error.as_deref()
+    }
+
+    #[cfg(not(feature = "persistence"))]
+    #[allow(clippy::unused_self)]
+    pub fn storage_error(&self) -> Option<&str> {
+        None
+    }
+
+    /// The window of the table called exactly `name`, if it is loaded.
+    pub fn window_mut(&mut self, name: &str) -> Option<&mut TableWindow> {
+        self.windows.get_mut(name)
+    }
+
+    /// Open the window of the table called exactly `name`.
+    pub fn open(&mut self, name: &str) {
+        set_open(&mut self.open, name, true);
+    }
+
+    /// The table called exactly `name`, if it is loaded.
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.windows.get(name).map(TableWindow::table)
+    }
+
+    pub fn windows(&mut self, ctx: &Context) {
+        for dataset in &DATASETS {
+            let mut is_open = self.open.contains(dataset.name);
+            if is_open {
+                window(&mut self.windows, dataset).show(ctx, &mut is_open);
+            }
+            set_open(&mut self.open, dataset.name, is_open);
+        }
+        let names: Vec<String> = self.added.iter().map(|(_, name)| name.clone()).collect();
+        for name in names {
+            let mut is_open = self.open.contains(&name);
+            if is_open {
+                // Stored tables are loaded when first opened
+                self.load_stored(&name);
+            }
+            if let (true, Some(window)) = (is_open, self.windows.get_mut(&name)) {
+                window.show(ctx, &mut is_open);
+            }
+            set_open(&mut self.open, &name, is_open);
+        }
+    }
+}
+
+/// The window of `dataset`, loading it if needed.
+fn window<'a>(
+    windows: &'a mut BTreeMap<String, TableWindow>,
+    dataset: &Dataset,
+) -> &'a mut TableWindow {
+    windows.entry(dataset.name.to_owned()).or_insert_with(|| {
+        log::debug!("Loading {:?}", dataset.name);
+        TableWindow::new(dataset.load())
+    })
+}
+
+// ----------------------------------------------------------------------------
+
+fn set_open(open: &mut BTreeSet<String>, key: &str, is_open: bool) {
+    if is_open {
+        if !open.contains(key) {
+            open.insert(key.to_owned());
+        }
+    } else {
+        open.remove(key);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A menu bar in which you can open tables, each in its own window.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DatabaseWindows {
+    about_is_open: bool,
+    about: About,
+    datasets: Datasets,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    import: ImportWindow,
+    query: QueryWindow,
+    chart: ChartWindow,
+}
+
+impl Default for DatabaseWindows {
+    fn default() -> Self {
+        Self {
+            about_is_open: true,
+            about: Default::default(),
+            datasets: Default::default(),
+            import: Default::default(),
+            query: Default::default(),
+            chart: Default::default(),
+        }
+    }
+}
+
+impl DatabaseWindows {
+    /// Open the windows among `open` that are still around, from state saved when
+    /// they were listed as demos.
+    #[cfg(feature = "persistence")]
+    pub(crate) fn reopen_demos(&mut self, open: &BTreeSet<String>) {
+        if open.contains(self.about.name()) {
+            self.about_is_open = true;
+        }
+    }
+
+    /// Show the app ui (menu bar and windows).
+    pub fn ui(&mut self, ctx: &Context) {
+        if is_mobile(ctx) {
+            self.mobile_ui(ctx);
+        } else {
+            self.desktop_ui(ctx);
+        }
+    }
+
+    fn mobile_ui(&mut self, ctx: &Context) {
+        if self.about_is_open {
+            let screen_size = ctx.input(|i| i.screen_rect.size());
+            let default_width = (screen_size.x - 32.0).at_most(400.0);
+
+            let mut close = false;
+            egui::Window::new(self.about.name())
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .default_width(default_width)
+                .default_height(ctx.available_rect().height() - 46.0)
+                .vscroll(true)
+                .open(&mut self.about_is_open)
+                .resizable(false)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    self.about.ui(ui);
+                    ui.add_space(12.0);
+                    ui.vertical_centered_justified(|ui| {
+                        if ui
+                            .button(egui::RichText::new("Continue to the data!").size(20.0))
+                            .clicked()
+                        {
+                            close = true;
+                        }
+                    });
+                });
+            self.about_is_open &= !close;
+        } else {
+            self.mobile_top_bar(ctx);
+            self.show_windows(ctx);
+        }
+    }
+
+    fn mobile_top_bar(&mut self, ctx: &Context) {
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let font_size = 16.5;
+
+                ui.menu_button(egui::RichText::new("⏷ tables").size(font_size), |ui| {
+                    ui.set_style(ui.ctx().style()); // ignore the "menu" style set by `menu_button`.
+                    self.table_list_ui(ui);
+                    if ui.ui_contains_pointer() && ui.input(|i| i.pointer.any_click()) {
+                        ui.close_menu();
+                    }
+                });
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    use egui::special_emojis::{GITHUB, TWITTER};
+                    ui.hyperlink_to(
+                        egui::RichText::new(TWITTER).size(font_size),
+                        "https://twitter.com/ernerfeldt",
+                    );
+                    ui.hyperlink_to(
+                        egui::RichText::new(GITHUB).size(font_size),
+                        "https://github.com/emilk/egui",
+                    );
+                });
+            });
+        });
+    }
+
+    fn desktop_ui(&mut self, ctx: &Context) {
+        egui::SidePanel::right("egui_demo_panel")
+            .resizable(false)
+            .default_width(160.0)
+            .min_width(160.0)
+            .show(ctx, |ui| {
+                ui.add_space(4.0);
+                ui.vertical_centered(|ui| {
+                    ui.heading("🗄 Tables");
+                });
+
+                ui.separator();
+
+                use egui::special_emojis::{GITHUB, TWITTER};
+                ui.hyperlink_to(
+                    format!("{GITHUB} egui on GitHub"),
+                    "https://github.com/emilk/egui",
+                );
+                ui.hyperlink_to(
+                    format!("{TWITTER} @ernerfeldt"),
+                    "https://twitter.com/ernerfeldt",
+                );
+
+                ui.separator();
+
+                self.table_list_ui(ui);
+            });
+
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                file_menu_button(ui);
+            });
+        });
+
+        self.show_windows(ctx);
+    }
+
+    /// Preview the file `name` before importing it as a table.
+    pub fn import(&mut self, name: &str, bytes: &[u8]) {
+        self.import.load(name, bytes.to_vec());
+    }
+
+    /// Show the open windows.
+    fn show_windows(&mut self, ctx: &Context) {
+        self.datasets.update(ctx);
+        self.about.show(ctx, &mut self.about_is_open);
+        if let Some(table) = self.import.show(ctx) {
+            self.datasets.add(table, Category::Imported);
+        }
+        self.query.show(ctx, &mut self.datasets);
+        self.chart.show(ctx, &mut self.datasets);
+        self.datasets.windows(ctx);
+    }
+
+    fn table_list_ui(&mut self, ui: &mut egui::Ui) {
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                ui.toggle_value(&mut self.about_is_open, self.about.name());
+
+                ui.separator();
+                ui.toggle_value(&mut self.import.open, "📥 Import…");
+                ui.toggle_value(&mut self.query.open, "🔍 Query…");
+                ui.toggle_value(&mut self.chart.open, "📊 Chart…");
+                ui.separator();
+                self.datasets.checkboxes(ui, Category::Database);
+                for (category, label) in [
+                    (Category::Imported, "Imported"),
+                    (Category::Result, "Query results"),
+                ] {
+                    if self.datasets.has(category) {
+                        ui.weak(label);
+                        self.datasets.checkboxes(ui, category);
+                    }
+                }
+                if let Some(error) = self.datasets.storage_error() {
+                    ui.colored_label(ui.visuals().warn_fg_color, "⚠ Storage error")
+                        .on_hover_text(error);
+                }
+                ui.separator();
+                ui.weak("Tests");
+                self.datasets.checkboxes(ui, Category::Test);
+                ui.separator();
+
+                if ui.button("Organize windows").clicked() {
+                    ui.ctx().memory_mut(|mem| mem.reset_areas());
+                }
+            });
+        });
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+fn file_menu_button(ui: &mut Ui) {
+    let organize_shortcut =
+        egui::KeyboardShortcut::new(Modifiers::CTRL | Modifiers::SHIFT, egui::Key::O);
+    let reset_shortcut =
+        egui::KeyboardShortcut::new(Modifiers::CTRL | Modifiers::SHIFT, egui::Key::R);
+
+    // NOTE: we must check the shortcuts OUTSIDE of the actual "File" menu,
+    // or else they would only be checked if the "File" menu was actually open!
+
+    if ui.input_mut(|i| i.consume_shortcut(&organize_shortcut)) {
+        ui.ctx().memory_mut(|mem| mem.reset_areas());
+    }
+
+    if ui.input_mut(|i| i.consume_shortcut(&reset_shortcut)) {
+        ui.ctx().memory_mut(|mem| *mem = Default::default());
+    }
+
+    ui.menu_button("File", |ui| {
+        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+        // On the web the browser controls the zoom
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            egui::gui_zoom::zoom_menu_buttons(ui);
+            ui.weak(format!(
+                "Current zoom: {:.0}%",
+                100.0 * ui.ctx().zoom_factor()
+            ))
+            .on_hover_text("The UI zoom level, on top of the operating system's default value");
+            ui.separator();
+        }
+
+        if ui
+            .add(
+                egui::Button::new("Organize Windows")
+                    .shortcut_text(ui.ctx().format_shortcut(&organize_shortcut)),
+            )
+            .clicked()
+        {
+            ui.ctx().memory_mut(|mem| mem.reset_areas());
+            ui.close_menu();
+        }
+
+        if ui
+            .add(
+                egui::Button::new("Reset egui memory")
+                    .shortcut_text(ui.ctx().format_shortcut(&reset_shortcut)),
+            )
+            .on_hover_text("Forget scroll, positions, sizes etc")
+            .clicked()
+        {
+            ui.ctx().memory_mut(|mem| *mem = Default::default());
+            ui.close_menu();
+        }
+    });
+}
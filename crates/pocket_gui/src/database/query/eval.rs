@@ -0,0 +1,801 @@
+//! Running a [`Query`] over tables.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::database::infer::parse_date;
+use crate::database::table::{Column, ColumnData, DataType, Table};
+
+use super::{
+    Aggregate, BinaryOp, Expr, JoinKind, Query, QueryError, Scalar, SelectItem, TableRef, UnaryOp,
+};
+
+/// The most pairs of rows a join that is not on an equality may compare.
+const MAX_PAIRS: usize = 50_000_000;
+
+/// A row of the `FROM` table, and the row of the `JOIN` table it is joined with, if any.
+#[derive(Clone, Copy, Debug)]
+struct Row {
+    left: usize,
+    right: Option<usize>,
+}
+
+/// An [`Expr`] with its columns looked up.
+#[derive(Clone, Debug, PartialEq)]
+enum Bound {
+    Literal(Scalar),
+    /// The table (0 for `FROM`, 1 for `JOIN`) and the column in it.
+    Column(usize, usize),
+    Unary(UnaryOp, Box<Bound>),
+    Binary(Box<Bound>, BinaryOp, Box<Bound>),
+    IsNull(Box<Bound>, bool),
+    Aggregate(Aggregate, Option<Box<Bound>>),
+}
+
+impl Bound {
+    fn contains_aggregate(&self) -> bool {
+        match self {
+            Self::Literal(_) | Self::Column(..) => false,
+            Self::Aggregate(..) => true,
+            Self::Unary(_, expr) | Self::IsNull(expr, _) => expr.contains_aggregate(),
+            Self::Binary(left, _, right) => left.contains_aggregate() || right.contains_aggregate(),
+        }
+    }
+
+    /// The first column that is neither grouped by nor in an aggregate.
+    fn ungrouped(&self, group_by: &[Self]) -> Option<(usize, usize)> {
+        if group_by.contains(self) {
+            return None;
+        }
+        match self {
+            Self::Literal(_) | Self::Aggregate(..) => None,
+            Self::Column(table, column) => Some((*table, *column)),
+            Self::Unary(_, expr) | Self::IsNull(expr, _) => expr.ungrouped(group_by),
+            Self::Binary(left, _, right) => left
+                .ungrouped(group_by)
+                .or_else(|| right.ungrouped(group_by)),
+        }
+    }
+}
+
+/// Run `query` over the tables found by `lookup`, naming the result `name`.
+///
+/// # Errors
+/// If the query reads tables or columns that do not exist, or mixes up types.
+pub fn run<'a>(
+    query: &'a Query,
+    lookup: impl Fn(&str) -> Option<&'a Table>,
+    name: &str,
+) -> Result<Table, QueryError> {
+    let mut tables = vec![];
+    for table_ref in std::iter::once(&query.from).chain(query.join.as_ref().map(|join| &join.table))
+    {
+        let table = lookup(&table_ref.name)
+            .ok_or_else(|| QueryError::UnknownTable(table_ref.name.clone()))?;
+        tables.push((table_ref, table));
+    }
+    if let [(a, _), (b, _)] = tables.as_slice() {
+        if a.qualifier().eq_ignore_ascii_case(b.qualifier()) {
+            return Err(QueryError::AmbiguousTable(b.qualifier().to_owned()));
+        }
+    }
+    let sources = Sources { tables };
+
+    // The output columns, with `*` expanded
+    let mut outputs: Vec<(String, Expr)> = vec![];
+    for item in &query.items {
+        match item {
+            SelectItem::Wildcard => {
+                for (table_ref, table) in &sources.tables {
+                    for column in table.columns() {
+                        let expr = Expr::Column {
+                            table: Some(table_ref.qualifier().to_owned()),
+                            name: column.name.clone(),
+                        };
+                        outputs.push((column.name.clone(), expr));
+                    }
+                }
+            }
+            SelectItem::Expr { expr, alias } => {
+                let name = match (alias, expr) {
+                    (Some(alias), _) => alias.clone(),
+                    (None, Expr::Column { name, .. }) => name.clone(),
+                    (None, expr) => expr.to_string(),
+                };
+                outputs.push((name, expr.clone()));
+            }
+        }
+    }
+    // Qualify the names of columns that are in both tables
+    let names: Vec<String> = outputs.iter().map(|(name, _)| name.clone()).collect();
+    for (name, expr) in &mut outputs {
+        if let Expr::Column {
+            table: Some(table), ..
+        } = expr
+        {
+            if names.iter().filter(|other| *other == name).count() > 1 {
+                *name = format!("{table}.{name}");
+            }
+        }
+    }
+
+    let project = outputs
+        .iter()
+        .map(|(_, expr)| sources.bind(expr, None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let on = match &query.join {
+        Some(join) => Some((join.kind, sources.bind(&join.on, Some("ON"))?)),
+        None => None,
+    };
+    let filter = query
+        .filter
+        .as_ref()
+        .map(|expr| sources.bind(expr, Some("WHERE")))
+        .transpose()?;
+    let group_by = query
+        .group_by
+        .iter()
+        .map(|expr| sources.bind(expr, Some("GROUP BY")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let having = query
+        .having
+        .as_ref()
+        .map(|expr| sources.bind(expr, None))
+        .transpose()?;
+    // ORDER BY may name an output column, or give its position
+    let mut order_by = vec![];
+    for order in &query.order_by {
+        let bound = match &order.expr {
+            Expr::Literal(Scalar::Integer(position)) => usize::try_from(*position - 1)
+                .ok()
+                .and_then(|i| project.get(i).cloned())
+                .ok_or_else(|| QueryError::UnknownColumn(format!("#{position}")))?,
+            Expr::Column { table: None, name } => {
+                match outputs.iter().position(|(output, _)| output == name) {
+                    Some(i) => project[i].clone(),
+                    None => sources.bind(&order.expr, None)?,
+                }
+            }
+            expr => sources.bind(expr, None)?,
+        };
+        order_by.push((bound, order.descending));
+    }
+
+    let grouped = !group_by.is_empty()
+        || having.is_some()
+        || project.iter().any(Bound::contains_aggregate)
+        || order_by.iter().any(|(expr, _)| expr.contains_aggregate());
+    if grouped {
+        let used = project
+            .iter()
+            .chain(&having)
+            .chain(order_by.iter().map(|(expr, _)| expr));
+        for expr in used {
+            if let Some((table, column)) = expr.ungrouped(&group_by) {
+                let name = &sources.tables[table].1.columns()[column].name;
+                return Err(QueryError::NotGrouped(name.clone()));
+            }
+        }
+    }
+
+    let mut rows = sources.join(on.as_ref().map(|(kind, on)| (*kind, on)))?;
+    if let Some(filter) = &filter {
+        let mut kept = Vec::with_capacity(rows.len());
+        for row in rows {
+            if is_true(&sources.eval(filter, &[row])?, "WHERE")? {
+                kept.push(row);
+            }
+        }
+        rows = kept;
+    }
+
+    // What each output row is computed from: one row, or a group of them
+    let groups;
+    let mut units: Vec<&[Row]> = if grouped {
+        groups = sources.group(rows, &group_by)?;
+        groups.iter().map(Vec::as_slice).collect()
+    } else {
+        rows.iter().map(std::slice::from_ref).collect()
+    };
+    if let Some(having) = &having {
+        let mut kept = Vec::with_capacity(units.len());
+        for unit in units {
+            if is_true(&sources.eval(having, unit)?, "HAVING")? {
+                kept.push(unit);
+            }
+        }
+        units = kept;
+    }
+
+    if !order_by.is_empty() {
+        let keys = units
+            .iter()
+            .map(|unit| {
+                order_by
+                    .iter()
+                    .map(|(expr, _)| sources.eval(expr, unit))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut order: Vec<usize> = (0..units.len()).collect();
+        // Stable, so that ties keep their order
+        order.sort_by(|&a, &b| {
+            order_by
+                .iter()
+                .zip(keys[a].iter().zip(&keys[b]))
+                .map(|((_, descending), (x, y))| sort_cmp(x, y, *descending))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        units = order.into_iter().map(|i| units[i]).collect();
+    }
+
+    let limit = query.limit.unwrap_or(usize::MAX);
+    let mut columns: Vec<Vec<Scalar>> = vec![vec![]; project.len()];
+    let mut seen = HashSet::new();
+    let mut num_rows = 0;
+    for unit in units {
+        if num_rows == limit {
+            break;
+        }
+        let row = project
+            .iter()
+            .map(|expr| sources.eval(expr, unit))
+            .collect::<Result<Vec<_>, _>>()?;
+        if query.distinct && !seen.insert(row.clone()) {
+            continue;
+        }
+        for (column, value) in columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+        num_rows += 1;
+    }
+
+    let columns = outputs
+        .into_iter()
+        .zip(columns)
+        .map(|((name, _), values)| Column::new(name, column_data(values)))
+        .collect();
+    Ok(Table::new(name, columns))
+}
+
+/// The tables of a query.
+struct Sources<'a> {
+    tables: Vec<(&'a TableRef, &'a Table)>,
+}
+
+impl Sources<'_> {
+    /// Look up the columns of `expr`.
+    ///
+    /// `clause` is where aggregates are not allowed, if they are not.
+    fn bind(&self, expr: &Expr, clause: Option<&str>) -> Result<Bound, QueryError> {
+        let bind = |expr: &Expr| self.bind(expr, clause);
+        Ok(match expr {
+            Expr::Literal(value) => Bound::Literal(value.clone()),
+            Expr::Column { table, name } => {
+                let (table, column) = self.column(table.as_deref(), name)?;
+                Bound::Column(table, column)
+            }
+            Expr::Unary(op, expr) => Bound::Unary(*op, Box::new(bind(expr)?)),
+            Expr::Binary(left, op, right) => {
+                Bound::Binary(Box::new(bind(left)?), *op, Box::new(bind(right)?))
+            }
+            Expr::IsNull { expr, negated } => Bound::IsNull(Box::new(bind(expr)?), *negated),
+            Expr::Aggregate(aggregate, arg) => {
+                if let Some(clause) = clause {
+                    return Err(QueryError::MisplacedAggregate(clause.to_owned()));
+                }
+                let arg = match arg {
+                    Some(arg) => Some(Box::new(self.bind(arg, Some("an aggregate"))?)),
+                    None => None,
+                };
+                Bound::Aggregate(*aggregate, arg)
+            }
+        })
+    }
+
+    /// The table and index of a column.
+    fn column(&self, qualifier: Option<&str>, name: &str) -> Result<(usize, usize), QueryError> {
+        let find = |table: &Table| {
+            let columns = table.columns();
+            columns
+                .iter()
+                .position(|column| column.name == name)
+                .or_else(|| {
+                    columns
+                        .iter()
+                        .position(|column| column.name.eq_ignore_ascii_case(name))
+                })
+        };
+
+        if let Some(qualifier) = qualifier {
+            let table = self
+                .tables
+                .iter()
+                .position(|(table_ref, _)| {
+                    table_ref.qualifier().eq_ignore_ascii_case(qualifier)
+                        || table_ref.name.eq_ignore_ascii_case(qualifier)
+                })
+                .ok_or_else(|| QueryError::UnknownTable(qualifier.to_owned()))?;
+            let column = find(self.tables[table].1)
+                .ok_or_else(|| QueryError::UnknownColumn(format!("{qualifier}.{name}")))?;
+            return Ok((table, column));
+        }
+
+        let mut found = self
+            .tables
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, table))| Some((i, find(table)?)));
+        match (found.next(), found.next()) {
+            (Some(found), None) => Ok(found),
+            (Some(_), Some(_)) => Err(QueryError::AmbiguousColumn(name.to_owned())),
+            (None, _) => Err(QueryError::UnknownColumn(name.to_owned())),
+        }
+    }
+
+    fn cell(&self, table: usize, column: usize, row: usize) -> Scalar {
+        self.tables[table].1.columns()[column].data.get(row).into()
+    }
+
+    /// The rows of the `FROM` table, joined with those of the `JOIN` table if any.
+    fn join(&self, join: Option<(JoinKind, &Bound)>) -> Result<Vec<Row>, QueryError> {
+        let num_left = self.tables[0].1.num_rows();
+        let Some((kind, on)) = join else {
+            return Ok((0..num_left)
+                .map(|left| Row { left, right: None })
+                .collect());
+        };
+        let num_right = self.tables[1].1.num_rows();
+        let mut rows = vec![];
+
+        // Joining on `a.x = b.y` is done by looking up `a.x` in an index of `b.y`
+        let columns = match on {
+            Bound::Binary(a, BinaryOp::Eq, b) => match (a.as_ref(), b.as_ref()) {
+                (Bound::Column(0, left), Bound::Column(1, right))
+                | (Bound::Column(1, right), Bound::Column(0, left)) => Some((*left, *right)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some((left_column, right_column)) = columns {
+            let mut index: HashMap<Scalar, Vec<usize>> = HashMap::new();
+            for right in 0..num_right {
+                let key = self.cell(1, right_column, right);
+                if !key.is_null() {
+                    index.entry(key).or_default().push(right);
+                }
+            }
+            for left in 0..num_left {
+                match index.get(&self.cell(0, left_column, left)) {
+                    Some(matches) => rows.extend(matches.iter().map(|&right| Row {
+                        left,
+                        right: Some(right),
+                    })),
+                    None if kind == JoinKind::Left => rows.push(Row { left, right: None }),
+                    None => {}
+                }
+            }
+            return Ok(rows);
+        }
+
+        if num_left.saturating_mul(num_right) > MAX_PAIRS {
+            return Err(QueryError::TooLarge);
+        }
+        for left in 0..num_left {
+            let mut matched = false;
+            for right in 0..num_right {
+                let row = Row {
+                    left,
+                    right: Some(right),
+                };
+                if is_true(&self.eval(on, &[row])?, "ON")? {
+                    rows.push(row);
+                    matched = true;
+                }
+            }
+            if !matched && kind == JoinKind::Left {
+                rows.push(Row { left, right: None });
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Split `rows` by the values of `group_by`, in order of first appearance.
+    ///
+    /// Without `GROUP BY`, all rows are one group, even if there are none.
+    fn group(&self, rows: Vec<Row>, group_by: &[Bound]) -> Result<Vec<Vec<Row>>, QueryError> {
+        if group_by.is_empty() {
+            return Ok(vec![rows]);
+        }
+        let mut groups: Vec<Vec<Row>> = vec![];
+        let mut index: HashMap<Vec<Scalar>, usize> = HashMap::new();
+        for row in rows {
+            let key = group_by
+                .iter()
+                .map(|expr| self.eval(expr, &[row]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let group = *index.entry(key).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[group].push(row);
+        }
+        Ok(groups)
+    }
+
+    /// The value of `expr` for a row, or for a group of rows.
+    ///
+    /// Columns outside aggregates take their value from the first row.
+    fn eval(&self, expr: &Bound, rows: &[Row]) -> Result<Scalar, QueryError> {
+        Ok(match expr {
+            Bound::Literal(value) => value.clone(),
+            Bound::Column(table, column) => {
+                let row = rows.first().and_then(|row| match table {
+                    0 => Some(row.left),
+                    _ => row.right,
+                });
+                row.map_or(Scalar::Null, |row| self.cell(*table, *column, row))
+            }
+            Bound::Unary(op, expr) => unary(*op, self.eval(expr, rows)?)?,
+            Bound::Binary(left, op @ (BinaryOp::And | BinaryOp::Or), right) => {
+                let left = self.eval(left, rows)?;
+                // Short-circuit: false AND …, true OR …
+                if truth(&left, *op)? == Some(*op == BinaryOp::Or) {
+                    return Ok(left);
+                }
+                binary(&left, *op, &self.eval(right, rows)?)?
+            }
+            Bound::Binary(left, op, right) => {
+                binary(&self.eval(left, rows)?, *op, &self.eval(right, rows)?)?
+            }
+            Bound::IsNull(expr, negated) => {
+                Scalar::Boolean(self.eval(expr, rows)?.is_null() != *negated)
+            }
+            Bound::Aggregate(aggregate, arg) => self.aggregate(*aggregate, arg.as_deref(), rows)?,
+        })
+    }
+
+    fn aggregate(
+        &self,
+        aggregate: Aggregate,
+        arg: Option<&Bound>,
+        rows: &[Row],
+    ) -> Result<Scalar, QueryError> {
+        let Some(arg) = arg else {
+            return Ok(Scalar::Integer(rows.len() as i64));
+        };
+        let mut result = Scalar::Null;
+        let mut count = 0;
+        for row in rows {
+            let value = self.eval(arg, std::slice::from_ref(row))?;
+            if value.is_null() {
+                continue;
+            }
+            count += 1;
+            result = match (aggregate, result) {
+                (Aggregate::Count, _) => Scalar::Null,
+                (Aggregate::Sum | Aggregate::Avg, Scalar::Null) => {
+                    binary(&Scalar::Integer(0), BinaryOp::Add, &value)?
+                }
+                (Aggregate::Sum | Aggregate::Avg, sum) => binary(&sum, BinaryOp::Add, &value)?,
+                (Aggregate::Min | Aggregate::Max, Scalar::Null) => value,
+                (Aggregate::Min | Aggregate::Max, best) => {
+                    let ordering = compare(&value, &best)?;
+                    let better = if aggregate == Aggregate::Min {
+                        Ordering::Less
+                    } else {
+                        Ordering::Greater
+                    };
+                    if ordering == Some(better) {
+                        value
+                    } else {
+                        best
+                    }
+                }
+            };
+        }
+        Ok(match (aggregate, result) {
+            (Aggregate::Count, _) => Scalar::Integer(count),
+            (Aggregate::Avg, Scalar::Integer(sum)) => Scalar::Float(sum as f64 / count as f64),
+            (Aggregate::Avg, Scalar::Float(sum)) => Scalar::Float(sum / count as f64),
+            (_, result) => result,
+        })
+    }
+}
+
+/// Whether a row passes `clause`; missing values do not.
+fn is_true(value: &Scalar, clause: &str) -> Result<bool, QueryError> {
+    match value {
+        Scalar::Boolean(value) => Ok(*value),
+        Scalar::Null => Ok(false),
+        value => Err(QueryError::Type(format!(
+            "{clause} must be true or false, not {}",
+            value.type_name()
+        ))),
+    }
+}
+
+fn truth(value: &Scalar, op: BinaryOp) -> Result<Option<bool>, QueryError> {
+    match value {
+        Scalar::Boolean(value) => Ok(Some(*value)),
+        Scalar::Null => Ok(None),
+        value => Err(QueryError::Type(format!(
+            "{} needs true or false, not {}",
+            op.symbol(),
+            value.type_name()
+        ))),
+    }
+}
+
+fn unary(op: UnaryOp, value: Scalar) -> Result<Scalar, QueryError> {
+    Ok(match (op, value) {
+        (_, Scalar::Null) => Scalar::Null,
+        (UnaryOp::Not, Scalar::Boolean(value)) => Scalar::Boolean(!value),
+        (UnaryOp::Neg, Scalar::Integer(value)) => value
+            .checked_neg()
+            .map_or(Scalar::Float(-(value as f64)), Scalar::Integer),
+        (UnaryOp::Neg, Scalar::Float(value)) => Scalar::Float(-value),
+        (op, value) => {
+            let op = match op {
+                UnaryOp::Not => "NOT",
+                UnaryOp::Neg => "-",
+            };
+            return Err(QueryError::Type(format!(
+                "{op} does not apply to {}",
+                value.type_name()
+            )));
+        }
+    })
+}
+
+fn binary(left: &Scalar, op: BinaryOp, right: &Scalar) -> Result<Scalar, QueryError> {
+    use Scalar::{Date, Float, Integer, Null};
+
+    let type_error = |left: &Scalar, right: &Scalar| {
+        QueryError::Type(format!(
+            "{} does not apply to {} and {}",
+            op.symbol(),
+            left.type_name(),
+            right.type_name()
+        ))
+    };
+    Ok(match op {
+        BinaryOp::And | BinaryOp::Or => {
+            let (left, right) = (truth(left, op)?, truth(right, op)?);
+            match (left, right) {
+                (Some(left), Some(right)) if op == BinaryOp::And => Scalar::Boolean(left && right),
+                (Some(left), Some(right)) => Scalar::Boolean(left || right),
+                (Some(value), None) | (None, Some(value)) if value == (op == BinaryOp::Or) => {
+                    Scalar::Boolean(value)
+                }
+                _ => Null,
+            }
+        }
+        BinaryOp::Eq
+        | BinaryOp::NotEq
+        | BinaryOp::Lt
+        | BinaryOp::LtEq
+        | BinaryOp::Gt
+        | BinaryOp::GtEq => match compare(left, right)? {
+            None => Null,
+            Some(ordering) => Scalar::Boolean(match op {
+                BinaryOp::Eq => ordering.is_eq(),
+                BinaryOp::NotEq => ordering.is_ne(),
+                BinaryOp::Lt => ordering.is_lt(),
+                BinaryOp::LtEq => ordering.is_le(),
+                BinaryOp::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }),
+        },
+        BinaryOp::Like => match (left, right) {
+            (Null, _) | (_, Null) => Null,
+            (Scalar::String(text), Scalar::String(pattern)) => Scalar::Boolean(like(text, pattern)),
+            _ => return Err(type_error(left, right)),
+        },
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+            match (left, right) {
+                (Null, _) | (_, Null) => Null,
+                (Integer(a), Integer(b)) => {
+                    let (a, b) = (*a, *b);
+                    let value = match op {
+                        BinaryOp::Add => a.checked_add(b),
+                        BinaryOp::Sub => a.checked_sub(b),
+                        BinaryOp::Mul => a.checked_mul(b),
+                        // Dividing integers gives a float, as 1 / 2 is rarely meant to be 0
+                        BinaryOp::Div => return Ok(arithmetic(a as f64, op, b as f64)),
+                        _ if b == 0 => return Ok(Null),
+                        _ => a.checked_rem(b),
+                    };
+                    // On overflow, a float
+                    value.map_or_else(|| arithmetic(a as f64, op, b as f64), Integer)
+                }
+                (Integer(_) | Float(_), Integer(_) | Float(_)) => {
+                    arithmetic(as_f64(left), op, as_f64(right))
+                }
+                // Dates plus or minus days, or the days between two dates
+                (Date(date), Integer(days)) if matches!(op, BinaryOp::Add | BinaryOp::Sub) => {
+                    let days = if op == BinaryOp::Add { *days } else { -*days };
+                    date.checked_add_signed(chrono::Duration::days(days))
+                        .map_or(Null, Date)
+                }
+                (Date(a), Date(b)) if op == BinaryOp::Sub => Integer((*a - *b).num_days()),
+                _ => return Err(type_error(left, right)),
+            }
+        }
+    })
+}
+
+fn as_f64(value: &Scalar) -> f64 {
+    match value {
+        Scalar::Integer(value) => *value as f64,
+        Scalar::Float(value) => *value,
+        _ => f64::NAN,
+    }
+}
+
+/// Float arithmetic, where dividing by zero gives a missing value.
+fn arithmetic(a: f64, op: BinaryOp, b: f64) -> Scalar {
+    match op {
+        BinaryOp::Add => Scalar::Float(a + b),
+        BinaryOp::Sub => Scalar::Float(a - b),
+        BinaryOp::Mul => Scalar::Float(a * b),
+        _ if b == 0.0 => Scalar::Null,
+        BinaryOp::Div => Scalar::Float(a / b),
+        _ => Scalar::Float(a % b),
+    }
+}
+
+/// How two values compare, or `None` if either is missing.
+///
+/// Dates compare with text in a date format, e.g. `collected < '2020-01-01'`.
+fn compare(left: &Scalar, right: &Scalar) -> Result<Option<Ordering>, QueryError> {
+    use Scalar::{Boolean, Date, Float, Integer, Null, String};
+
+    let date = |text: &str| {
+        parse_date(text.trim())
+            .ok_or_else(|| QueryError::Type(format!("{text:?} is not a date, e.g. 2020-12-31")))
+    };
+    Ok(match (left, right) {
+        (Null, _) | (_, Null) => None,
+        (Integer(a), Integer(b)) => Some(a.cmp(b)),
+        (Integer(_) | Float(_), Integer(_) | Float(_)) => as_f64(left).partial_cmp(&as_f64(right)),
+        (String(a), String(b)) => Some(a.cmp(b)),
+        (Date(a), Date(b)) => Some(a.cmp(b)),
+        (Date(a), String(b)) => Some(a.cmp(&date(b)?)),
+        (String(a), Date(b)) => Some(date(a)?.cmp(b)),
+        (Boolean(a), Boolean(b)) => Some(a.cmp(b)),
+        _ => {
+            return Err(QueryError::Type(format!(
+                "Cannot compare {} with {}",
+                left.type_name(),
+                right.type_name()
+            )))
+        }
+    })
+}
+
+/// The order of rows in `ORDER BY`: missing values last either way,
+/// and values that do not compare by type.
+fn sort_cmp(x: &Scalar, y: &Scalar, descending: bool) -> Ordering {
+    let rank = |value: &Scalar| match value {
+        Scalar::Null => 0,
+        Scalar::Boolean(_) => 1,
+        Scalar::Integer(_) | Scalar::Float(_) => 2,
+        Scalar::Date(_) => 3,
+        Scalar::String(_) => 4,
+    };
+    match (x.is_null(), y.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => {
+            let ordering = match compare(x, y) {
+                Ok(Some(ordering)) => ordering,
+                _ => rank(x).cmp(&rank(y)),
+            };
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+    }
+}
+
+/// SQL `LIKE`, ignoring ASCII case: `%` is any text, `_` any one character.
+fn like(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let (mut t, mut p) = (0, 0);
+    // The last `%`, and where in the text it was tried from
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('%') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '_' || c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                // Let the `%` take one more character
+                Some((percent, from)) => {
+                    backtrack = Some((percent, from + 1));
+                    p = percent + 1;
+                    t = from + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+/// A column of values of one type; mixed integers and floats are floats,
+/// and other mixes are text.
+fn column_data(values: Vec<Scalar>) -> ColumnData {
+    let mut data_type = None;
+    for value in &values {
+        let value_type = match value {
+            Scalar::Null => continue,
+            Scalar::String(_) => DataType::String,
+            Scalar::Integer(_) => DataType::Integer,
+            Scalar::Float(_) => DataType::Float,
+            Scalar::Date(_) => DataType::Date,
+            Scalar::Boolean(_) => DataType::Boolean,
+        };
+        data_type = Some(match (data_type, value_type) {
+            (None, value_type) => value_type,
+            (Some(a), b) if a == b => a,
+            (Some(DataType::Integer | DataType::Float), DataType::Integer | DataType::Float) => {
+                DataType::Float
+            }
+            _ => DataType::String,
+        });
+    }
+
+    let values = values.into_iter();
+    match data_type.unwrap_or(DataType::String) {
+        DataType::String => ColumnData::String(
+            values
+                .map(|value| (!value.is_null()).then(|| value.to_string()))
+                .collect(),
+        ),
+        DataType::Integer => ColumnData::Integer(
+            values
+                .map(|value| match value {
+                    Scalar::Integer(value) => Some(value),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        DataType::Float => ColumnData::Float(
+            values
+                .map(|value| match value {
+                    Scalar::Integer(value) => Some(value as f64),
+                    Scalar::Float(value) => Some(value),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        DataType::Date => ColumnData::Date(
+            values
+                .map(|value| match value {
+                    Scalar::Date(value) => Some(value),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        DataType::Boolean => ColumnData::Boolean(
+            values
+                .map(|value| match value {
+                    Scalar::Boolean(value) => Some(value),
+                    _ => None,
+                })
+                .collect(),
+        ),
+    }
+}
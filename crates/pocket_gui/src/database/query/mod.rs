@@ -0,0 +1,346 @@
+//! A SQL-like query language over the loaded tables.
+//!
+//! Supported is a single `SELECT [DISTINCT] … FROM …` with an optional
+//! `[LEFT] JOIN … ON …`, `WHERE`, `GROUP BY`, `HAVING`, `ORDER BY` and `LIMIT`.
+//! The aggregates are `COUNT`, `SUM`, `AVG`, `MIN` and `MAX`.
+//! Names with spaces or punctuation are written in double quotes, e.g. `"Generated (10k rows)"`.
+
+mod eval;
+mod parse;
+
+use std::hash::{Hash, Hasher};
+
+use chrono::NaiveDate;
+
+use super::table::Value;
+
+pub use eval::run;
+pub use parse::parse;
+
+/// A parsed `SELECT` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub distinct: bool,
+    pub items: Vec<SelectItem>,
+    pub from: TableRef,
+    pub join: Option<Join>,
+    pub filter: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: Option<usize>,
+}
+
+impl Query {
+    /// The names of the tables the query reads.
+    pub fn table_names(&self) -> Vec<&str> {
+        std::iter::once(&self.from)
+            .chain(self.join.as_ref().map(|join| &join.table))
+            .map(|table| table.name.as_str())
+            .collect()
+    }
+}
+
+/// A table in `FROM` or `JOIN`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableRef {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+impl TableRef {
+    /// The name columns are qualified with, e.g. `s` in `s.country`.
+    pub fn qualifier(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only rows with a match on both sides.
+    Inner,
+    /// Every row of the left table, with missing values where nothing matches.
+    Left,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Join {
+    pub kind: JoinKind,
+    pub table: TableRef,
+    pub on: Expr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectItem {
+    /// `*`, all the columns.
+    Wildcard,
+    Expr {
+        expr: Expr,
+        alias: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderBy {
+    pub expr: Expr,
+    pub descending: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    /// `LIKE`, where `%` is any text and `_` any one character.
+    Like,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinaryOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Or => "OR",
+            Self::And => "AND",
+            Self::Eq => "=",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::LtEq => "<=",
+            Self::Gt => ">",
+            Self::GtEq => ">=",
+            Self::Like => "LIKE",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    pub const ALL: [Self; 5] = [Self::Count, Self::Sum, Self::Avg, Self::Min, Self::Max];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Literal(Scalar),
+    /// A column, optionally qualified by its table, e.g. `s.country`.
+    Column {
+        table: Option<String>,
+        name: String,
+    },
+    Unary(UnaryOp, Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    /// `None` is `COUNT(*)`.
+    Aggregate(Aggregate, Option<Box<Expr>>),
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Literal(Scalar::String(text)) => write!(f, "'{}'", text.replace('\'', "''")),
+            Self::Literal(Scalar::Null) => write!(f, "NULL"),
+            Self::Literal(value) => value.fmt(f),
+            Self::Column { table: None, name } => name.fmt(f),
+            Self::Column {
+                table: Some(table),
+                name,
+            } => write!(f, "{table}.{name}"),
+            Self::Unary(UnaryOp::Not, expr) => write!(f, "NOT {expr}"),
+            Self::Unary(UnaryOp::Neg, expr) => write!(f, "-{expr}"),
+            Self::Binary(left, op, right) => write!(f, "{left} {} {right}", op.symbol()),
+            Self::IsNull {
+                expr,
+                negated: false,
+            } => write!(f, "{expr} IS NULL"),
+            Self::IsNull {
+                expr,
+                negated: true,
+            } => write!(f, "{expr} IS NOT NULL"),
+            Self::Aggregate(aggregate, None) => write!(f, "{}(*)", aggregate.name()),
+            Self::Aggregate(aggregate, Some(expr)) => write!(f, "{}({expr})", aggregate.name()),
+        }
+    }
+}
+
+/// A value computed by a query.
+///
+/// Floats compare and hash by their bits, so that they can be grouped by.
+#[derive(Clone, Debug)]
+pub enum Scalar {
+    Null,
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Date(NaiveDate),
+    Boolean(bool),
+}
+
+impl Scalar {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::String(_) => "string",
+            Self::Integer(_) => "integer",
+            Self::Float(_) => "float",
+            Self::Date(_) => "date",
+            Self::Boolean(_) => "boolean",
+        }
+    }
+}
+
+impl From<Value<'_>> for Scalar {
+    fn from(value: Value<'_>) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::String(value) => Self::String(value.to_owned()),
+            Value::Integer(value) => Self::Integer(value),
+            Value::Float(value) => Self::Float(value),
+            Value::Date(value) => Self::Date(value),
+            Value::Boolean(value) => Self::Boolean(value),
+        }
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Null, Self::Null) => true,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::Date(a), Self::Date(b)) => a == b,
+            (Self::Boolean(a), Self::Boolean(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Scalar {}
+
+impl Hash for Scalar {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Null => {}
+            Self::String(value) => value.hash(state),
+            Self::Integer(value) => value.hash(state),
+            Self::Float(value) => value.to_bits().hash(state),
+            Self::Date(value) => value.hash(state),
+            Self::Boolean(value) => value.hash(state),
+        }
+    }
+}
+
+impl std::fmt::Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => Ok(()),
+            Self::String(value) => value.fmt(f),
+            Self::Integer(value) => value.fmt(f),
+            Self::Float(value) => value.fmt(f),
+            Self::Date(value) => value.fmt(f),
+            Self::Boolean(value) => value.fmt(f),
+        }
+    }
+}
+
+/// Why a query could not be run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueryError {
+    /// The text is not a query; at this character offset.
+    Syntax {
+        position: usize,
+        message: String,
+    },
+    UnknownTable(String),
+    /// Both tables of a join go by this name.
+    AmbiguousTable(String),
+    UnknownColumn(String),
+    /// A column name that is in both tables of a join.
+    AmbiguousColumn(String),
+    /// A column used outside an aggregate that is not grouped by.
+    NotGrouped(String),
+    /// An aggregate where there can be none, e.g. in `WHERE`.
+    MisplacedAggregate(String),
+    /// Values of types that do not go together, e.g. `'a' + 1`.
+    Type(String),
+    /// A join without an equality between the tables that would take too long.
+    TooLarge,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { position, message } => {
+                write!(f, "Syntax error at character {}: {message}", position + 1)
+            }
+            Self::UnknownTable(name) => write!(f, "There is no table {name:?}"),
+            Self::AmbiguousTable(name) => write!(
+                f,
+                "Both tables are called {name:?}; give one an alias, e.g. JOIN {name:?} AS b"
+            ),
+            Self::UnknownColumn(name) => write!(f, "There is no column {name:?}"),
+            Self::AmbiguousColumn(name) => {
+                write!(
+                    f,
+                    "Both tables have a column {name:?}; qualify it, e.g. t.{name}"
+                )
+            }
+            Self::NotGrouped(expr) => write!(
+                f,
+                "{expr} must be in GROUP BY, or used in an aggregate such as count({expr})"
+            ),
+            Self::MisplacedAggregate(clause) => write!(f, "Aggregates cannot be used in {clause}"),
+            Self::Type(message) => message.fmt(f),
+            Self::TooLarge => write!(
+                f,
+                "This join compares too many pairs of rows; join ON an equality of two columns"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
@@ -0,0 +1,486 @@
+//! From text to a [`Query`].
+
+use super::{
+    Aggregate, BinaryOp, Expr, Join, JoinKind, OrderBy, Query, QueryError, Scalar, SelectItem,
+    TableRef, UnaryOp,
+};
+
+/// Words that end an expression or a name, so they cannot be aliases.
+const KEYWORDS: [&str; 25] = [
+    "SELECT", "DISTINCT", "FROM", "JOIN", "INNER", "LEFT", "OUTER", "ON", "WHERE", "GROUP", "BY",
+    "HAVING", "ORDER", "ASC", "DESC", "LIMIT", "AS", "AND", "OR", "NOT", "IS", "NULL", "LIKE",
+    "TRUE", "FALSE",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// A name or keyword.
+    Word(String),
+    /// A name in double quotes or backticks, never a keyword.
+    Quoted(String),
+    Integer(i64),
+    Float(f64),
+    /// Text in single quotes.
+    String(String),
+    /// Punctuation and operators, e.g. `,` or `<=`.
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 16] = [
+    "<=", ">=", "!=", "<>", "=", "<", ">", "+", "-", "*", "/", "%", ",", "(", ")", ".",
+];
+
+/// Parse the text of a query, e.g. `SELECT country, count(*) FROM Samples GROUP BY country`.
+///
+/// # Errors
+/// If the text is not a query of the supported form.
+pub fn parse(text: &str) -> Result<Query, QueryError> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        next: 0,
+        end: text.len(),
+    };
+    let query = parser.query()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("expected the end of the query"));
+    }
+    Ok(query)
+}
+
+/// The tokens of `text`, with the byte offset each starts at.
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        let error = |message: &str| QueryError::Syntax {
+            position: start,
+            message: message.to_owned(),
+        };
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '-' && text[start..].starts_with("--") {
+            // A comment, to the end of the line
+            while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+        } else if c.is_alphabetic() || c == '_' {
+            let mut word = String::new();
+            while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_') {
+                word.push(c);
+            }
+            tokens.push((start, Token::Word(word)));
+        } else if c.is_ascii_digit()
+            || (c == '.' && text[start + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            let mut number = String::new();
+            while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '.')
+            {
+                number.push(c);
+            }
+            let token = if let Ok(value) = number.parse::<i64>() {
+                Token::Integer(value)
+            } else if let Ok(value) = number.parse::<f64>() {
+                Token::Float(value)
+            } else {
+                return Err(error("not a number"));
+            };
+            tokens.push((start, token));
+        } else if matches!(c, '\'' | '"' | '`') {
+            chars.next();
+            let mut quoted = String::new();
+            loop {
+                match chars.next() {
+                    // A doubled quote stands for itself
+                    Some((_, q)) if q == c => {
+                        if chars.next_if(|&(_, q)| q == c).is_some() {
+                            quoted.push(c);
+                        } else {
+                            break;
+                        }
+                    }
+                    Some((_, q)) => quoted.push(q),
+                    None => return Err(error("the quote is never closed")),
+                }
+            }
+            let token = if c == '\'' {
+                Token::String(quoted)
+            } else {
+                Token::Quoted(quoted)
+            };
+            tokens.push((start, token));
+        } else if let Some(symbol) = SYMBOLS
+            .iter()
+            .find(|symbol| text[start..].starts_with(**symbol))
+        {
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push((start, Token::Symbol(symbol)));
+        } else if c == ';' && text[start + 1..].trim().is_empty() {
+            chars.next();
+        } else {
+            return Err(error(&format!("unexpected {c:?}")));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    /// The length of the text, where errors at the end are.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).map(|(_, token)| token.clone());
+        self.next += 1;
+        token
+    }
+
+    fn error(&self, message: &str) -> QueryError {
+        QueryError::Syntax {
+            position: self
+                .tokens
+                .get(self.next)
+                .map_or(self.end, |&(position, _)| position),
+            message: message.to_owned(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Skip `keyword` if it is next.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let is_next = self.is_keyword(keyword);
+        if is_next {
+            self.next += 1;
+        }
+        is_next
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {keyword}")))
+        }
+    }
+
+    /// Skip `symbol` if it is next.
+    fn symbol(&mut self, symbol: &str) -> bool {
+        let is_next = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        if is_next {
+            self.next += 1;
+        }
+        is_next
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), QueryError> {
+        if self.symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {symbol:?}")))
+        }
+    }
+
+    /// A table or column name: a word that is not a keyword, or anything quoted.
+    fn name(&mut self) -> Option<String> {
+        match self.peek()? {
+            Token::Word(word) if !KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word)) => {}
+            Token::Quoted(_) => {}
+            _ => return None,
+        }
+        match self.advance() {
+            Some(Token::Word(name) | Token::Quoted(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn expect_name(&mut self, what: &str) -> Result<String, QueryError> {
+        self.name()
+            .ok_or_else(|| self.error(&format!("expected {what}")))
+    }
+
+    /// `[AS] alias`
+    fn alias(&mut self) -> Result<Option<String>, QueryError> {
+        if self.keyword("AS") {
+            self.expect_name("a name after AS").map(Some)
+        } else {
+            Ok(self.name())
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, QueryError> {
+        self.expect_keyword("SELECT")?;
+        let distinct = self.keyword("DISTINCT");
+        let mut items = vec![self.select_item()?];
+        while self.symbol(",") {
+            items.push(self.select_item()?);
+        }
+
+        self.expect_keyword("FROM")?;
+        let from = self.table_ref()?;
+
+        let kind = if self.keyword("LEFT") {
+            self.keyword("OUTER");
+            Some(JoinKind::Left)
+        } else if self.keyword("INNER") || self.is_keyword("JOIN") {
+            Some(JoinKind::Inner)
+        } else {
+            None
+        };
+        let join = match kind {
+            Some(kind) => {
+                self.expect_keyword("JOIN")?;
+                let table = self.table_ref()?;
+                self.expect_keyword("ON")?;
+                let on = self.expr()?;
+                Some(Join { kind, table, on })
+            }
+            None => None,
+        };
+
+        let filter = if self.keyword("WHERE") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut group_by = vec![];
+        if self.keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by.push(self.expr()?);
+            while self.symbol(",") {
+                group_by.push(self.expr()?);
+            }
+        }
+
+        let having = if self.keyword("HAVING") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut order_by = vec![];
+        if self.keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            order_by.push(self.order_by()?);
+            while self.symbol(",") {
+                order_by.push(self.order_by()?);
+            }
+        }
+
+        let limit = if self.keyword("LIMIT") {
+            match self.advance() {
+                Some(Token::Integer(limit)) if limit >= 0 => Some(limit as usize),
+                _ => {
+                    self.next -= 1;
+                    return Err(self.error("expected a number of rows after LIMIT"));
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Query {
+            distinct,
+            items,
+            from,
+            join,
+            filter,
+            group_by,
+            having,
+            order_by,
+            limit,
+        })
+    }
+
+    fn select_item(&mut self) -> Result<SelectItem, QueryError> {
+        if self.symbol("*") {
+            return Ok(SelectItem::Wildcard);
+        }
+        let expr = self.expr()?;
+        let alias = self.alias()?;
+        Ok(SelectItem::Expr { expr, alias })
+    }
+
+    fn table_ref(&mut self) -> Result<TableRef, QueryError> {
+        let name = self.expect_name("a table name")?;
+        let alias = self.alias()?;
+        Ok(TableRef { name, alias })
+    }
+
+    fn order_by(&mut self) -> Result<OrderBy, QueryError> {
+        let expr = self.expr()?;
+        let descending = if self.keyword("DESC") {
+            true
+        } else {
+            self.keyword("ASC");
+            false
+        };
+        Ok(OrderBy { expr, descending })
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.and()?;
+        while self.keyword("OR") {
+            left = Expr::Binary(Box::new(left), BinaryOp::Or, Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.not()?;
+        while self.keyword("AND") {
+            left = Expr::Binary(Box::new(left), BinaryOp::And, Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if self.keyword("NOT") {
+            Ok(Expr::Unary(UnaryOp::Not, Box::new(self.not()?)))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, QueryError> {
+        let left = self.additive()?;
+        if self.keyword("IS") {
+            let negated = self.keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull {
+                expr: Box::new(left),
+                negated,
+            });
+        }
+        if self.keyword("NOT") {
+            self.expect_keyword("LIKE")?;
+            let like = Expr::Binary(Box::new(left), BinaryOp::Like, Box::new(self.additive()?));
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(like)));
+        }
+        let op = if self.keyword("LIKE") {
+            BinaryOp::Like
+        } else {
+            let op = match self.peek() {
+                Some(Token::Symbol("=")) => BinaryOp::Eq,
+                Some(Token::Symbol("!=" | "<>")) => BinaryOp::NotEq,
+                Some(Token::Symbol("<")) => BinaryOp::Lt,
+                Some(Token::Symbol("<=")) => BinaryOp::LtEq,
+                Some(Token::Symbol(">")) => BinaryOp::Gt,
+                Some(Token::Symbol(">=")) => BinaryOp::GtEq,
+                _ => return Ok(left),
+            };
+            self.next += 1;
+            op
+        };
+        Ok(Expr::Binary(Box::new(left), op, Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("+")) => BinaryOp::Add,
+                Some(Token::Symbol("-")) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.next += 1;
+            left = Expr::Binary(Box::new(left), op, Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("*")) => BinaryOp::Mul,
+                Some(Token::Symbol("/")) => BinaryOp::Div,
+                Some(Token::Symbol("%")) => BinaryOp::Rem,
+                _ => return Ok(left),
+            };
+            self.next += 1;
+            left = Expr::Binary(Box::new(left), op, Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.symbol("-") {
+            Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        if self.symbol("(") {
+            let expr = self.expr()?;
+            self.expect_symbol(")")?;
+            return Ok(expr);
+        }
+        for (keyword, value) in [
+            ("NULL", Scalar::Null),
+            ("TRUE", Scalar::Boolean(true)),
+            ("FALSE", Scalar::Boolean(false)),
+        ] {
+            if self.keyword(keyword) {
+                return Ok(Expr::Literal(value));
+            }
+        }
+        match self.peek() {
+            Some(Token::Integer(value)) => {
+                let value = *value;
+                self.next += 1;
+                return Ok(Expr::Literal(Scalar::Integer(value)));
+            }
+            Some(Token::Float(value)) => {
+                let value = *value;
+                self.next += 1;
+                return Ok(Expr::Literal(Scalar::Float(value)));
+            }
+            Some(Token::String(_)) => {
+                let Some(Token::String(value)) = self.advance() else {
+                    unreachable!()
+                };
+                return Ok(Expr::Literal(Scalar::String(value)));
+            }
+            _ => {}
+        }
+
+        let Some(name) = self.name() else {
+            return Err(self.error("expected a value or a column name"));
+        };
+        if self.symbol("(") {
+            let aggregate = Aggregate::ALL
+                .into_iter()
+                .find(|aggregate| aggregate.name().eq_ignore_ascii_case(&name))
+                .ok_or_else(|| {
+                    self.next -= 2;
+                    self.error(&format!("unknown function {name:?}"))
+                })?;
+            let arg = if aggregate == Aggregate::Count && self.symbol("*") {
+                None
+            } else {
+                Some(Box::new(self.expr()?))
+            };
+            self.expect_symbol(")")?;
+            return Ok(Expr::Aggregate(aggregate, arg));
+        }
+        if self.symbol(".") {
+            let column = self.expect_name("a column name")?;
+            return Ok(Expr::Column {
+                table: Some(name),
+                name: column,
+            });
+        }
+        Ok(Expr::Column { table: None, name })
+    }
+}
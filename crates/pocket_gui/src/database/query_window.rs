@@ -0,0 +1,188 @@
+//! The "Query" window: ask questions of the tables, and get the answers as new tables.
+
+use std::collections::BTreeMap;
+
+use egui::{Key, KeyboardShortcut, Modifiers, Ui};
+
+use super::datasets::Category;
+use super::query::{self, QueryError};
+use super::table::Table;
+use super::Datasets;
+
+const EXAMPLE: &str = "SELECT country, count(*) AS samples, avg(coverage) AS mean_coverage
+FROM Samples
+WHERE collected >= '2015-01-01'
+GROUP BY country
+ORDER BY samples DESC";
+
+const SYNTAX: &str = "SELECT [DISTINCT] * | expr [AS name], …
+FROM table [AS t]
+[[LEFT] JOIN table [AS u] ON t.a = u.b]
+[WHERE condition]
+[GROUP BY expr, …]
+[HAVING condition]
+[ORDER BY expr [ASC | DESC], …]
+[LIMIT rows]
+
+Aggregates: count(*), count(x), sum(x), avg(x), min(x), max(x)
+Operators: = != < <= > >= LIKE, IS [NOT] NULL, AND OR NOT, + - * / %
+Text in 'single quotes'; names with spaces in \"double quotes\".";
+
+/// Write a query, and run it to add its result as a table.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct QueryWindow {
+    pub open: bool,
+    text: String,
+    /// The name of the result table.
+    name: String,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    error: Option<String>,
+}
+
+impl Default for QueryWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            text: EXAMPLE.to_owned(),
+            name: "Result".to_owned(),
+            error: None,
+        }
+    }
+}
+
+impl QueryWindow {
+    pub fn show(&mut self, ctx: &egui::Context, datasets: &mut Datasets) {
+        let mut open = self.open;
+        egui::Window::new("🔍 Query")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| self.ui(ui, datasets));
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut Ui, datasets: &mut Datasets) {
+        let run_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter);
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Tables:");
+            let names: Vec<String> = datasets.names().map(quote).collect();
+            ui.weak(names.join(", "));
+        });
+
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .code_editor()
+                .desired_rows(6)
+                .desired_width(f32::INFINITY),
+        );
+
+        let run = ui
+            .horizontal(|ui| {
+                let clicked = ui
+                    .add(
+                        egui::Button::new("▶ Run")
+                            .shortcut_text(ui.ctx().format_shortcut(&run_shortcut)),
+                    )
+                    .clicked();
+                ui.label("Result name:");
+                ui.text_edit_singleline(&mut self.name);
+                clicked
+            })
+            .inner;
+        if run || ui.input_mut(|i| i.consume_shortcut(&run_shortcut)) {
+            let name = match self.name.trim() {
+                "" => "Result",
+                name => name,
+            };
+            self.error = match run_query(&self.text, name, datasets) {
+                Ok(table) => {
+                    datasets.add(table, Category::Result);
+                    None
+                }
+                Err(err) => Some(err),
+            };
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        self.saved_ui(ui, datasets);
+
+        ui.collapsing("Syntax", |ui| {
+            ui.label(egui::RichText::new(SYNTAX).monospace().small());
+        });
+    }
+
+    /// Save the query under its result name, and open saved ones.
+    fn saved_ui(&mut self, ui: &mut Ui, datasets: &mut Datasets) {
+        let name = self.name.trim().to_owned();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("💾 Save query"))
+                .on_hover_text("Keep this query under its result name")
+                .clicked()
+            {
+                datasets.save_query(&name, &self.text);
+            }
+
+            let mut opened = None;
+            let mut removed = None;
+            ui.add_enabled_ui(!datasets.queries().is_empty(), |ui| {
+                ui.menu_button("📂 Saved queries", |ui| {
+                    for (name, text) in datasets.queries() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                removed = Some(name.clone());
+                            }
+                            if ui.button(name).on_hover_text(text).clicked() {
+                                opened = Some((name.clone(), text.clone()));
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            });
+            if let Some((name, text)) = opened {
+                self.name = name;
+                self.text = text;
+                self.error = None;
+            }
+            if let Some(name) = removed {
+                datasets.remove_query(&name);
+            }
+        });
+    }
+}
+
+/// Parse `text`, load the tables it reads, and run it.
+fn run_query(text: &str, name: &str, datasets: &mut Datasets) -> Result<Table, String> {
+    let query = query::parse(text).map_err(|err| err.to_string())?;
+    // The names as written in the query, to the names of the tables
+    let mut names = BTreeMap::new();
+    for table in query.table_names() {
+        if let Some(found) = datasets.load(table) {
+            if datasets.is_loading(&found) {
+                return Err(format!(
+                    "{found:?} is still loading; run the query again in a moment"
+                ));
+            }
+            names.insert(table.to_owned(), found);
+        }
+    }
+    let datasets = &*datasets;
+    query::run(&query, |table| datasets.table(names.get(table)?), name)
+        .map_err(|err: QueryError| err.to_string())
+}
+
+/// A table name as it is written in a query.
+fn quote(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if plain {
+        name.to_owned()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
@@ -0,0 +1,299 @@
+//! A small key-value store in a single file, which is only ever appended to.
+//!
+//! Every record is checked with a CRC, so that one torn by a crash is dropped the next time
+//! the file is opened. A damaged record anywhere else makes the file fail to open, rather
+//! than losing the good records after it. Once most of the file is overwritten or deleted
+//! records, it is rewritten with only the live ones.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"POCKETDB";
+const VERSION: u32 = 1;
+const HEADER_LEN: u64 = 12;
+
+const PUT: u8 = 1;
+const DELETE: u8 = 2;
+
+/// Kind, key length, value length and value CRC.
+const RECORD_HEADER_LEN: usize = 1 + 4 + 8 + 4;
+
+/// Longer keys are refused, so that a damaged key length isn't taken for a torn record.
+const MAX_KEY_LEN: usize = 1 << 16;
+
+/// Rewrite the file once at least this many bytes are dead, and more than are live.
+const COMPACT_AFTER: u64 = 1 << 20;
+
+/// Where the value of a key is.
+#[derive(Clone, Copy)]
+struct Entry {
+    offset: u64,
+    len: u64,
+    crc: u32,
+    /// The length of the whole record, which is dead once overwritten or deleted.
+    record_len: u64,
+}
+
+pub struct Store {
+    path: PathBuf,
+    file: File,
+    index: BTreeMap<String, Entry>,
+    /// The bytes of overwritten and deleted records.
+    dead: u64,
+    /// The end of the last record.
+    len: u64,
+}
+
+impl Store {
+    /// Open the store at `path`, creating it if needed.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        if file.metadata()?.len() == 0 {
+            file.write_all(MAGIC)?;
+            file.write_all(&VERSION.to_le_bytes())?;
+            file.sync_all()?;
+        } else {
+            let mut header = [0; HEADER_LEN as usize];
+            file.read_exact(&mut header)?;
+            let version = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+            if &header[..8] != MAGIC || version != VERSION {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} is not a database this app can read", path.display()),
+                ));
+            }
+        }
+
+        let mut store = Self {
+            path: path.to_owned(),
+            file,
+            index: BTreeMap::new(),
+            dead: 0,
+            len: HEADER_LEN,
+        };
+        store.scan()?;
+        Ok(store)
+    }
+
+    /// Read the keys of all the records, and drop a torn one at the end.
+    ///
+    /// # Errors
+    /// If a record is damaged, other than by being cut short at the end of the file.
+    fn scan(&mut self) -> io::Result<()> {
+        let file_len = self.file.metadata()?.len();
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(HEADER_LEN))?;
+        let mut offset = HEADER_LEN;
+        loop {
+            let (kind, key, entry) = match read_record(&mut reader, offset, file_len)? {
+                Record::Good(kind, key, entry) => (kind, key, entry),
+                Record::End | Record::Torn => break,
+                Record::Damaged => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{} is damaged at byte {offset}", self.path.display()),
+                    ));
+                }
+            };
+            reader.seek_relative(entry.len as i64)?;
+            let replaced = if kind == PUT {
+                self.index.insert(key, entry)
+            } else {
+                self.dead += entry.record_len;
+                self.index.remove(&key)
+            };
+            self.dead += replaced.map_or(0, |entry| entry.record_len);
+            offset += entry.record_len;
+        }
+        if file_len > offset {
+            log::warn!(
+                "Dropping {} bytes of a damaged record at the end of {}",
+                file_len - offset,
+                self.path.display()
+            );
+            self.file.set_len(offset)?;
+        }
+        self.len = offset;
+        Ok(())
+    }
+
+    /// The keys starting with `prefix`, in order.
+    pub fn keys<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.index
+            .range(prefix.to_owned()..)
+            .map(|(key, _)| key.as_str())
+            .take_while(move |key| key.starts_with(prefix))
+    }
+
+    pub fn get(&mut self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let Some(entry) = self.index.get(key).copied() else {
+            return Ok(None);
+        };
+        let mut value = vec![0; entry.len as usize];
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        self.file.read_exact(&mut value)?;
+        if crc32fast::hash(&value) != entry.crc {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("The stored value of {key:?} is damaged"),
+            ));
+        }
+        Ok(Some(value))
+    }
+
+    pub fn put(&mut self, key: &str, value: &[u8]) -> io::Result<()> {
+        let entry = self.append(PUT, key, value)?;
+        if let Some(replaced) = self.index.insert(key.to_owned(), entry) {
+            self.dead += replaced.record_len;
+        }
+        self.compact_if_needed()
+    }
+
+    pub fn delete(&mut self, key: &str) -> io::Result<()> {
+        let Some(deleted) = self.index.remove(key) else {
+            return Ok(());
+        };
+        let tombstone = self.append(DELETE, key, &[])?;
+        self.dead += deleted.record_len + tombstone.record_len;
+        self.compact_if_needed()
+    }
+
+    /// Write a record at the end of the file, and wait for it to be on disk.
+    fn append(&mut self, kind: u8, key: &str, value: &[u8]) -> io::Result<Entry> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Keys can't be longer than {MAX_KEY_LEN} bytes"),
+            ));
+        }
+        let crc = crc32fast::hash(value);
+        let mut header = Vec::with_capacity(RECORD_HEADER_LEN + key.len() + 4);
+        header.push(kind);
+        header.extend((key.len() as u32).to_le_bytes());
+        header.extend((value.len() as u64).to_le_bytes());
+        header.extend(crc.to_le_bytes());
+        header.extend(key.as_bytes());
+        header.extend(crc32fast::hash(&header).to_le_bytes());
+
+        self.file.seek(SeekFrom::Start(self.len))?;
+        self.file.write_all(&header)?;
+        self.file.write_all(value)?;
+        self.file.sync_data()?;
+
+        let entry = Entry {
+            offset: self.len + header.len() as u64,
+            len: value.len() as u64,
+            crc,
+            record_len: (header.len() + value.len()) as u64,
+        };
+        self.len += entry.record_len;
+        Ok(entry)
+    }
+
+    fn compact_if_needed(&mut self) -> io::Result<()> {
+        let live = self.len - HEADER_LEN - self.dead;
+        if self.dead >= COMPACT_AFTER && self.dead > live {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the file with only the live records.
+    fn compact(&mut self) -> io::Result<()> {
+        let path = self.path.with_extension("compacting");
+        let _ = fs::remove_file(&path);
+        let mut compacted = Self::open(&path)?;
+        let keys: Vec<String> = self.index.keys().cloned().collect();
+        for key in keys {
+            if let Some(value) = self.get(&key)? {
+                compacted.append(PUT, &key, &value)?;
+            }
+        }
+        drop(compacted);
+        fs::rename(&path, &self.path)?;
+        log::debug!(
+            "Compacted {}, dropping {} bytes",
+            self.path.display(),
+            self.dead
+        );
+        *self = Self::open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// What [`read_record`] found.
+enum Record {
+    Good(u8, String, Entry),
+    /// The end of the file.
+    End,
+    /// The start of a record, cut short by the end of the file, as by a crash while writing it.
+    Torn,
+    /// A record that is damaged.
+    Damaged,
+}
+
+/// The record at `offset`, which `reader` is at.
+fn read_record(reader: &mut impl Read, offset: u64, file_len: u64) -> io::Result<Record> {
+    let remaining = file_len - offset;
+    if remaining == 0 {
+        return Ok(Record::End);
+    }
+    let mut header = [0; RECORD_HEADER_LEN];
+    if remaining < RECORD_HEADER_LEN as u64 {
+        return Ok(Record::Torn);
+    }
+    reader.read_exact(&mut header)?;
+    let kind = header[0];
+    let key_len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    let mut len = [0; 8];
+    len.copy_from_slice(&header[5..13]);
+    let len = u64::from_le_bytes(len);
+    let crc = u32::from_le_bytes([header[13], header[14], header[15], header[16]]);
+    if ![PUT, DELETE].contains(&kind) || key_len > MAX_KEY_LEN {
+        return Ok(Record::Damaged);
+    }
+
+    let value_offset = offset + (RECORD_HEADER_LEN + key_len + 4) as u64;
+    if value_offset > file_len {
+        return Ok(Record::Torn);
+    }
+    let mut key = vec![0; key_len];
+    reader.read_exact(&mut key)?;
+    let mut header_crc = [0; 4];
+    reader.read_exact(&mut header_crc)?;
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&header);
+    hasher.update(&key);
+    if hasher.finalize() != u32::from_le_bytes(header_crc) {
+        return Ok(Record::Damaged);
+    }
+    let Ok(key) = String::from_utf8(key) else {
+        return Ok(Record::Damaged);
+    };
+
+    // The header is good, so only a crash can have cut the value short
+    let Some(end) = value_offset.checked_add(len) else {
+        return Ok(Record::Damaged);
+    };
+    if end > file_len {
+        return Ok(Record::Torn);
+    }
+    let entry = Entry {
+        offset: value_offset,
+        len,
+        crc,
+        record_len: end - offset,
+    };
+    Ok(Record::Good(kind, key, entry))
+}
@@ -0,0 +1,116 @@
+//! Keeping imported tables, query results and saved queries between runs.
+//!
+//! On native they are kept in a single file next to the rest of the app's state,
+//! and on the web in the browser's `IndexedDB`.
+//! On the web everything finishes later, so what is loaded comes back as [`Event`]s.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod file;
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+
+use super::datasets::Category;
+use super::table::Table;
+
+#[cfg(not(target_arch = "wasm32"))]
+use native::Database;
+#[cfg(target_arch = "wasm32")]
+use web::Database;
+
+/// Something the storage is done with.
+pub enum Event {
+    /// What is stored: the tables, not loaded yet, and the saved queries by name.
+    Listed {
+        tables: Vec<(Category, String)>,
+        queries: BTreeMap<String, String>,
+    },
+    Loaded(Table),
+    /// The table called `name` could not be loaded.
+    NotLoaded {
+        name: String,
+        error: String,
+    },
+    Failed(String),
+}
+
+/// Where tables and queries are kept between runs.
+pub struct Storage {
+    database: Database,
+    events: mpsc::Receiver<Event>,
+}
+
+impl Storage {
+    /// Open the storage, and list what is in it.
+    pub fn open(ctx: &egui::Context) -> Self {
+        let (sender, events) = mpsc::channel();
+        let mut database = Database::open(Sender {
+            sender,
+            ctx: ctx.clone(),
+        });
+        database.list();
+        Self { database, events }
+    }
+
+    /// What the storage is done with since last asked.
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.events.try_iter()
+    }
+
+    /// Load the table called `name`, which comes back as [`Event::Loaded`] or [`Event::NotLoaded`].
+    pub fn load(&mut self, name: &str) {
+        self.database.load(name);
+    }
+
+    pub fn save_table(&mut self, table: &Table, category: Category) {
+        self.database.save_table(table, category);
+    }
+
+    pub fn remove_table(&mut self, name: &str) {
+        self.database.remove_table(name);
+    }
+
+    pub fn save_query(&mut self, name: &str, text: &str) {
+        self.database.save_query(name, text);
+    }
+
+    pub fn remove_query(&mut self, name: &str) {
+        self.database.remove_query(name);
+    }
+}
+
+/// Sends [`Event`]s, and wakes up the UI to handle them.
+#[derive(Clone)]
+struct Sender {
+    sender: mpsc::Sender<Event>,
+    ctx: egui::Context,
+}
+
+impl Sender {
+    fn send(&self, event: Event) {
+        if let Event::Failed(error) | Event::NotLoaded { error, .. } = &event {
+            log::warn!("{error}");
+        }
+        self.sender.send(event).ok();
+        self.ctx.request_repaint();
+    }
+
+    /// Tell of an error doing `what`, if any.
+    fn report<E: std::fmt::Display>(&self, what: &str, result: Result<(), E>) {
+        if let Err(err) = result {
+            self.send(Event::Failed(format!("Failed to {what}: {err}")));
+        }
+    }
+}
+
+fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, bincode::Error> {
+    bincode::serialize(value)
+}
+
+fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, bincode::Error> {
+    bincode::deserialize(bytes)
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+use super::file::Store;
+use super::{decode, encode, Category, Event, Sender, Table};
+
+/// The keys of the tables start with this, and then their names.
+const TABLES: &str = "table/";
+/// The categories of the tables, so they can be listed without reading them.
+const CATALOG: &str = "catalog/";
+const QUERIES: &str = "query/";
+
+const FILE_NAME: &str = "database.pocketdb";
+
+pub struct Database {
+    /// `None` if it could not be opened.
+    store: Option<Store>,
+    sender: Sender,
+}
+
+impl Database {
+    pub fn open(sender: Sender) -> Self {
+        let store = if let Some(dir) = eframe::storage_dir(crate::APP_NAME) {
+            let path = dir.join(FILE_NAME);
+            match Store::open(&path) {
+                Ok(store) => {
+                    log::debug!("Opened {}", path.display());
+                    Some(store)
+                }
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                    // Keep what can be rescued from it, and start over
+                    let corrupt = dir.join(format!("{FILE_NAME}.corrupt"));
+                    let error = format!(
+                        "Failed to open {}: {err}. It is kept as {}",
+                        path.display(),
+                        corrupt.display()
+                    );
+                    sender.send(Event::Failed(error));
+                    let reopened = fs::rename(&path, &corrupt).and_then(|()| Store::open(&path));
+                    match reopened {
+                        Ok(store) => Some(store),
+                        Err(err) => {
+                            let error = format!("Failed to start over: {err}");
+                            sender.send(Event::Failed(error));
+                            None
+                        }
+                    }
+                }
+                Err(err) => {
+                    let error = format!("Failed to open {}: {err}", path.display());
+                    sender.send(Event::Failed(error));
+                    None
+                }
+            }
+        } else {
+            let error = "There is nowhere to keep tables on this system".to_owned();
+            sender.send(Event::Failed(error));
+            None
+        };
+        Self { store, sender }
+    }
+
+    pub fn list(&mut self) {
+        let Some(store) = &mut self.store else {
+            return;
+        };
+        let mut tables = vec![];
+        let keys: Vec<String> = store.keys(CATALOG).map(str::to_owned).collect();
+        for key in keys {
+            let category = store
+                .get(&key)
+                .ok()
+                .flatten()
+                .and_then(|bytes| decode(&bytes).ok());
+            if let Some(category) = category {
+                tables.push((category, key[CATALOG.len()..].to_owned()));
+            }
+        }
+        let mut queries = BTreeMap::new();
+        let keys: Vec<String> = store.keys(QUERIES).map(str::to_owned).collect();
+        for key in keys {
+            if let Ok(Some(text)) = store.get(&key) {
+                let text = String::from_utf8_lossy(&text).into_owned();
+                queries.insert(key[QUERIES.len()..].to_owned(), text);
+            }
+        }
+        self.sender.send(Event::Listed { tables, queries });
+    }
+
+    pub fn load(&mut self, name: &str) {
+        let Some(store) = &mut self.store else {
+            self.sender.send(Event::NotLoaded {
+                name: name.to_owned(),
+                error: format!("Failed to load {name:?}: there is no storage"),
+            });
+            return;
+        };
+        let result = match store.get(&format!("{TABLES}{name}")) {
+            Ok(Some(bytes)) => decode::<Table>(&bytes).map_err(|err| err.to_string()),
+            Ok(None) => Err("it is not stored".to_owned()),
+            Err(err) => Err(err.to_string()),
+        };
+        self.sender.send(match result {
+            Ok(mut table) => {
+                name.clone_into(&mut table.name);
+                Event::Loaded(table)
+            }
+            Err(err) => Event::NotLoaded {
+                name: name.to_owned(),
+                error: format!("Failed to load {name:?}: {err}"),
+            },
+        });
+    }
+
+    pub fn save_table(&mut self, table: &Table, category: Category) {
+        let Some(store) = &mut self.store else {
+            return;
+        };
+        let name = &table.name;
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            store.put(&format!("{TABLES}{name}"), &encode(table)?)?;
+            store.put(&format!("{CATALOG}{name}"), &encode(&category)?)?;
+            Ok(())
+        })();
+        self.sender.report(&format!("save {name:?}"), result);
+    }
+
+    pub fn remove_table(&mut self, name: &str) {
+        let Some(store) = &mut self.store else {
+            return;
+        };
+        let result = store
+            .delete(&format!("{CATALOG}{name}"))
+            .and_then(|()| store.delete(&format!("{TABLES}{name}")));
+        self.sender.report(&format!("remove {name:?}"), result);
+    }
+
+    pub fn save_query(&mut self, name: &str, text: &str) {
+        let Some(store) = &mut self.store else {
+            return;
+        };
+        let result = store.put(&format!("{QUERIES}{name}"), text.as_bytes());
+        self.sender
+            .report(&format!("save the query {name:?}"), result);
+    }
+
+    pub fn remove_query(&mut self, name: &str) {
+        let Some(store) = &mut self.store else {
+            return;
+        };
+        let result = store.delete(&format!("{QUERIES}{name}"));
+        self.sender
+            .report(&format!("remove the query {name:?}"), result);
+    }
+}
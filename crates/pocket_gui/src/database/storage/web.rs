@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use eframe::wasm_bindgen::{closure::Closure, JsCast as _, JsValue};
+use web_sys::{IdbDatabase, IdbRequest, IdbTransactionMode};
+
+use super::{decode, encode, Category, Event, Sender, Table};
+
+const VERSION: u32 = 1;
+
+/// The tables, by name.
+const TABLES: &str = "tables";
+/// The categories of the tables, so they can be listed without reading them.
+const CATALOG: &str = "catalog";
+/// The text of the saved queries, by name.
+const QUERIES: &str = "queries";
+
+/// The tables and queries in the browser's `IndexedDB`.
+///
+/// Every call opens the database and returns; its events are sent once done.
+pub struct Database {
+    sender: Sender,
+}
+
+impl Database {
+    pub fn open(sender: Sender) -> Self {
+        Self { sender }
+    }
+
+    pub fn list(&mut self) {
+        let sender = self.sender.clone();
+        self.spawn("list the stored tables", async move {
+            let database = open().await?;
+            let stores = js_sys::Array::of2(&CATALOG.into(), &QUERIES.into());
+            let transaction = database.transaction_with_str_sequence(&stores)?;
+
+            // Every request is made before waiting, or the transaction would be over
+            let catalog = transaction.object_store(CATALOG)?;
+            let queries = transaction.object_store(QUERIES)?;
+            let requests = [
+                catalog.get_all_keys()?,
+                catalog.get_all()?,
+                queries.get_all_keys()?,
+                queries.get_all()?,
+            ];
+            let waits: Vec<_> = requests.iter().map(wait).collect();
+            let mut results = vec![];
+            for wait in waits {
+                results.push(js_sys::Array::from(&wait.await?));
+            }
+
+            let tables = results[0]
+                .iter()
+                .zip(results[1].iter())
+                .filter_map(|(name, category)| {
+                    let category: Category = decode(&bytes(&category)).ok()?;
+                    Some((category, name.as_string()?))
+                })
+                .collect();
+            let queries: BTreeMap<String, String> = results[2]
+                .iter()
+                .zip(results[3].iter())
+                .filter_map(|(name, text)| Some((name.as_string()?, text.as_string()?)))
+                .collect();
+
+            sender.send(Event::Listed { tables, queries });
+            Ok(())
+        });
+    }
+
+    pub fn load(&mut self, name: &str) {
+        let sender = self.sender.clone();
+        let name = name.to_owned();
+        wasm_bindgen_futures::spawn_local(async move {
+            sender.send(match load(&name).await {
+                Ok(table) => Event::Loaded(table),
+                Err(err) => Event::NotLoaded {
+                    error: format!("Failed to load {name:?}: {}", message(&err)),
+                    name,
+                },
+            });
+        });
+    }
+
+    pub fn save_table(&mut self, table: &Table, category: Category) {
+        let name = table.name.clone();
+        let encoded = encode(table).and_then(|table| Ok((table, encode(&category)?)));
+        let (table, category) = match encoded {
+            Ok(encoded) => encoded,
+            Err(err) => {
+                self.sender.report(&format!("save {name:?}"), Err(err));
+                return;
+            }
+        };
+        self.spawn(&format!("save {name:?}"), async move {
+            let database = open().await?;
+            let stores = js_sys::Array::of2(&TABLES.into(), &CATALOG.into());
+            let transaction = database
+                .transaction_with_str_sequence_and_mode(&stores, IdbTransactionMode::Readwrite)?;
+            let key = JsValue::from(name.as_str());
+            let table = js_sys::Uint8Array::from(table.as_slice());
+            let category = js_sys::Uint8Array::from(category.as_slice());
+            let put_table = transaction
+                .object_store(TABLES)?
+                .put_with_key(&table, &key)?;
+            let put_category = transaction
+                .object_store(CATALOG)?
+                .put_with_key(&category, &key)?;
+            let (put_table, put_category) = (wait(&put_table), wait(&put_category));
+            put_table.await?;
+            put_category.await?;
+            Ok(())
+        });
+    }
+
+    pub fn remove_table(&mut self, name: &str) {
+        let name = name.to_owned();
+        self.spawn(&format!("remove {name:?}"), async move {
+            let database = open().await?;
+            let stores = js_sys::Array::of2(&TABLES.into(), &CATALOG.into());
+            let transaction = database
+                .transaction_with_str_sequence_and_mode(&stores, IdbTransactionMode::Readwrite)?;
+            let key = JsValue::from(name.as_str());
+            let delete_category = transaction.object_store(CATALOG)?.delete(&key)?;
+            let delete_table = transaction.object_store(TABLES)?.delete(&key)?;
+            let (delete_category, delete_table) = (wait(&delete_category), wait(&delete_table));
+            delete_category.await?;
+            delete_table.await?;
+            Ok(())
+        });
+    }
+
+    pub fn save_query(&mut self, name: &str, text: &str) {
+        let (name, text) = (name.to_owned(), text.to_owned());
+        self.spawn(&format!("save the query {name:?}"), async move {
+            let store = open_store(QUERIES).await?;
+            wait(&store.put_with_key(&text.into(), &name.into())?).await?;
+            Ok(())
+        });
+    }
+
+    pub fn remove_query(&mut self, name: &str) {
+        let name = name.to_owned();
+        self.spawn(&format!("remove the query {name:?}"), async move {
+            let store = open_store(QUERIES).await?;
+            wait(&store.delete(&name.into())?).await?;
+            Ok(())
+        });
+    }
+
+    /// Run `task` in the background, and tell if it fails.
+    fn spawn(&self, what: &str, task: impl Future<Output = Result<(), JsValue>> + 'static) {
+        let sender = self.sender.clone();
+        let what = what.to_owned();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = task.await.map_err(|err| message(&err));
+            sender.report(&what, result);
+        });
+    }
+}
+
+async fn load(name: &str) -> Result<Table, JsValue> {
+    let database = open().await?;
+    let store = database
+        .transaction_with_str(TABLES)?
+        .object_store(TABLES)?;
+    let value = wait(&store.get(&name.into())?).await?;
+    if value.is_undefined() {
+        return Err("it is not stored".into());
+    }
+    let mut table: Table = decode(&bytes(&value)).map_err(|err| err.to_string())?;
+    name.clone_into(&mut table.name);
+    Ok(table)
+}
+
+/// The message of a JavaScript error.
+fn message(err: &JsValue) -> String {
+    err.as_string()
+        .or_else(|| err.dyn_ref::<js_sys::Error>().map(|e| e.message().into()))
+        .unwrap_or_else(|| format!("{err:?}"))
+}
+
+/// Open the database, creating the object stores the first time.
+async fn open() -> Result<IdbDatabase, JsValue> {
+    let factory = web_sys::window()
+        .and_then(|window| window.indexed_db().ok().flatten())
+        .ok_or("IndexedDB is not available")?;
+    let request = factory.open_with_u32(crate::APP_NAME, VERSION)?;
+    let on_upgrade = {
+        let request = request.clone();
+        Closure::once_into_js(move || {
+            let Ok(database) = request.result() else {
+                return;
+            };
+            let database: IdbDatabase = database.unchecked_into();
+            for store in [TABLES, CATALOG, QUERIES] {
+                if !database.object_store_names().contains(store) {
+                    if let Err(err) = database.create_object_store(store) {
+                        log::warn!("Failed to create the object store {store:?}: {err:?}");
+                    }
+                }
+            }
+        })
+    };
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+    Ok(wait(&request).await?.unchecked_into())
+}
+
+/// One object store, to read and write.
+async fn open_store(name: &str) -> Result<web_sys::IdbObjectStore, JsValue> {
+    open()
+        .await?
+        .transaction_with_str_and_mode(name, IdbTransactionMode::Readwrite)?
+        .object_store(name)
+}
+
+/// Wait for `request` to be done, for its result.
+///
+/// It is listened to right away, so requests can be waited for one after another.
+fn wait(request: &IdbRequest) -> wasm_bindgen_futures::JsFuture {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let on_success = {
+            let request = request.clone();
+            Closure::once_into_js(move || {
+                let result = request.result().unwrap_or(JsValue::UNDEFINED);
+                resolve.call1(&JsValue::UNDEFINED, &result).ok();
+            })
+        };
+        let on_error = {
+            let request = request.clone();
+            Closure::once_into_js(move || {
+                let error = request
+                    .error()
+                    .ok()
+                    .flatten()
+                    .map_or(JsValue::NULL, Into::into);
+                reject.call1(&JsValue::UNDEFINED, &error).ok();
+            })
+        };
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+}
+
+/// The bytes stored as a `Uint8Array`.
+fn bytes(value: &JsValue) -> Vec<u8> {
+    js_sys::Uint8Array::new(value).to_vec()
+}
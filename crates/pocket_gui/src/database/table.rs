@@ -0,0 +1,283 @@
+//! Typed, column-oriented tables.
+
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+
+/// The type of the values in a [`Column`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum DataType {
+    String,
+    Integer,
+    Float,
+    Date,
+    Boolean,
+}
+
+impl DataType {
+    pub const ALL: [Self; 5] = [
+        Self::String,
+        Self::Integer,
+        Self::Float,
+        Self::Date,
+        Self::Boolean,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Date => "date",
+            Self::Boolean => "boolean",
+        }
+    }
+
+    /// Can the values be plotted as numbers?
+    pub fn is_numeric(self) -> bool {
+        matches!(self, Self::Integer | Self::Float)
+    }
+
+    /// A short tag for column headers.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::String => "abc",
+            Self::Integer => "123",
+            Self::Float => "1.2",
+            Self::Date => "📅",
+            Self::Boolean => "☑",
+        }
+    }
+}
+
+/// One cell of a [`Table`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'a> {
+    Null,
+    String(&'a str),
+    Integer(i64),
+    Float(f64),
+    Date(NaiveDate),
+    Boolean(bool),
+}
+
+impl std::fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => Ok(()),
+            Self::String(value) => value.fmt(f),
+            Self::Integer(value) => value.fmt(f),
+            Self::Float(value) => value.fmt(f),
+            Self::Date(value) => value.fmt(f),
+            Self::Boolean(value) => value.fmt(f),
+        }
+    }
+}
+
+/// The values of a [`Column`], `None` where missing.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ColumnData {
+    String(Vec<Option<String>>),
+    Integer(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Date(Vec<Option<NaiveDate>>),
+    Boolean(Vec<Option<bool>>),
+}
+
+impl ColumnData {
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::String(_) => DataType::String,
+            Self::Integer(_) => DataType::Integer,
+            Self::Float(_) => DataType::Float,
+            Self::Date(_) => DataType::Date,
+            Self::Boolean(_) => DataType::Boolean,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::String(values) => values.len(),
+            Self::Integer(values) => values.len(),
+            Self::Float(values) => values.len(),
+            Self::Date(values) => values.len(),
+            Self::Boolean(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The value in `row`, which must be in range.
+    pub fn get(&self, row: usize) -> Value<'_> {
+        fn value<T: Copy>(
+            values: &[Option<T>],
+            row: usize,
+            f: impl Fn(T) -> Value<'static>,
+        ) -> Value<'static> {
+            values[row].map_or(Value::Null, f)
+        }
+        match self {
+            Self::String(values) => values[row].as_deref().map_or(Value::Null, Value::String),
+            Self::Integer(values) => value(values, row, Value::Integer),
+            Self::Float(values) => value(values, row, Value::Float),
+            Self::Date(values) => value(values, row, Value::Date),
+            Self::Boolean(values) => value(values, row, Value::Boolean),
+        }
+    }
+
+    /// The value in `row` as a number, or `None` if missing or not numeric.
+    pub fn number(&self, row: usize) -> Option<f64> {
+        match self {
+            Self::Integer(values) => values[row].map(|value| value as f64),
+            Self::Float(values) => values[row],
+            Self::String(_) | Self::Date(_) | Self::Boolean(_) => None,
+        }
+    }
+
+    /// Compare the values in two rows, with missing values last either way.
+    pub fn cmp_rows(&self, a: usize, b: usize, descending: bool) -> Ordering {
+        fn cmp<T>(
+            a: Option<T>,
+            b: Option<T>,
+            descending: bool,
+            f: impl Fn(&T, &T) -> Ordering,
+        ) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) if descending => f(&b, &a),
+                (Some(a), Some(b)) => f(&a, &b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+        match self {
+            Self::String(values) => {
+                cmp(values[a].as_ref(), values[b].as_ref(), descending, Ord::cmp)
+            }
+            Self::Integer(values) => cmp(values[a], values[b], descending, Ord::cmp),
+            Self::Float(values) => cmp(values[a], values[b], descending, f64::total_cmp),
+            Self::Date(values) => cmp(values[a], values[b], descending, Ord::cmp),
+            Self::Boolean(values) => cmp(values[a], values[b], descending, Ord::cmp),
+        }
+    }
+
+    /// The number of missing values.
+    pub fn null_count(&self) -> usize {
+        fn count<T>(values: &[Option<T>]) -> usize {
+            values.iter().filter(|value| value.is_none()).count()
+        }
+        match self {
+            Self::String(values) => count(values),
+            Self::Integer(values) => count(values),
+            Self::Float(values) => count(values),
+            Self::Date(values) => count(values),
+            Self::Boolean(values) => count(values),
+        }
+    }
+}
+
+/// A named column of values of one type.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Column {
+    pub name: String,
+    pub data: ColumnData,
+}
+
+impl Column {
+    pub fn new(name: impl Into<String>, data: ColumnData) -> Self {
+        Self {
+            name: name.into(),
+            data,
+        }
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.data.data_type()
+    }
+}
+
+/// Rows of values, stored column by column.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawTable"))]
+pub struct Table {
+    pub name: String,
+    columns: Vec<Column>,
+    num_rows: usize,
+}
+
+/// A [`Table`] as stored, checked before it becomes one.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawTable {
+    name: String,
+    columns: Vec<Column>,
+    num_rows: usize,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawTable> for Table {
+    type Error = String;
+
+    fn try_from(raw: RawTable) -> Result<Self, Self::Error> {
+        let RawTable {
+            name,
+            columns,
+            num_rows,
+        } = raw;
+        let expected = columns.first().map_or(0, |column| column.data.len());
+        if num_rows != expected || columns.iter().any(|column| column.data.len() != num_rows) {
+            return Err(format!(
+                "the columns of table {name:?} are not all {num_rows} rows long"
+            ));
+        }
+        Ok(Self {
+            name,
+            columns,
+            num_rows,
+        })
+    }
+}
+
+impl Table {
+    /// # Panics
+    /// If the columns are not all of the same length.
+    pub fn new(name: impl Into<String>, columns: Vec<Column>) -> Self {
+        let num_rows = columns.first().map_or(0, |column| column.data.len());
+        assert!(
+            columns.iter().all(|column| column.data.len() == num_rows),
+            "All the columns of a table must have the same length"
+        );
+        Self {
+            name: name.into(),
+            columns,
+            num_rows,
+        }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// The row indices in the order of `column`, with missing values last either way.
+    pub fn sorted_rows(&self, column: usize, descending: bool) -> Vec<usize> {
+        let data = &self.columns[column].data;
+        let mut rows: Vec<usize> = (0..self.num_rows).collect();
+        // Ties keep their original order
+        rows.sort_unstable_by(|&a, &b| data.cmp_rows(a, b, descending).then(a.cmp(&b)));
+        rows
+    }
+}
@@ -0,0 +1,434 @@
+//! A window browsing one [`Table`].
+
+use std::fmt::Write as _;
+use std::ops::RangeInclusive;
+
+use egui::{Align, Layout, RichText, Ui};
+
+use super::export::{self, Format};
+use super::table::{Table, Value};
+
+/// Keeps the rows with a number in a range, e.g. from brushing a chart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeFilter {
+    /// The numeric column, or `None` for the row number as in the `#` column.
+    pub column: Option<usize>,
+    pub range: RangeInclusive<f64>,
+}
+
+impl RangeFilter {
+    fn contains(&self, table: &Table, row: usize) -> bool {
+        let number = match self.column {
+            Some(column) => table.columns()[column].data.number(row),
+            None => Some((row + 1) as f64),
+        };
+        number.is_some_and(|number| self.range.contains(&number))
+    }
+
+    fn describe(&self, table: &Table) -> String {
+        let name = self
+            .column
+            .map_or("#", |column| table.columns()[column].name.as_str());
+        format!(
+            "{} ≤ {name} ≤ {}",
+            format_number(*self.range.start()),
+            format_number(*self.range.end())
+        )
+    }
+}
+
+/// A number rounded for a label, e.g. on a chart axis.
+pub fn format_number(number: f64) -> String {
+    if number != 0.0 && !(1e-3..1e7).contains(&number.abs()) {
+        format!("{number:.2e}")
+    } else {
+        let rounded = (number * 1000.0).round() / 1000.0;
+        // No "-0"
+        format!("{}", rounded + 0.0)
+    }
+}
+
+/// The values of one column as lower-cased text, made once for filtering by text.
+///
+/// All values are in one string, so a million rows make two allocations, not a million.
+struct SearchText {
+    text: String,
+    /// Where the text of each row ends.
+    ends: Vec<usize>,
+}
+
+impl SearchText {
+    fn new(table: &Table, column: usize) -> Self {
+        let data = &table.columns()[column].data;
+        let mut text = String::new();
+        let mut ends = Vec::with_capacity(table.num_rows());
+        let mut value_text = String::new();
+        for row in 0..table.num_rows() {
+            // Missing values are written as nothing
+            value_text.clear();
+            write!(value_text, "{}", data.get(row)).ok();
+            // Lower-casing can change the length, so one value at a time
+            text.extend(value_text.chars().flat_map(char::to_lowercase));
+            ends.push(text.len());
+        }
+        Self { text, ends }
+    }
+
+    fn get(&self, row: usize) -> &str {
+        let start = if row == 0 { 0 } else { self.ends[row - 1] };
+        &self.text[start..self.ends[row]]
+    }
+}
+
+/// A [`Table`] shown in its own window, sortable by any column and filterable by text
+/// and by ranges of numbers.
+///
+/// Only the visible rows are laid out, so tables of millions of rows scroll smoothly.
+pub struct TableWindow {
+    table: Table,
+    /// The column sorted by, and whether descending.
+    sort: Option<(usize, bool)>,
+    /// Only rows with a value containing this text are shown, ignoring case.
+    filter: String,
+    /// Only rows in all of these ranges are shown.
+    ranges: Vec<RangeFilter>,
+    /// The rows shown, in order, or `None` when all of them are, unsorted.
+    rows: Option<Vec<usize>>,
+    /// All rows in the order of `sort`, made when it changes.
+    sorted: Option<Vec<usize>>,
+    /// The text of each column, made when first filtered by text.
+    search: Option<Vec<SearchText>>,
+    /// The sorted rows with a value containing a filter, with that (lower-cased) filter.
+    ///
+    /// Typing more of a filter only narrows these down instead of searching every row again.
+    matched: Option<(String, Vec<usize>)>,
+    /// What became of the last export.
+    export_status: Option<String>,
+}
+
+impl TableWindow {
+    pub fn new(table: Table) -> Self {
+        Self {
+            table,
+            sort: None,
+            filter: String::new(),
+            ranges: vec![],
+            rows: None,
+            sorted: None,
+            search: None,
+            matched: None,
+            export_status: None,
+        }
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// Sort by `column`, or stop sorting with `None`.
+    pub fn sort_by(&mut self, sort: Option<(usize, bool)>) {
+        self.sort = sort;
+        self.sorted = sort.map(|(column, descending)| self.table.sorted_rows(column, descending));
+        self.matched = None;
+        self.update_rows();
+    }
+
+    /// Show only the rows with a value containing `filter`, or all with an empty one.
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.update_rows();
+    }
+
+    pub fn ranges(&self) -> &[RangeFilter] {
+        &self.ranges
+    }
+
+    /// Show only the rows in all of `ranges`, or all with none.
+    pub fn set_ranges(&mut self, ranges: Vec<RangeFilter>) {
+        if ranges != self.ranges {
+            self.ranges = ranges;
+            self.update_rows();
+        }
+    }
+
+    fn update_rows(&mut self) {
+        let filter = self.filter.trim().to_lowercase();
+        if filter.is_empty() {
+            self.matched = None;
+        }
+        if self.sort.is_none() && filter.is_empty() && self.ranges.is_empty() {
+            self.rows = None;
+            return;
+        }
+        let mut rows = if filter.is_empty() {
+            self.all_rows()
+        } else {
+            let candidates = match self.matched.take() {
+                Some((matched, rows)) if filter.contains(&matched) => rows,
+                _ => self.all_rows(),
+            };
+            let table = &self.table;
+            let search = self.search.get_or_insert_with(|| {
+                (0..table.num_columns())
+                    .map(|column| SearchText::new(table, column))
+                    .collect()
+            });
+            let rows: Vec<usize> = candidates
+                .into_iter()
+                .filter(|&row| search.iter().any(|text| text.get(row).contains(&filter)))
+                .collect();
+            self.matched = Some((filter, rows.clone()));
+            rows
+        };
+        if !self.ranges.is_empty() {
+            let table = &self.table;
+            rows.retain(|&row| self.ranges.iter().all(|range| range.contains(table, row)));
+        }
+        self.rows = Some(rows);
+    }
+
+    /// All rows, sorted.
+    fn all_rows(&self) -> Vec<usize> {
+        self.sorted
+            .clone()
+            .unwrap_or_else(|| (0..self.table.num_rows()).collect())
+    }
+
+    /// The number of rows shown.
+    fn num_shown(&self) -> usize {
+        self.rows
+            .as_ref()
+            .map_or(self.table.num_rows(), |rows| rows.len())
+    }
+
+    /// The row of the table shown at `index`.
+    fn row(&self, index: usize) -> usize {
+        self.rows.as_ref().map_or(index, |rows| rows[index])
+    }
+
+    /// The rows shown, in order.
+    pub fn shown_rows(&self) -> Vec<usize> {
+        self.rows
+            .clone()
+            .unwrap_or_else(|| (0..self.table.num_rows()).collect())
+    }
+
+    /// Save the rows shown as a file in `format`: where the user picks on native,
+    /// and as a download on the web.
+    fn export(&mut self, format: Format) {
+        let file_name = export::file_name(&self.table, format);
+        let bytes = match export::export(&self.table, &self.shown_rows(), format) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.export_status = Some(format!("Export failed: {err}"));
+                return;
+            }
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.export_status = Some(
+                match pocket_core::web::download(&file_name, format.mime(), &bytes) {
+                    Ok(()) => format!("Downloaded {file_name}"),
+                    Err(err) => format!("Download failed: {err:?}"),
+                },
+            );
+        }
+
+        #[cfg(all(not(target_arch = "wasm32"), feature = "rfd"))]
+        {
+            let Some(path) = rfd::FileDialog::new()
+                .set_file_name(&file_name)
+                .add_filter(format.name(), &[format.extension()])
+                .save_file()
+            else {
+                return;
+            };
+            self.export_status = Some(write(&path, &bytes));
+        }
+
+        #[cfg(all(not(target_arch = "wasm32"), not(feature = "rfd")))]
+        {
+            // Without a file dialog, save next to where we were started
+            self.export_status = Some(write(std::path::Path::new(&file_name), &bytes));
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(&self.table.name)
+            .id(egui::Id::new(("table_window", &self.table.name)))
+            .default_size([640.0, 400.0])
+            .open(open)
+            .resizable(true)
+            .show(ctx, |ui| self.ui(ui));
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if self.rows.is_some() && self.num_shown() != self.table.num_rows() {
+                ui.label(format!(
+                    "{} of {} rows × {} columns",
+                    self.num_shown(),
+                    self.table.num_rows(),
+                    self.table.num_columns()
+                ));
+            } else {
+                ui.label(format!(
+                    "{} rows × {} columns",
+                    self.table.num_rows(),
+                    self.table.num_columns()
+                ));
+            }
+            if self.sort.is_some() && ui.button("Unsort").clicked() {
+                self.sort_by(None);
+            }
+            if ui
+                .button("📋 Copy")
+                .on_hover_text("Copy the rows shown, in this order, as tab-separated text")
+                .clicked()
+            {
+                ui.ctx()
+                    .copy_text(export::to_tsv(&self.table, &self.shown_rows()));
+            }
+            ui.menu_button("💾 Export…", |ui| {
+                ui.weak("The rows shown, in this order");
+                for &format in Format::ALL {
+                    if ui.button(format.name()).clicked() {
+                        ui.close_menu();
+                        self.export(format);
+                    }
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.label("🔎");
+            let mut filter = self.filter.clone();
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut filter)
+                    .hint_text("Filter rows")
+                    .desired_width(160.0),
+            );
+            if response.changed() {
+                self.set_filter(filter);
+            }
+            if !self.filter.is_empty() && ui.small_button("✖").clicked() {
+                self.set_filter(String::new());
+            }
+            if !self.ranges.is_empty() {
+                let ranges: Vec<String> = self
+                    .ranges
+                    .iter()
+                    .map(|range| range.describe(&self.table))
+                    .collect();
+                ui.label(ranges.join(", "))
+                    .on_hover_text("Brushed in a chart");
+                if ui.small_button("✖").clicked() {
+                    self.set_ranges(vec![]);
+                }
+            }
+            if let Some(status) = &self.export_status {
+                ui.weak(status);
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::horizontal().show(ui, |ui| self.table_ui(ui));
+    }
+
+    fn table_ui(&mut self, ui: &mut Ui) {
+        let text_height = egui::TextStyle::Body
+            .resolve(ui.style())
+            .size
+            .max(ui.spacing().interact_size.y);
+        let row_digits = self.table.num_rows().max(1).ilog10() as f32 + 1.0;
+
+        let mut clicked = None;
+        egui_extras::TableBuilder::new(ui)
+            .id_salt(&self.table.name)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(egui_extras::Column::exact(row_digits * 8.0 + 8.0))
+            .columns(
+                egui_extras::Column::initial(120.0)
+                    .at_least(40.0)
+                    .clip(true),
+                self.table.num_columns(),
+            )
+            .min_scrolled_height(0.0)
+            .header(text_height + 4.0, |mut header| {
+                header.col(|ui| {
+                    ui.weak("#");
+                });
+                for (i, column) in self.table.columns().iter().enumerate() {
+                    header.col(|ui| {
+                        let arrow = match self.sort {
+                            Some((sorted, false)) if sorted == i => " ⏶",
+                            Some((sorted, true)) if sorted == i => " ⏷",
+                            _ => "",
+                        };
+                        let label = RichText::new(format!("{}{arrow}", column.name)).strong();
+                        let response = ui
+                            .add(egui::Label::new(label).sense(egui::Sense::click()))
+                            .on_hover_text(format!(
+                                "{} ({} missing)\nClick to sort",
+                                column.data_type().name(),
+                                column.data.null_count()
+                            ));
+                        ui.weak(column.data_type().symbol());
+                        if response.clicked() {
+                            clicked = Some(i);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(text_height, self.num_shown(), |mut row| {
+                    let index = self.row(row.index());
+                    row.col(|ui| {
+                        ui.weak((index + 1).to_string());
+                    });
+                    for column in self.table.columns() {
+                        row.col(|ui| cell_ui(ui, column.data.get(index)));
+                    }
+                });
+            });
+
+        // Ascending, descending, then back to the original order
+        if let Some(column) = clicked {
+            let sort = match self.sort {
+                Some((sorted, false)) if sorted == column => Some((column, true)),
+                Some((sorted, true)) if sorted == column => None,
+                _ => Some((column, false)),
+            };
+            self.sort_by(sort);
+        }
+    }
+}
+
+fn cell_ui(ui: &mut Ui, value: Value<'_>) {
+    match value {
+        Value::Null => {
+            ui.weak("—");
+        }
+        Value::Integer(_) | Value::Float(_) => {
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                ui.monospace(value.to_string());
+            });
+        }
+        Value::Boolean(value) => {
+            ui.label(if value { "✔" } else { "✖" });
+        }
+        Value::String(_) | Value::Date(_) => {
+            ui.label(value.to_string());
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(path: &std::path::Path, bytes: &[u8]) -> String {
+    match std::fs::write(path, bytes) {
+        Ok(()) => format!("Saved {}", path.display()),
+        Err(err) => format!("Failed to save {}: {err}", path.display()),
+    }
+}
@@ -0,0 +1,68 @@
+//! Handing files dropped on the app to the apps that can open them.
+
+use std::sync::Arc;
+
+/// An app that can open files dropped on [`crate::WrapApp`].
+pub trait FileHandler {
+    /// The MIME types this app opens, e.g. `"text/csv"`.
+    ///
+    /// Only browsers tell the MIME type of a dropped file; on native the
+    /// extension decides.
+    fn mime_types(&self) -> &'static [&'static str];
+
+    /// The file extensions this app opens, in lower case and without the dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Open the file `name` with these contents.
+    fn open_file(&mut self, ctx: &egui::Context, name: &str, bytes: Arc<[u8]>);
+
+    /// Can this app open `file`?
+    fn accepts(&self, file: &File) -> bool {
+        (!file.mime.is_empty() && self.mime_types().contains(&file.mime.as_str()))
+            || file
+                .extension()
+                .is_some_and(|extension| self.extensions().contains(&extension.as_str()))
+    }
+}
+
+/// A dropped file, read into memory.
+#[derive(Clone)]
+pub struct File {
+    pub name: String,
+    /// Empty if not known.
+    pub mime: String,
+    pub bytes: Arc<[u8]>,
+}
+
+impl File {
+    /// Read a dropped file, which on native only has a path.
+    pub fn read(file: &egui::DroppedFile) -> Option<Self> {
+        let name = match &file.path {
+            Some(path) => path.file_name()?.to_string_lossy().into_owned(),
+            None => file.name.clone(),
+        };
+        let bytes = match (&file.bytes, &file.path) {
+            (Some(bytes), _) => bytes.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            (None, Some(path)) => match std::fs::read(path) {
+                Ok(bytes) => bytes.into(),
+                Err(err) => {
+                    log::warn!("Failed to read {}: {err}", path.display());
+                    return None;
+                }
+            },
+            _ => return None,
+        };
+        Some(Self {
+            name,
+            mime: file.mime.clone(),
+            bytes,
+        })
+    }
+
+    /// The extension of the file name, in lower case.
+    pub fn extension(&self) -> Option<String> {
+        let (_, extension) = self.name.rsplit_once('.')?;
+        Some(extension.to_ascii_lowercase())
+    }
+}
@@ -1,14 +1,16 @@
 //! Demo app for egui
 #![allow(clippy::missing_errors_doc)]
 
+mod about;
 mod apps;
-mod backend_panel;
-mod frame_history;
+pub mod database;
+mod file_handler;
 mod wrap_app;
-mod database;
-mod about;
 
-pub use wrap_app::WrapApp;
+pub use wrap_app::{State, WrapApp};
+
+/// The name of the app, which is also where it keeps its state.
+pub const APP_NAME: &str = "pocket";
 
 /// Time of day as seconds since midnight. Used for clock in demo app.
 pub(crate) fn seconds_since_midnight() -> f64 {
@@ -51,4 +53,4 @@ macro_rules! pocket_github_link_file_line {
             egui::RichText::new($label).small()
         )
     };
-}
\ No newline at end of file
+}
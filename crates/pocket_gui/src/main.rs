@@ -52,9 +52,9 @@ fn main() -> eframe::Result {
         ..Default::default()
     };
     eframe::run_native(
-        "pocket",
+        pocket_gui::APP_NAME,
         options,
-        Box::new(|cc| Ok(Box::new(egui_demo_app::WrapApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(pocket_gui::WrapApp::new(cc)))),
     )
 }
 
@@ -1,4 +1,8 @@
-use egui_demo_lib::is_mobile;
+use std::sync::Arc;
+
+use pocket_core::Command;
+
+use crate::file_handler::{File, FileHandler};
 
 #[cfg(feature = "glow")]
 use eframe::glow;
@@ -9,7 +13,7 @@ use core::any::Any;
 #[derive(Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct EasyMarkApp {
-    editor: egui_demo_lib::easy_mark::EasyMarkEditor,
+    editor: crate::apps::EasyMarkEditor,
 }
 
 impl eframe::App for EasyMarkApp {
@@ -18,6 +22,20 @@ impl eframe::App for EasyMarkApp {
     }
 }
 
+impl FileHandler for EasyMarkApp {
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["text/markdown", "text/x-markdown"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["md", "markdown", "easymark"]
+    }
+
+    fn open_file(&mut self, _ctx: &egui::Context, _name: &str, bytes: Arc<[u8]>) {
+        self.editor.set_code(String::from_utf8_lossy(&bytes));
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 #[derive(Default)]
@@ -34,8 +52,6 @@ impl eframe::App for DemoApp {
 
 // ----------------------------------------------------------------------------
 
-
-
 #[derive(Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DatabaseApp {
@@ -48,6 +64,25 @@ impl eframe::App for DatabaseApp {
     }
 }
 
+impl FileHandler for DatabaseApp {
+    fn mime_types(&self) -> &'static [&'static str] {
+        &[
+            "text/csv",
+            "text/tab-separated-values",
+            "application/jsonl",
+            "application/x-ndjson",
+        ]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &crate::database::EXTENSIONS
+    }
+
+    fn open_file(&mut self, _ctx: &egui::Context, name: &str, bytes: Arc<[u8]>) {
+        self.database_windows.import(name, &bytes);
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 #[derive(Default)]
@@ -132,6 +167,18 @@ impl Anchor {
     }
 }
 
+impl Anchor {
+    /// The apps that may open dropped files, see [`FileHandler`].
+    fn file_handlers() -> Vec<Self> {
+        vec![
+            Self::Database,
+            Self::EasyMarkEditor,
+            #[cfg(feature = "image_viewer")]
+            Self::ImageViewer,
+        ]
+    }
+}
+
 impl std::fmt::Display for Anchor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -152,15 +199,6 @@ impl Default for Anchor {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Clone, Copy, Debug)]
-#[must_use]
-enum Command {
-    Nothing,
-    ResetEverything,
-}
-
-// ----------------------------------------------------------------------------
-
 /// The state that we persist (serialize).
 #[derive(Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -177,17 +215,75 @@ pub struct State {
     rendering_test: ColorTestApp,
 
     selected_anchor: Anchor,
-    backend_panel: super::backend_panel::BackendPanel,
+    backend_panel: pocket_core::BackendPanel,
+}
+
+#[cfg(feature = "persistence")]
+impl pocket_core::persistence::Versioned for State {
+    const MIGRATIONS: &'static [pocket_core::persistence::Migration] = &[migrate_demo_windows];
+}
+
+/// The state from before the database app had datasets, when its windows were demos.
+#[cfg(feature = "persistence")]
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct DemoWindowsState {
+    database: DemoWindowsApp,
+}
+
+#[cfg(feature = "persistence")]
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct DemoWindowsApp {
+    database_windows: DemoWindows,
+}
+
+#[cfg(feature = "persistence")]
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct DemoWindows {
+    demos: OpenDemos,
+}
+
+#[cfg(feature = "persistence")]
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct OpenDemos {
+    open: std::collections::BTreeSet<String>,
+}
+
+/// Version 0, from before there were versions, listed the About window of the database
+/// app among its demos, open while its name was in `demos.open`, next to an empty list
+/// of `tests`. Since version 1 the datasets are listed instead, and the About window is
+/// open while `about_is_open`.
+///
+/// Everything else reads the same, with defaults for what came later.
+#[cfg(feature = "persistence")]
+fn migrate_demo_windows(ron: &str) -> Result<String, String> {
+    let old: DemoWindowsState = ron::from_str(ron).map_err(|err| err.to_string())?;
+    let mut state: State = ron::from_str(ron).map_err(|err| err.to_string())?;
+    state
+        .database
+        .database_windows
+        .reopen_demos(&old.database.database_windows.demos.open);
+    ron::to_string(&state).map_err(|err| err.to_string())
 }
 
 /// Wraps many demo/test apps into one.
 pub struct WrapApp {
     state: State,
 
+    #[cfg(feature = "persistence")]
+    persistence: pocket_core::persistence::Persistence,
+
     #[cfg(any(feature = "glow", feature = "wgpu"))]
     custom3d: Option<crate::apps::Custom3d>,
 
+    /// Dropped files that no app can open.
     dropped_files: Vec<egui::DroppedFile>,
+
+    /// Dropped files that several apps can open, waiting for the user to pick one.
+    open_with: Vec<(File, Vec<Anchor>)>,
 }
 
 impl WrapApp {
@@ -199,18 +295,27 @@ impl WrapApp {
         let mut slf = Self {
             state: State::default(),
 
+            #[cfg(feature = "persistence")]
+            persistence: Default::default(),
+
             #[cfg(any(feature = "glow", feature = "wgpu"))]
             custom3d: crate::apps::Custom3d::new(cc),
 
             dropped_files: Default::default(),
+            open_with: Default::default(),
         };
 
         #[cfg(feature = "persistence")]
         if let Some(storage) = cc.storage {
-            if let Some(state) = eframe::get_value(storage, eframe::APP_KEY) {
+            if let Some(state) = slf.persistence.load(storage) {
                 slf.state = state;
             }
         }
+        // With the rest of the app's files, not wherever it was started
+        #[cfg(all(feature = "persistence", not(target_arch = "wasm32")))]
+        slf.state
+            .backend_panel
+            .set_recording_dir(eframe::storage_dir(crate::APP_NAME));
 
         slf
     }
@@ -273,7 +378,7 @@ impl WrapApp {
 impl eframe::App for WrapApp {
     #[cfg(feature = "persistence")]
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, &self.state);
+        self.persistence.save(storage, &self.state);
     }
 
     fn clear_color(&self, visuals: &egui::Visuals) -> [f32; 4] {
@@ -302,26 +407,19 @@ impl eframe::App for WrapApp {
         }
 
         let mut cmd = Command::Nothing;
-        egui::TopBottomPanel::top("wrap_app_top_bar")
-            .frame(egui::Frame::none().inner_margin(4.0))
-            .show(ctx, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.visuals_mut().button_frame = false;
-                    self.bar_contents(ui, frame, &mut cmd);
-                });
-            });
+        pocket_core::top_bar(ctx, |ui| self.bar_contents(ui, frame, &mut cmd));
 
         self.state.backend_panel.update(ctx, frame);
 
-        if !is_mobile(ctx) {
-            cmd = self.backend_panel(ctx, frame);
-        }
+        self.state
+            .backend_panel
+            .side_panel(ctx, frame, egui::panel::Side::Left, &mut cmd);
 
         self.show_selected_app(ctx, frame);
 
         self.state.backend_panel.end_of_frame(ctx);
 
-        self.ui_file_drag_and_drop(ctx);
+        self.ui_file_drag_and_drop(ctx, frame);
 
         self.run_cmd(ctx, cmd);
     }
@@ -340,29 +438,6 @@ impl eframe::App for WrapApp {
 }
 
 impl WrapApp {
-    fn backend_panel(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) -> Command {
-        // The backend-panel can be toggled on/off.
-        // We show a little animation when the user switches it.
-        let is_open =
-            self.state.backend_panel.open || ctx.memory(|mem| mem.everything_is_visible());
-
-        let mut cmd = Command::Nothing;
-
-        egui::SidePanel::left("backend_panel")
-            .resizable(false)
-            .show_animated(ctx, is_open, |ui| {
-                ui.add_space(4.0);
-                ui.vertical_centered(|ui| {
-                    ui.heading("💻 Backend");
-                });
-
-                ui.separator();
-                self.backend_panel_contents(ui, frame, &mut cmd);
-            });
-
-        cmd
-    }
-
     fn run_cmd(&mut self, ctx: &egui::Context, cmd: Command) {
         match cmd {
             Command::Nothing => {}
@@ -373,33 +448,6 @@ impl WrapApp {
         }
     }
 
-    fn backend_panel_contents(
-        &mut self,
-        ui: &mut egui::Ui,
-        frame: &mut eframe::Frame,
-        cmd: &mut Command,
-    ) {
-        self.state.backend_panel.ui(ui, frame);
-
-        ui.separator();
-
-        ui.horizontal(|ui| {
-            if ui
-                .button("Reset egui")
-                .on_hover_text("Forget scroll, positions, sizes etc")
-                .clicked()
-            {
-                ui.ctx().memory_mut(|mem| *mem = Default::default());
-                ui.close_menu();
-            }
-
-            if ui.button("Reset everything").clicked() {
-                *cmd = Command::ResetEverything;
-                ui.close_menu();
-            }
-        });
-    }
-
     fn show_selected_app(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         let selected_anchor = self.state.selected_anchor;
         for (_name, anchor, app) in self.apps_iter_mut() {
@@ -414,14 +462,7 @@ impl WrapApp {
 
         ui.separator();
 
-        if is_mobile(ui.ctx()) {
-            ui.menu_button("💻 Backend", |ui| {
-                ui.set_style(ui.ctx().style()); // ignore the "menu" style set by `menu_button`.
-                self.backend_panel_contents(ui, frame, cmd);
-            });
-        } else {
-            ui.toggle_value(&mut self.state.backend_panel.open, "💻 Backend");
-        }
+        self.state.backend_panel.top_bar_ui(ui, frame, cmd);
 
         ui.separator();
 
@@ -455,7 +496,45 @@ impl WrapApp {
         });
     }
 
-    fn ui_file_drag_and_drop(&mut self, ctx: &egui::Context) {
+    fn file_handler_mut(&mut self, anchor: Anchor) -> Option<&mut dyn FileHandler> {
+        match anchor {
+            Anchor::Database => Some(&mut self.state.database),
+            Anchor::EasyMarkEditor => Some(&mut self.state.easy_mark_editor),
+            #[cfg(feature = "image_viewer")]
+            Anchor::ImageViewer => Some(&mut self.state.image_viewer),
+            _ => None,
+        }
+    }
+
+    /// The apps that can open `file`.
+    fn file_handlers_of(&mut self, file: &File) -> Vec<Anchor> {
+        Anchor::file_handlers()
+            .into_iter()
+            .filter(|&anchor| {
+                self.file_handler_mut(anchor)
+                    .is_some_and(|handler| handler.accepts(file))
+            })
+            .collect()
+    }
+
+    /// Switch to the app of `anchor` and hand it `file`.
+    fn open_file(
+        &mut self,
+        ctx: &egui::Context,
+        frame: &eframe::Frame,
+        anchor: Anchor,
+        file: &File,
+    ) {
+        if let Some(handler) = self.file_handler_mut(anchor) {
+            handler.open_file(ctx, &file.name, file.bytes.clone());
+            self.state.selected_anchor = anchor;
+            if frame.is_web() {
+                ctx.open_url(egui::OpenUrl::same_tab(format!("#{anchor}")));
+            }
+        }
+    }
+
+    fn ui_file_drag_and_drop(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
         use egui::{Align2, Color32, Id, LayerId, Order, TextStyle};
         use std::fmt::Write as _;
 
@@ -489,12 +568,25 @@ impl WrapApp {
             );
         }
 
-        // Collect dropped files:
-        ctx.input(|i| {
-            if !i.raw.dropped_files.is_empty() {
-                self.dropped_files.clone_from(&i.raw.dropped_files);
+        // Hand dropped files to the apps that can open them:
+        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+        if !dropped_files.is_empty() {
+            self.dropped_files.clear();
+        }
+        for dropped_file in dropped_files {
+            let Some(file) = File::read(&dropped_file) else {
+                self.dropped_files.push(dropped_file);
+                continue;
+            };
+            let anchors = self.file_handlers_of(&file);
+            match anchors.as_slice() {
+                [] => self.dropped_files.push(dropped_file),
+                [anchor] => self.open_file(ctx, frame, *anchor, &file),
+                _ => self.open_with.push((file, anchors)),
             }
-        });
+        }
+
+        self.ui_open_with(ctx, frame);
 
         // Show dropped files (if any):
         if !self.dropped_files.is_empty() {
@@ -502,6 +594,7 @@ impl WrapApp {
             egui::Window::new("Dropped files")
                 .open(&mut open)
                 .show(ctx, |ui| {
+                    ui.label("No app can open these files:");
                     for file in &self.dropped_files {
                         let mut info = if let Some(path) = &file.path {
                             path.display().to_string()
@@ -530,6 +623,43 @@ impl WrapApp {
             }
         }
     }
+
+    /// Ask which app should open a file that several apps can open.
+    fn ui_open_with(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let Some((file, anchors)) = self.open_with.first() else {
+            return;
+        };
+        let (file_name, anchors) = (file.name.clone(), anchors.clone());
+        let names: Vec<(Anchor, String)> = self
+            .apps_iter_mut()
+            .filter(|(_, anchor, _)| anchors.contains(anchor))
+            .map(|(name, anchor, _)| (anchor, name.to_owned()))
+            .collect();
+
+        let mut chosen = None;
+        let mut open = true;
+        egui::Window::new("Open with…")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Which app should open {file_name:?}?"));
+                ui.horizontal(|ui| {
+                    for (anchor, name) in &names {
+                        if ui.button(name).clicked() {
+                            chosen = Some(*anchor);
+                        }
+                    }
+                });
+            });
+
+        if chosen.is_some() || !open {
+            let (file, _) = self.open_with.remove(0);
+            if let Some(anchor) = chosen {
+                self.open_file(ctx, frame, anchor, &file);
+            }
+        }
+    }
 }
 
 fn clock_button(ui: &mut egui::Ui, seconds_since_midnight: f64) -> egui::Response {
@@ -0,0 +1,172 @@
+//! Charts of small tables, checked value by value.
+
+use egui::pos2;
+use pocket_gui::database::chart_window::{
+    nearest, quantile, Chart, ChartKind, Plot, Settings, HOVER_DISTANCE,
+};
+use pocket_gui::database::table::{Column, ColumnData, Table};
+use pocket_gui::database::table_window::{RangeFilter, TableWindow};
+
+fn chart(table: &Table, kind: ChartKind, bins: usize) -> Chart {
+    let settings = Settings {
+        kind,
+        bins,
+        ..Settings::default()
+    };
+    Chart::new(table, &settings, 0, 1)
+}
+
+fn table(x: ColumnData, y: ColumnData) -> Table {
+    Table::new("t", vec![Column::new("x", x), Column::new("y", y)])
+}
+
+#[test]
+fn histograms_bin_every_finite_value() {
+    let mut values: Vec<Option<f64>> = (0..=10).map(|n| Some(f64::from(n))).collect();
+    values.extend([None, Some(f64::NAN), Some(f64::INFINITY)]);
+    let table = table(
+        ColumnData::Float(values),
+        ColumnData::Integer(vec![None; 14]),
+    );
+    let chart = chart(&table, ChartKind::Histogram, 5);
+    let Plot::Histogram { min, width, counts } = &chart.plot else {
+        panic!("not a histogram");
+    };
+    assert_eq!((*min, *width), (0.0, 2.0));
+    // The maximum goes in the last bin
+    assert_eq!(counts, &[2, 2, 2, 2, 3]);
+    assert_eq!(chart.bounds, [[0.0, 0.0], [10.0, 3.0 * 1.05]]);
+}
+
+#[test]
+fn histograms_of_one_value_have_one_full_bin() {
+    let table = table(
+        ColumnData::Integer(vec![Some(7); 3]),
+        ColumnData::Integer(vec![None; 3]),
+    );
+    let chart = chart(&table, ChartKind::Histogram, 4);
+    let Plot::Histogram { min, width, counts } = &chart.plot else {
+        panic!("not a histogram");
+    };
+    assert_eq!((*min, *width), (7.0, 0.25));
+    assert_eq!(counts, &[3, 0, 0, 0]);
+}
+
+#[test]
+fn quantiles_interpolate() {
+    let sorted = [1.0, 2.0, 3.0, 4.0];
+    assert_eq!(quantile(&sorted, 0.0), 1.0);
+    assert_eq!(quantile(&sorted, 0.5), 2.5);
+    assert_eq!(quantile(&sorted, 0.25), 1.75);
+    assert_eq!(quantile(&sorted, 1.0), 4.0);
+    assert_eq!(quantile(&[7.0], 0.75), 7.0);
+    assert_eq!(quantile(&[], 0.5), 0.0);
+}
+
+#[test]
+fn box_plots_show_outliers_beyond_the_whiskers() {
+    let values = [5, 100, 1, 2, 3, 4, -50, 6, 7, 8, 9];
+    let mut x: Vec<Option<i64>> = values.into_iter().map(Some).collect();
+    x.push(None);
+    let table = table(ColumnData::Integer(x), ColumnData::Integer(vec![None; 12]));
+    let chart = chart(&table, ChartKind::BoxPlot, 20);
+    let Plot::Box { stats, outliers } = &chart.plot else {
+        panic!("not a box plot");
+    };
+    // The whiskers end at the last values within 1.5 times the quartile range
+    assert_eq!(stats, &[1.0, 2.5, 5.0, 7.5, 9.0]);
+    assert_eq!(outliers, &[(6, -50.0), (1, 100.0)]);
+}
+
+#[test]
+fn lines_and_scatters_skip_missing_values() {
+    let table = table(
+        ColumnData::Float(vec![Some(1.0), None, Some(3.0), Some(4.0)]),
+        ColumnData::Integer(vec![Some(10), Some(20), None, Some(40)]),
+    );
+    let Plot::Points { points, lines } = chart(&table, ChartKind::Line, 20).plot else {
+        panic!("not a line");
+    };
+    assert!(lines);
+    assert_eq!(points, [(0, [1.0, 1.0]), (2, [3.0, 3.0]), (3, [4.0, 4.0])]);
+
+    let Plot::Points { points, lines } = chart(&table, ChartKind::Scatter, 20).plot else {
+        panic!("not a scatter");
+    };
+    assert!(!lines);
+    assert_eq!(points, [(0, [1.0, 10.0]), (3, [4.0, 40.0])]);
+}
+
+#[test]
+fn brushes_become_range_filters() {
+    let table = table(
+        ColumnData::Integer(vec![Some(1), Some(2), Some(3), Some(4), Some(5)]),
+        ColumnData::Float(vec![Some(50.0), Some(40.0), Some(30.0), Some(20.0), None]),
+    );
+    // Brushed from the upper right to the lower left
+    let brush = [[4.0, 45.0], [2.0, 25.0]];
+
+    let histogram = chart(&table, ChartKind::Histogram, 5);
+    let ranges = histogram.ranges(0, None, brush);
+    assert_eq!(
+        ranges,
+        [RangeFilter {
+            column: Some(0),
+            range: 2.0..=4.0,
+        }]
+    );
+
+    let box_plot = chart(&table, ChartKind::BoxPlot, 5);
+    assert_eq!(
+        box_plot.ranges(0, None, brush),
+        [RangeFilter {
+            column: Some(0),
+            range: 25.0..=45.0,
+        }]
+    );
+
+    let line = chart(&table, ChartKind::Line, 5);
+    assert_eq!(
+        line.ranges(0, None, brush),
+        [RangeFilter {
+            column: None,
+            range: 2.0..=4.0,
+        }]
+    );
+
+    let scatter = chart(&table, ChartKind::Scatter, 5);
+    let scatter_ranges = scatter.ranges(0, Some(1), brush);
+    assert_eq!(
+        scatter_ranges,
+        [
+            RangeFilter {
+                column: Some(0),
+                range: 2.0..=4.0,
+            },
+            RangeFilter {
+                column: Some(1),
+                range: 25.0..=45.0,
+            },
+        ]
+    );
+
+    let mut window = TableWindow::new(table);
+    window.set_ranges(ranges);
+    assert_eq!(window.shown_rows(), [1, 2, 3]);
+    window.set_ranges(scatter_ranges);
+    assert_eq!(window.shown_rows(), [1, 2]);
+}
+
+#[test]
+fn the_nearest_point_within_reach_is_hovered() {
+    let points = [
+        (0, pos2(0.0, 0.0)),
+        (1, pos2(5.0, 0.0)),
+        (2, pos2(100.0, 100.0)),
+    ];
+    assert_eq!(nearest(&points, pos2(4.0, 1.0)), Some(1));
+    assert_eq!(nearest(&points, pos2(1.0, -1.0)), Some(0));
+    assert_eq!(nearest(&points, pos2(50.0, 50.0)), None);
+    assert_eq!(nearest(&points, pos2(100.0, 100.0 + HOVER_DISTANCE)), None);
+    assert_eq!(nearest(&[], pos2(0.0, 0.0)), None);
+}
@@ -0,0 +1,27 @@
+//! Exported files must read back as what was exported.
+
+use pocket_gui::database::export::{export, to_csv, to_json_lines, Format};
+use pocket_gui::database::table::{Column, ColumnData, Table};
+
+#[test]
+fn text_formats_keep_the_row_order() {
+    let table = Table::new(
+        "t",
+        vec![
+            Column::new("a", ColumnData::Integer(vec![Some(1), None, Some(3)])),
+            Column::new(
+                "b",
+                ColumnData::String(vec![Some("x,\"y\"".to_owned()), Some("z".to_owned()), None]),
+            ),
+        ],
+    );
+    assert_eq!(
+        to_csv(&table, &[2, 0, 1]),
+        "a,b\r\n3,\r\n1,\"x,\"\"y\"\"\"\r\n,z\r\n"
+    );
+    assert_eq!(to_json_lines(&table, &[1]), "{\"a\":null,\"b\":\"z\"}\n");
+    assert_eq!(
+        export(&table, &[2, 0, 1], Format::Csv).unwrap(),
+        to_csv(&table, &[2, 0, 1]).into_bytes()
+    );
+}
@@ -0,0 +1,236 @@
+//! The native database file: what is written survives, and damage is caught.
+#![cfg(all(feature = "persistence", not(target_arch = "wasm32")))]
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+use pocket_gui::database::storage::file::Store;
+
+/// The length of the file header: magic and version.
+const HEADER_LEN: usize = 12;
+
+fn flip(path: &Path, offset: usize) {
+    let mut bytes = std::fs::read(path).unwrap();
+    bytes[offset] ^= 0x01;
+    std::fs::write(path, bytes).unwrap();
+}
+
+#[test]
+fn survives_reopening() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested").join("pocket.db");
+    {
+        let mut store = Store::open(&path).unwrap();
+        store.put("table/a", b"first").unwrap();
+        store.put("table/b", b"").unwrap();
+        store.put("query/x", b"SELECT 1").unwrap();
+        store.put("table/a", b"second").unwrap();
+        store.put("table/c", &[0xFF; 1000]).unwrap();
+        store.delete("table/c").unwrap();
+        store.delete("missing").unwrap();
+    }
+
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(
+        store.keys("table/").collect::<Vec<_>>(),
+        ["table/a", "table/b"]
+    );
+    assert_eq!(store.keys("").count(), 3);
+    assert_eq!(
+        store.get("table/a").unwrap().as_deref(),
+        Some(b"second".as_slice())
+    );
+    assert_eq!(
+        store.get("table/b").unwrap().as_deref(),
+        Some(b"".as_slice())
+    );
+    assert_eq!(store.get("table/c").unwrap(), None);
+
+    // Writing after reopening appends after what is there
+    store.put("table/c", b"again").unwrap();
+    drop(store);
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(
+        store.get("table/c").unwrap().as_deref(),
+        Some(b"again".as_slice())
+    );
+    assert_eq!(
+        store.get("query/x").unwrap().as_deref(),
+        Some(b"SELECT 1".as_slice())
+    );
+}
+
+#[test]
+fn torn_record_is_dropped() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pocket.db");
+    let mut store = Store::open(&path).unwrap();
+    store.put("kept", b"safe").unwrap();
+    drop(store);
+    let kept_len = std::fs::metadata(&path).unwrap().len();
+
+    // A crash halfway through the value, or the header, of the last record
+    for torn_len in [50, 3] {
+        let mut store = Store::open(&path).unwrap();
+        store.put("torn", &[7; 100]).unwrap();
+        drop(store);
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(kept_len + torn_len).unwrap();
+        drop(file);
+
+        let mut store = Store::open(&path).unwrap();
+        assert_eq!(store.keys("").collect::<Vec<_>>(), ["kept"]);
+        assert_eq!(
+            store.get("kept").unwrap().as_deref(),
+            Some(b"safe".as_slice())
+        );
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), kept_len);
+    }
+
+    // Writing goes on where the good records end
+    let mut store = Store::open(&path).unwrap();
+    store.put("after", b"crash").unwrap();
+    drop(store);
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(
+        store.get("after").unwrap().as_deref(),
+        Some(b"crash".as_slice())
+    );
+}
+
+#[test]
+fn flipped_bytes_are_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pocket.db");
+    let mut store = Store::open(&path).unwrap();
+    store.put("a", b"first value").unwrap();
+    store.put("b", b"second value").unwrap();
+    drop(store);
+    let len = std::fs::metadata(&path).unwrap().len() as usize;
+
+    // In a value: the key is still there, but reading it fails
+    flip(&path, len - 1);
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(store.keys("").collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(
+        store.get("a").unwrap().as_deref(),
+        Some(b"first value".as_slice())
+    );
+    let err = store.get("b").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    drop(store);
+    flip(&path, len - 1);
+
+    // In the key of the last record: it is whole, so it is damaged rather than torn
+    let second = len - "second value".len() - 4 - 1;
+    flip(&path, second);
+    let err = Store::open(&path).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    // ... and the file is left alone
+    assert_eq!(std::fs::metadata(&path).unwrap().len() as usize, len);
+}
+
+#[test]
+fn damage_before_good_records_keeps_them() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pocket.db");
+    let mut store = Store::open(&path).unwrap();
+    store.put("a", b"first value").unwrap();
+    store.put("b", b"second value").unwrap();
+    store.put("c", b"third value").unwrap();
+    drop(store);
+    let original = std::fs::read(&path).unwrap();
+
+    // The kind, key length, value length, value CRC and key of the first record
+    for offset in [0, 1, 4, 5, 12, 13, 17] {
+        flip(&path, HEADER_LEN + offset);
+        let err = Store::open(&path).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "byte {offset}");
+        assert_eq!(std::fs::read(&path).unwrap().len(), original.len());
+        flip(&path, HEADER_LEN + offset);
+    }
+
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(store.keys("").collect::<Vec<_>>(), ["a", "b", "c"]);
+    assert_eq!(
+        store.get("c").unwrap().as_deref(),
+        Some(b"third value".as_slice())
+    );
+}
+
+#[test]
+fn damaged_lengths_do_not_overflow() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pocket.db");
+    let mut store = Store::open(&path).unwrap();
+    store.put("a", b"value").unwrap();
+    drop(store);
+
+    // The value length of the record, as large as it goes, with a header CRC to match
+    let mut bytes = std::fs::read(&path).unwrap();
+    let header = HEADER_LEN..HEADER_LEN + 17 + "a".len();
+    bytes[HEADER_LEN + 5..HEADER_LEN + 13].copy_from_slice(&u64::MAX.to_le_bytes());
+    let crc = crc32fast::hash(&bytes[header.clone()]);
+    bytes[header.end..header.end + 4].copy_from_slice(&crc.to_le_bytes());
+    std::fs::write(&path, bytes).unwrap();
+    let err = Store::open(&path).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn other_files_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pocket.db");
+    let mut store = Store::open(&path).unwrap();
+    store.put("a", b"1").unwrap();
+    drop(store);
+    let original = std::fs::read(&path).unwrap();
+    assert_eq!(&original[..8], b"POCKETDB");
+
+    // Wrong magic
+    flip(&path, 0);
+    let err = Store::open(&path).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    // ... and the file is left alone
+    assert_eq!(std::fs::read(&path).unwrap().len(), original.len());
+
+    // A version from the future
+    let mut bytes = original.clone();
+    bytes[8..HEADER_LEN].copy_from_slice(&2_u32.to_le_bytes());
+    std::fs::write(&path, bytes).unwrap();
+    let err = Store::open(&path).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    // Too short for a header
+    std::fs::write(&path, b"POCK").unwrap();
+    assert!(Store::open(&path).is_err());
+
+    std::fs::write(&path, original).unwrap();
+    assert!(Store::open(&path).is_ok());
+}
+
+#[test]
+fn compacts_dead_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pocket.db");
+    let mut store = Store::open(&path).unwrap();
+    let big = vec![42; 400_000];
+    store.put("keep", b"small").unwrap();
+    for i in 0..8_u8 {
+        let mut value = big.clone();
+        value[0] = i;
+        store.put("big", &value).unwrap();
+    }
+    // Compacted whenever over 1 MiB was dead, so most of what was written is gone
+    let len = std::fs::metadata(&path).unwrap().len();
+    assert!(len < 3 * big.len() as u64, "{len}");
+    assert!(!path.with_extension("compacting").exists());
+
+    drop(store);
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(
+        store.get("keep").unwrap().as_deref(),
+        Some(b"small".as_slice())
+    );
+    assert_eq!(store.get("big").unwrap().unwrap()[0], 7);
+}
@@ -0,0 +1,197 @@
+//! Reading files people have lying around, quirks and all.
+
+use pocket_gui::database::import::{Encoding, Format, ImportError, Options, Records};
+use pocket_gui::database::infer::{infer_type, parse_column};
+use pocket_gui::database::table::{ColumnData, DataType};
+
+fn csv(text: &str) -> Result<Records, ImportError> {
+    let options = Options {
+        format: Format::Delimited(','),
+        has_header: true,
+        encoding: Encoding::Utf8,
+    };
+    Records::parse(text.as_bytes(), &options)
+}
+
+fn rows(records: &Records) -> Vec<Vec<&str>> {
+    records
+        .rows
+        .iter()
+        .map(|row| row.iter().map(String::as_str).collect())
+        .collect()
+}
+
+#[test]
+fn quoted_cells() {
+    let records = csv(concat!(
+        "name,note\r\n",
+        "\"Smith, J.\",\"said \"\"hi\"\"\"\r\n",
+        "\r\n",
+        "Doe,\"two\r\nlines\"\r\n",
+        "\n",
+        "Roe,\"\"\r\n",
+    ))
+    .unwrap();
+    assert_eq!(records.header, ["name", "note"]);
+    assert_eq!(
+        rows(&records),
+        [
+            vec!["Smith, J.", "said \"hi\""],
+            // Newlines inside quotes are kept as they are
+            vec!["Doe", "two\r\nlines"],
+            vec!["Roe", ""],
+        ]
+    );
+
+    // A quote in the middle of a cell is just a character
+    let records = csv("a,b\nsix\"7,8\n").unwrap();
+    assert_eq!(rows(&records), [["six\"7", "8"]]);
+}
+
+#[test]
+fn unterminated_quotes_report_their_line() {
+    assert_eq!(
+        csv("a,b\n1,2\n3,\"four\nfive\n").unwrap_err(),
+        ImportError::UnterminatedQuote { line: 3 }
+    );
+    // Lines inside earlier quoted cells count too
+    assert_eq!(
+        csv("a\n\"x\ny\"\n\"z\n").unwrap_err(),
+        ImportError::UnterminatedQuote { line: 4 }
+    );
+    assert_eq!(csv("\n\n").unwrap_err(), ImportError::Empty);
+}
+
+#[test]
+fn headers_and_ragged_rows() {
+    let records = csv(" id ,,\n1,2,3,4\n5\n").unwrap();
+    assert_eq!(records.header, ["id", "column_2", "column_3", "column_4"]);
+    assert_eq!(rows(&records), [vec!["1", "2", "3", "4"], vec!["5"]]);
+
+    let options = Options {
+        format: Format::Delimited(';'),
+        has_header: false,
+        encoding: Encoding::Utf8,
+    };
+    let records = Records::parse(b"1;x\n2;y", &options).unwrap();
+    assert_eq!(records.header, ["column_1", "column_2"]);
+    assert_eq!(rows(&records), [["1", "x"], ["2", "y"]]);
+}
+
+#[test]
+fn encodings() {
+    let text = "name\nZoë\n";
+    let utf16 = |to_bytes: fn(u16) -> [u8; 2]| {
+        std::iter::once(0xFEFF)
+            .chain(text.encode_utf16())
+            .flat_map(to_bytes)
+            .collect::<Vec<u8>>()
+    };
+    let le = utf16(u16::to_le_bytes);
+    let be = utf16(u16::to_be_bytes);
+    assert_eq!(&le[..2], [0xFF, 0xFE]);
+    assert_eq!(Encoding::sniff(&le), Encoding::Utf16Le);
+    assert_eq!(Encoding::sniff(&be), Encoding::Utf16Be);
+    assert_eq!(Encoding::Utf16Le.decode(&le), text);
+    assert_eq!(Encoding::Utf16Be.decode(&be), text);
+
+    let utf8 = [b"\xEF\xBB\xBF".as_slice(), text.as_bytes()].concat();
+    assert_eq!(Encoding::sniff(&utf8), Encoding::Utf8);
+    assert_eq!(Encoding::Utf8.decode(&utf8), text);
+
+    let latin1 = b"name\nZo\xEB\n";
+    assert_eq!(Encoding::sniff(latin1), Encoding::Latin1);
+    assert_eq!(Encoding::Latin1.decode(latin1), text);
+
+    let options = Options::sniff("names.csv", &le);
+    assert_eq!(options.encoding, Encoding::Utf16Le);
+    assert_eq!(rows(&Records::parse(&le, &options).unwrap()), [["Zoë"]]);
+}
+
+#[test]
+fn formats_are_sniffed() {
+    assert_eq!(Format::sniff("a.jsonl", "x,y"), Format::JsonLines);
+    assert_eq!(Format::sniff("a.NDJSON", ""), Format::JsonLines);
+    assert_eq!(Format::sniff("a.tsv", "x,y"), Format::Delimited('\t'));
+    assert_eq!(Format::sniff("a.txt", "  {\"x\": 1}"), Format::JsonLines);
+    assert_eq!(Format::sniff("a.csv", "x;y;z,w"), Format::Delimited(';'));
+    assert_eq!(Format::sniff("a.csv", "x|y"), Format::Delimited('|'));
+    // Ties, and lines without any delimiter, go to commas
+    assert_eq!(Format::sniff("a.txt", "x;y,z"), Format::Delimited(','));
+    assert_eq!(Format::sniff("data", "x"), Format::Delimited(','));
+}
+
+#[test]
+fn json_lines_keep_key_order() {
+    let options = Options {
+        format: Format::JsonLines,
+        has_header: false,
+        encoding: Encoding::Utf8,
+    };
+    let text = concat!(
+        "{\"zebra\": 1, \"apple\": \"a\"}\n",
+        "\n",
+        "{\"apple\": null, \"mango\": [1, 2], \"zebra\": true}\n",
+        "{\"nested\": {\"k\": 1.5}}\n",
+    );
+    let records = Records::parse(text.as_bytes(), &options).unwrap();
+    assert_eq!(records.header, ["zebra", "apple", "mango", "nested"]);
+    assert_eq!(
+        rows(&records),
+        [
+            vec!["1", "a"],
+            vec!["true", "", "[1,2]"],
+            vec!["", "", "", "{\"k\":1.5}"],
+        ]
+    );
+
+    let error = Records::parse(b"{\"a\": 1}\n\n[1]\n", &options).unwrap_err();
+    assert_eq!(
+        error,
+        ImportError::Json {
+            line: 3,
+            message: "expected an object".to_owned(),
+        }
+    );
+    assert!(matches!(
+        Records::parse(b"{oops", &options),
+        Err(ImportError::Json { line: 1, .. })
+    ));
+}
+
+#[test]
+fn column_types_are_inferred() {
+    assert_eq!(infer_type(&["yes", "No", "", "TRUE"]), DataType::Boolean);
+    assert_eq!(infer_type(&[" 1", "-2", "NA"]), DataType::Integer);
+    assert_eq!(infer_type(&["1", "2.5", "1e3"]), DataType::Float);
+    assert_eq!(
+        infer_type(&["2024-01-31", "2024/02/01", "03.02.2024"]),
+        DataType::Date
+    );
+    assert_eq!(infer_type(&["1", "two"]), DataType::String);
+    assert_eq!(infer_type(&["", "null", "N/A"]), DataType::String);
+    assert_eq!(infer_type(&[]), DataType::String);
+
+    assert_eq!(
+        parse_column(&["1", " 2 ", "x", "NaN", ""], DataType::Integer),
+        ColumnData::Integer(vec![Some(1), Some(2), None, None, None])
+    );
+    assert_eq!(
+        parse_column(&["yes", "false", "maybe"], DataType::Boolean),
+        ColumnData::Boolean(vec![Some(true), Some(false), None])
+    );
+    assert_eq!(
+        parse_column(&[" a ", "None"], DataType::String),
+        ColumnData::String(vec![Some("a".to_owned()), None])
+    );
+
+    let records = csv("n,when,label\n1,2024-01-31,x\n,2024-02-01,\n3.5,,y\n").unwrap();
+    let types = records.infer_types();
+    assert_eq!(types, [DataType::Float, DataType::Date, DataType::String]);
+    let table = records.to_table("t", &types);
+    assert_eq!(table.num_rows(), 3);
+    assert_eq!(
+        table.columns()[0].data,
+        ColumnData::Float(vec![Some(1.0), None, Some(3.5)])
+    );
+}
@@ -0,0 +1,159 @@
+//! Exported Parquet files must read back as what was exported, by other tools too.
+#![cfg(feature = "parquet")]
+
+use chrono::NaiveDate;
+use parquet::basic::{ConvertedType, Repetition, Type as PhysicalType};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use pocket_gui::database::export::{export, to_parquet, Format};
+use pocket_gui::database::table::{Column, ColumnData, Table};
+
+const NUM_ROWS: usize = 300;
+
+fn date(days: i64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + chrono::Duration::days(days)
+}
+
+/// Every type twice: always present, and missing in runs of varying length.
+fn table() -> Table {
+    let present = |_: usize| true;
+    let sometimes = |row: usize| row % 7 > 2 && row % 50 != 10;
+    let mut columns = vec![];
+    for (suffix, defined) in [("", &present as &dyn Fn(usize) -> bool), ("?", &sometimes)] {
+        let rows = 0..NUM_ROWS;
+        columns.extend([
+            Column::new(
+                &format!("string{suffix}"),
+                ColumnData::String(
+                    rows.clone()
+                        .map(|row| defined(row).then(|| format!("row {row} ✓").repeat(row % 3)))
+                        .collect(),
+                ),
+            ),
+            Column::new(
+                &format!("integer{suffix}"),
+                ColumnData::Integer(
+                    rows.clone()
+                        .map(|row| defined(row).then_some((row as i64 - 150) * 1_000_000_007))
+                        .collect(),
+                ),
+            ),
+            Column::new(
+                &format!("float{suffix}"),
+                ColumnData::Float(
+                    rows.clone()
+                        .map(|row| defined(row).then_some(row as f64 / 3.0 - 7.5))
+                        .collect(),
+                ),
+            ),
+            Column::new(
+                &format!("date{suffix}"),
+                ColumnData::Date(
+                    rows.clone()
+                        .map(|row| defined(row).then(|| date(row as i64 * 97 - 10_000)))
+                        .collect(),
+                ),
+            ),
+            Column::new(
+                &format!("boolean{suffix}"),
+                ColumnData::Boolean(
+                    rows.map(|row| defined(row).then_some(row % 3 == 0))
+                        .collect(),
+                ),
+            ),
+        ]);
+    }
+    Table::new("all types", columns)
+}
+
+/// What the `parquet` crate reads back for `column` of `table` at `row`.
+fn expected(data: &ColumnData, row: usize) -> Field {
+    let field = match data {
+        ColumnData::String(values) => values[row].clone().map(Field::Str),
+        ColumnData::Integer(values) => values[row].map(Field::Long),
+        ColumnData::Float(values) => values[row].map(Field::Double),
+        ColumnData::Date(values) => {
+            values[row].map(|value| Field::Date((value - date(0)).num_days() as i32))
+        }
+        ColumnData::Boolean(values) => values[row].map(Field::Bool),
+    };
+    field.unwrap_or(Field::Null)
+}
+
+fn read(bytes: Vec<u8>) -> SerializedFileReader<bytes::Bytes> {
+    SerializedFileReader::new(bytes::Bytes::from(bytes)).unwrap()
+}
+
+#[test]
+fn parquet_round_trip() {
+    let table = table();
+    // Every other row, backwards, to check the order is kept
+    let rows: Vec<usize> = (0..NUM_ROWS).rev().step_by(2).collect();
+    let reader = read(to_parquet(&table, &rows).unwrap());
+
+    let metadata = reader.metadata();
+    assert_eq!(metadata.file_metadata().num_rows(), rows.len() as i64);
+    assert_eq!(metadata.num_row_groups(), 1);
+
+    let schema = metadata.file_metadata().schema_descr();
+    assert_eq!(schema.num_columns(), table.num_columns());
+    for (i, column) in table.columns().iter().enumerate() {
+        let descriptor = schema.column(i);
+        assert_eq!(descriptor.name(), column.name);
+        assert_eq!(
+            descriptor.self_type().get_basic_info().repetition(),
+            Repetition::OPTIONAL
+        );
+        let (physical_type, converted_type) = match column.data {
+            ColumnData::String(_) => (PhysicalType::BYTE_ARRAY, ConvertedType::UTF8),
+            ColumnData::Integer(_) => (PhysicalType::INT64, ConvertedType::NONE),
+            ColumnData::Float(_) => (PhysicalType::DOUBLE, ConvertedType::NONE),
+            ColumnData::Date(_) => (PhysicalType::INT32, ConvertedType::DATE),
+            ColumnData::Boolean(_) => (PhysicalType::BOOLEAN, ConvertedType::NONE),
+        };
+        assert_eq!(descriptor.physical_type(), physical_type, "{}", column.name);
+        assert_eq!(
+            descriptor.converted_type(),
+            converted_type,
+            "{}",
+            column.name
+        );
+    }
+
+    let read_rows = reader
+        .get_row_iter(None)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(read_rows.len(), rows.len());
+    for (read_row, &row) in read_rows.iter().zip(&rows) {
+        let fields = read_row.get_column_iter().collect::<Vec<_>>();
+        assert_eq!(fields.len(), table.num_columns());
+        for ((name, field), column) in fields.into_iter().zip(table.columns()) {
+            assert_eq!(name, &column.name);
+            assert_eq!(field, &expected(&column.data, row), "{name} in row {row}");
+        }
+    }
+}
+
+#[test]
+fn parquet_without_rows() {
+    let reader = read(to_parquet(&table(), &[]).unwrap());
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 0);
+}
+
+#[test]
+fn export_writes_parquet() {
+    let table = Table::new(
+        "t",
+        vec![Column::new(
+            "a",
+            ColumnData::Integer(vec![Some(1), None, Some(3)]),
+        )],
+    );
+    assert_eq!(
+        export(&table, &[0, 1, 2], Format::Parquet).unwrap(),
+        to_parquet(&table, &[0, 1, 2]).unwrap()
+    );
+}
@@ -0,0 +1,342 @@
+//! Queries over small tables, checked cell by cell.
+
+use pocket_gui::database::query::{
+    self, BinaryOp, Expr, JoinKind, OrderBy, QueryError, Scalar, SelectItem,
+};
+use pocket_gui::database::table::{Column, ColumnData, Table};
+
+fn strings(values: &[Option<&str>]) -> ColumnData {
+    ColumnData::String(values.iter().map(|v| v.map(str::to_owned)).collect())
+}
+
+/// `people(id, name, city)` and `cities(name, country, size)`.
+fn tables() -> Vec<Table> {
+    let people = Table::new(
+        "people",
+        vec![
+            Column::new(
+                "id",
+                ColumnData::Integer(vec![Some(1), Some(2), Some(3), Some(4), Some(5)]),
+            ),
+            Column::new(
+                "name",
+                strings(&[Some("Ada"), Some("Bo"), Some("Cy"), Some("Di"), Some("Ed")]),
+            ),
+            Column::new(
+                "city",
+                strings(&[
+                    Some("Oslo"),
+                    Some("Lima"),
+                    Some("Oslo"),
+                    Some("Nowhere"),
+                    None,
+                ]),
+            ),
+            Column::new(
+                "age",
+                ColumnData::Float(vec![Some(30.0), None, Some(40.0), Some(25.0), None]),
+            ),
+        ],
+    );
+    let cities = Table::new(
+        "cities",
+        vec![
+            Column::new("name", strings(&[Some("Oslo"), Some("Lima"), Some("Lima")])),
+            Column::new("country", strings(&[Some("NO"), Some("PE"), Some("US")])),
+        ],
+    );
+    vec![people, cities]
+}
+
+fn run(text: &str) -> Result<Table, QueryError> {
+    let tables = tables();
+    let query = query::parse(text)?;
+    query::run(
+        &query,
+        |name| tables.iter().find(|table| table.name == name),
+        "result",
+    )
+}
+
+/// The column names, then every row, as text; missing values are `-`.
+fn rows(text: &str) -> Vec<Vec<String>> {
+    let table = run(text).unwrap_or_else(|err| panic!("{text}: {err}"));
+    let header = table.columns().iter().map(|c| c.name.clone()).collect();
+    let rows = (0..table.num_rows()).map(|row| {
+        table
+            .columns()
+            .iter()
+            .map(|column| match Scalar::from(column.data.get(row)) {
+                Scalar::Null => "-".to_owned(),
+                value => value.to_string(),
+            })
+            .collect()
+    });
+    std::iter::once(header).chain(rows).collect()
+}
+
+fn syntax(text: &str) -> (usize, String) {
+    match query::parse(text) {
+        Err(QueryError::Syntax { position, message }) => (position, message),
+        other => panic!("{text}: {other:?}"),
+    }
+}
+
+#[test]
+fn parses_every_clause() {
+    let query = query::parse(
+        "SELECT DISTINCT p.city AS town, count(*) FROM people p \
+         LEFT JOIN cities AS c ON p.city = c.name \
+         WHERE p.age >= 18 GROUP BY p.city HAVING count(*) > 1 \
+         ORDER BY 2 DESC, town LIMIT 10",
+    )
+    .unwrap();
+    assert!(query.distinct);
+    assert_eq!(query.table_names(), ["people", "cities"]);
+    assert_eq!(query.from.qualifier(), "p");
+    let join = query.join.as_ref().unwrap();
+    assert_eq!(join.kind, JoinKind::Left);
+    assert_eq!(join.table.qualifier(), "c");
+    assert!(matches!(join.on, Expr::Binary(_, BinaryOp::Eq, _)));
+    assert_eq!(
+        query.items[0],
+        SelectItem::Expr {
+            expr: Expr::Column {
+                table: Some("p".to_owned()),
+                name: "city".to_owned(),
+            },
+            alias: Some("town".to_owned()),
+        }
+    );
+    assert_eq!(query.group_by.len(), 1);
+    assert!(query.having.is_some());
+    assert_eq!(
+        query.order_by[0],
+        OrderBy {
+            expr: Expr::Literal(Scalar::Integer(2)),
+            descending: true,
+        }
+    );
+    assert!(!query.order_by[1].descending);
+    assert_eq!(query.limit, Some(10));
+
+    // Keywords are not case sensitive, quoted names can be anything
+    let query = query::parse("select \"a b\" from \"my table\"").unwrap();
+    assert_eq!(query.table_names(), ["my table"]);
+}
+
+#[test]
+fn syntax_errors_point_at_the_problem() {
+    assert_eq!(syntax(""), (0, "expected SELECT".to_owned()));
+    assert_eq!(
+        syntax("SELECT"),
+        (6, "expected a value or a column name".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT a FROM"),
+        (13, "expected a table name".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT a FROM t LIMIT -1"),
+        (22, "expected a number of rows after LIMIT".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT 'abc FROM t"),
+        (7, "the quote is never closed".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT a # b FROM t"),
+        (9, "unexpected '#'".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT (a FROM t"),
+        (10, "expected \")\"".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT foo(a) FROM t"),
+        (7, "unknown function \"foo\"".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT a FROM t JOIN u"),
+        (22, "expected ON".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT a FROM t u v"),
+        (18, "expected the end of the query".to_owned())
+    );
+    assert_eq!(
+        syntax("SELECT 1.2.3 FROM t"),
+        (7, "not a number".to_owned())
+    );
+}
+
+#[test]
+fn inner_join_on_equal_columns() {
+    // Lima matches twice, and people in no city or an unknown one not at all
+    assert_eq!(
+        rows("SELECT p.name, c.country FROM people p JOIN cities c ON c.name = p.city"),
+        [
+            ["name", "country"],
+            ["Ada", "NO"],
+            ["Bo", "PE"],
+            ["Bo", "US"],
+            ["Cy", "NO"],
+        ]
+    );
+    // Not an equality of columns, so every pair is compared
+    assert_eq!(
+        rows("SELECT p.id, c.country FROM people p JOIN cities c ON p.city = c.name AND p.id > 1"),
+        [["id", "country"], ["2", "PE"], ["2", "US"], ["3", "NO"]]
+    );
+}
+
+#[test]
+fn left_join_keeps_unmatched_rows() {
+    assert_eq!(
+        rows("SELECT p.name, c.country FROM people p LEFT JOIN cities c ON p.city = c.name"),
+        [
+            ["name", "country"],
+            ["Ada", "NO"],
+            ["Bo", "PE"],
+            ["Bo", "US"],
+            ["Cy", "NO"],
+            ["Di", "-"],
+            ["Ed", "-"],
+        ]
+    );
+    assert_eq!(
+        rows(
+            "SELECT p.name FROM people p LEFT JOIN cities c ON p.city = c.name \
+             WHERE c.country IS NULL"
+        ),
+        [["name"], ["Di"], ["Ed"]]
+    );
+}
+
+#[test]
+fn unknown_and_ambiguous_names() {
+    let join = "FROM people p JOIN cities c ON p.city = c.name";
+    assert_eq!(
+        run(&format!("SELECT name {join}")).unwrap_err(),
+        QueryError::AmbiguousColumn("name".to_owned())
+    );
+    // Qualified, or only in one of the tables, is fine
+    assert!(run(&format!("SELECT c.name, country, city {join}")).is_ok());
+    // Both qualified `*` columns called `name` get their table as prefix
+    assert_eq!(
+        rows(&format!("SELECT * {join} LIMIT 0"))[0],
+        ["id", "p.name", "city", "age", "c.name", "country"]
+    );
+
+    assert_eq!(
+        run("SELECT nope FROM people").unwrap_err(),
+        QueryError::UnknownColumn("nope".to_owned())
+    );
+    assert_eq!(
+        run(&format!("SELECT p.country {join}")).unwrap_err(),
+        QueryError::UnknownColumn("p.country".to_owned())
+    );
+    assert_eq!(
+        run("SELECT x.id FROM people").unwrap_err(),
+        QueryError::UnknownTable("x".to_owned())
+    );
+    assert_eq!(
+        run("SELECT id FROM nowhere").unwrap_err(),
+        QueryError::UnknownTable("nowhere".to_owned())
+    );
+    assert_eq!(
+        run("SELECT id FROM people JOIN people ON id = id").unwrap_err(),
+        QueryError::AmbiguousTable("people".to_owned())
+    );
+    assert_eq!(
+        run("SELECT id FROM people ORDER BY 3").unwrap_err(),
+        QueryError::UnknownColumn("#3".to_owned())
+    );
+}
+
+#[test]
+fn aggregates_skip_missing_values() {
+    assert_eq!(
+        rows("SELECT count(*), count(age), sum(age), avg(age), min(age), max(age) FROM people"),
+        [
+            vec![
+                "count(*)",
+                "count(age)",
+                "sum(age)",
+                "avg(age)",
+                "min(age)",
+                "max(age)"
+            ],
+            vec!["5", "3", "95", "31.666666666666668", "25", "40"],
+        ]
+    );
+    // Only missing values, or no rows at all: nothing to sum
+    assert_eq!(
+        rows("SELECT count(age), sum(age), avg(age), max(age) FROM people WHERE age IS NULL"),
+        [
+            ["count(age)", "sum(age)", "avg(age)", "max(age)"],
+            ["0", "-", "-", "-"],
+        ]
+    );
+    assert_eq!(
+        rows("SELECT count(*), min(name) FROM people WHERE id > 99"),
+        [["count(*)", "min(name)"], ["0", "-"]]
+    );
+    assert_eq!(
+        run("SELECT sum(age) FROM people WHERE count(*) > 1").unwrap_err(),
+        QueryError::MisplacedAggregate("WHERE".to_owned())
+    );
+}
+
+#[test]
+fn group_by_and_having() {
+    // Groups come in order of first appearance, missing values are a group
+    assert_eq!(
+        rows("SELECT city, count(*) AS n, avg(age) FROM people GROUP BY city"),
+        [
+            ["city", "n", "avg(age)"],
+            ["Oslo", "2", "35"],
+            ["Lima", "1", "-"],
+            ["Nowhere", "1", "25"],
+            ["-", "1", "-"],
+        ]
+    );
+    assert_eq!(
+        rows("SELECT city FROM people GROUP BY city HAVING count(*) > 1"),
+        [["city"], ["Oslo"]]
+    );
+    assert_eq!(
+        run("SELECT name, count(*) FROM people GROUP BY city").unwrap_err(),
+        QueryError::NotGrouped("name".to_owned())
+    );
+}
+
+#[test]
+fn order_limit_and_distinct() {
+    // Missing values sort last, ties keep their order
+    assert_eq!(
+        rows("SELECT name, age FROM people ORDER BY age"),
+        [
+            ["name", "age"],
+            ["Di", "25"],
+            ["Ada", "30"],
+            ["Cy", "40"],
+            ["Bo", "-"],
+            ["Ed", "-"],
+        ]
+    );
+    assert_eq!(
+        rows("SELECT name AS who FROM people ORDER BY who DESC LIMIT 2"),
+        [["who"], ["Ed"], ["Di"]]
+    );
+    assert_eq!(
+        rows("SELECT city, count(*) FROM people GROUP BY city ORDER BY 2 DESC, city LIMIT 2"),
+        [["city", "count(*)"], ["Oslo", "2"], ["Lima", "1"]]
+    );
+    assert_eq!(rows("SELECT id FROM people LIMIT 0"), [["id"]]);
+    // The limit counts distinct rows
+    assert_eq!(
+        rows("SELECT DISTINCT city FROM people LIMIT 3"),
+        [["city"], ["Oslo"], ["Lima"], ["Nowhere"]]
+    );
+}
@@ -0,0 +1,78 @@
+//! State saved by past versions of the app is still read.
+#![cfg(feature = "persistence")]
+
+use std::collections::HashMap;
+
+use pocket_core::persistence::{Persistence, Versioned, VERSION_KEY};
+use pocket_gui::State;
+
+#[derive(Default)]
+struct Storage(HashMap<String, String>);
+
+impl eframe::Storage for Storage {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_owned(), value);
+    }
+
+    fn flush(&mut self) {}
+}
+
+/// Load `ron` saved without a version, and save it again as the current version.
+fn resave_unversioned(ron: &str) -> Storage {
+    let mut storage = Storage::default();
+    storage.0.insert(eframe::APP_KEY.to_owned(), ron.to_owned());
+
+    let mut persistence = Persistence::default();
+    let state: State = persistence.load(&storage).expect("the state is read");
+    persistence.save(&mut storage, &state);
+    storage
+}
+
+/// The saved state of the database app, leaving out the demo app with windows of its own.
+fn database_windows(storage: &Storage) -> &str {
+    let ron = &storage.0[eframe::APP_KEY];
+    let start = ron
+        .find("database_windows:")
+        .expect("the database app is saved");
+    let end = ron.find("easy_mark_editor:").unwrap_or(ron.len());
+    &ron[start..end]
+}
+
+#[test]
+fn demo_windows_become_datasets() {
+    let storage = resave_unversioned(include_str!("fixtures/wrap_app_state_v0.ron"));
+    assert_eq!(storage.0[VERSION_KEY], State::version().to_string());
+
+    let ron = database_windows(&storage);
+    // The About window was open as a demo
+    assert!(ron.contains("about_is_open:true"), "{ron}");
+    assert!(ron.contains("datasets:("), "{ron}");
+    assert!(!ron.contains("demos:"), "{ron}");
+    assert!(!ron.contains("tests:"), "{ron}");
+}
+
+#[test]
+fn the_rest_is_kept() {
+    let storage = resave_unversioned(include_str!("fixtures/wrap_app_state_v0.ron"));
+
+    let ron = &storage.0[eframe::APP_KEY];
+    assert!(ron.contains("selected_anchor:Clock"), "{ron}");
+    assert!(ron.contains("code:\"# Notes\""), "{ron}");
+    assert!(ron.contains("inspection:true"), "{ron}");
+    // Run modes came later, with their defaults
+    assert!(ron.contains("run_mode:Reactive"), "{ron}");
+}
+
+#[test]
+fn closed_about_window_stays_closed() {
+    let storage = resave_unversioned(
+        "(database:(database_windows:(about_is_open:false,demos:(open:[]),tests:(open:[]))))",
+    );
+
+    let ron = database_windows(&storage);
+    assert!(ron.contains("about_is_open:false"), "{ron}");
+}
@@ -0,0 +1,99 @@
+//! Tables must sort and filter the way their windows show them.
+
+use pocket_gui::database::table::{Column, ColumnData, Table};
+use pocket_gui::database::table_window::{RangeFilter, TableWindow};
+
+fn table() -> Table {
+    Table::new(
+        "t",
+        vec![
+            Column::new(
+                "name",
+                ColumnData::String(vec![
+                    Some("Alpha".to_owned()),
+                    Some("beta".to_owned()),
+                    None,
+                    Some("ALPHABET".to_owned()),
+                    Some("gamma".to_owned()),
+                ]),
+            ),
+            Column::new(
+                "count",
+                ColumnData::Integer(vec![Some(3), None, Some(1), Some(3), Some(12)]),
+            ),
+        ],
+    )
+}
+
+#[test]
+fn missing_values_sort_last_either_way() {
+    let table = table();
+    assert_eq!(table.sorted_rows(1, false), [2, 0, 3, 4, 1]);
+    assert_eq!(table.sorted_rows(1, true), [4, 0, 3, 2, 1]);
+    assert_eq!(table.sorted_rows(0, false), [3, 0, 1, 4, 2]);
+}
+
+#[test]
+fn filters_ignore_case_and_search_every_column() {
+    let mut window = TableWindow::new(table());
+    window.set_filter("alpha".to_owned());
+    assert_eq!(window.shown_rows(), [0, 3]);
+    window.set_filter(" 12 ".to_owned());
+    assert_eq!(window.shown_rows(), [4]);
+    window.set_filter("no such value".to_owned());
+    assert_eq!(window.shown_rows(), [] as [usize; 0]);
+    window.set_filter(String::new());
+    assert_eq!(window.shown_rows(), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn typing_and_deleting_match_filtering_afresh() {
+    let mut window = TableWindow::new(table());
+    window.sort_by(Some((1, true)));
+    let mut typed = String::new();
+    for c in "alphab".chars() {
+        typed.push(c);
+        window.set_filter(typed.clone());
+        let mut fresh = TableWindow::new(table());
+        fresh.sort_by(Some((1, true)));
+        fresh.set_filter(typed.clone());
+        assert_eq!(window.shown_rows(), fresh.shown_rows(), "after {typed:?}");
+    }
+    assert_eq!(window.shown_rows(), [3]);
+    window.set_filter("a".to_owned());
+    assert_eq!(window.shown_rows(), [4, 0, 3, 1]);
+    window.sort_by(None);
+    assert_eq!(window.shown_rows(), [0, 1, 3, 4]);
+}
+
+#[test]
+fn ranges_and_text_filters_combine() {
+    let mut window = TableWindow::new(table());
+    window.set_filter("a".to_owned());
+    window.set_ranges(vec![RangeFilter {
+        column: Some(1),
+        range: 2.0..=5.0,
+    }]);
+    assert_eq!(window.shown_rows(), [0, 3]);
+    window.set_ranges(vec![RangeFilter {
+        column: None,
+        range: 1.0..=2.0,
+    }]);
+    assert_eq!(window.shown_rows(), [0, 1]);
+    window.set_ranges(vec![]);
+    assert_eq!(window.shown_rows(), [0, 1, 3, 4]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn decoded_tables_have_columns_of_one_length() {
+    let json = serde_json::to_string(&table()).unwrap();
+    assert_eq!(serde_json::from_str::<Table>(&json).unwrap(), table());
+
+    let short = json.replacen("[3,null,1,3,12]", "[3,null,1]", 1);
+    assert_ne!(short, json);
+    assert!(serde_json::from_str::<Table>(&short).is_err());
+    let longer = json.replacen("\"num_rows\":5", "\"num_rows\":6", 1);
+    assert_ne!(longer, json);
+    assert!(serde_json::from_str::<Table>(&longer).is_err());
+}
@@ -0,0 +1,38 @@
+//! Embed the Markdown articles in `content/about` so that the web build
+//! (which has no file system) can show them.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let content_dir = Path::new(&manifest_dir).join("content").join("about");
+    println!("cargo:rerun-if-changed={}", content_dir.display());
+
+    let mut paths = std::fs::read_dir(&content_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    paths.sort();
+
+    let mut code = "/// `(file name, contents)` of every article in `content/about`.\n".to_owned();
+    code += "pub const EMBEDDED_ARTICLES: &[(&str, &str)] = &[\n";
+    for path in &paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path.file_name().unwrap().to_string_lossy();
+        writeln!(
+            code,
+            "    ({name:?}, include_str!({:?})),",
+            path.display().to_string()
+        )
+        .unwrap();
+    }
+    code += "];\n";
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("about_articles.rs"), code).unwrap();
+}
@@ -0,0 +1,101 @@
+//! The command line of the native binary.
+
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: pocket_minimal [OPTIONS]
+
+Options:
+  --view <VIEW>                  View to start in, e.g. About or Game.
+                                 Can include a deep link, e.g. 'About/Section 1'
+  --window-size <WIDTH>x<HEIGHT> Initial window size, e.g. 1280x1024
+  --persistence-dir <FOLDER>     Where to store the app state
+  --log-level <LEVEL>            error, warn, info, debug or trace (default: RUST_LOG)
+  --export-html <FOLDER>         Write the About articles as a static site and exit
+  --export-single-html <FILE>    Write the About articles as one printable page and exit
+  --help                         Print this message and exit";
+
+/// What the command line asks for.
+#[derive(Debug, PartialEq)]
+pub enum Cli {
+    Run(Options),
+    /// Write the About articles as a static site into this folder.
+    ExportHtml(PathBuf),
+    /// Write the About articles as one printable page into this file.
+    ExportSingleHtml(PathBuf),
+    Help,
+}
+
+/// How to run the app.
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    /// A deep link to start at, e.g. `About/Section 1`.
+    pub view: Option<String>,
+    pub window_size: Option<[f32; 2]>,
+    pub persistence_path: Option<PathBuf>,
+    /// Used instead of `RUST_LOG`.
+    pub log_level: Option<String>,
+}
+
+/// Parse the arguments, without the name of the binary.
+///
+/// The first export or `--help` wins, and the arguments after it are ignored.
+///
+/// # Errors
+/// If an argument is unknown, or an option is missing its value or has a bad one.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+    let mut options = Options::default();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |usage: &str| args.next().ok_or(format!("Usage: {arg} {usage}"));
+        match arg.as_str() {
+            "--view" => {
+                options.view = Some(value("<VIEW>")?);
+            }
+
+            "--window-size" => {
+                let size = value("<WIDTH>x<HEIGHT>")?;
+                let size = parse_size(&size)
+                    .ok_or(format!("Bad window size {size:?}, expected e.g. 1280x1024"))?;
+                options.window_size = Some(size);
+            }
+
+            "--persistence-dir" => {
+                options.persistence_path = Some(PathBuf::from(value("<FOLDER>")?));
+            }
+
+            "--log-level" => {
+                let level = value("<LEVEL>")?;
+                if level.parse::<log::LevelFilter>().is_err() {
+                    return Err(format!("Unknown log level: {level}"));
+                }
+                options.log_level = Some(level);
+            }
+
+            "--export-html" => {
+                return Ok(Cli::ExportHtml(PathBuf::from(value("<FOLDER>")?)));
+            }
+
+            "--export-single-html" => {
+                return Ok(Cli::ExportSingleHtml(PathBuf::from(value("<FILE>")?)));
+            }
+
+            "--help" | "-h" => {
+                return Ok(Cli::Help);
+            }
+
+            _ => {
+                return Err(format!("Unknown argument: {arg}"));
+            }
+        }
+    }
+    Ok(Cli::Run(options))
+}
+
+/// Parse `1280x1024` into `[1280.0, 1024.0]`.
+fn parse_size(size: &str) -> Option<[f32; 2]> {
+    let (width, height) = size.split_once(['x', 'X'])?;
+    let size: [f32; 2] = [width.trim().parse().ok()?, height.trim().parse().ok()?];
+    size.iter().all(|&x| x > 0.0).then_some(size)
+}
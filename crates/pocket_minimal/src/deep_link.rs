@@ -0,0 +1,53 @@
+//! Deep links like `#About/Section%201.1`: a view name followed by a view-specific sub-path.
+
+/// Build the URL hash for a view and an optional sub-path inside it.
+pub fn format(view: &str, path: Option<&str>) -> String {
+    match path {
+        Some(path) if !path.is_empty() => format!("#{}/{}", encode(view), encode(path)),
+        _ => format!("#{}", encode(view)),
+    }
+}
+
+/// Split a URL hash into the view name and the (decoded) sub-path,
+/// which is empty if there is none.
+pub fn parse(hash: &str) -> (String, String) {
+    let hash = hash.strip_prefix('#').unwrap_or(hash);
+    let (view, path) = hash.split_once('/').unwrap_or((hash, ""));
+    (decode(view), decode(path))
+}
+
+/// Percent-encode everything but unreserved characters and `/`.
+fn encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded += &format!("%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Undo [`encode`]. Malformed escapes are kept as they are.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            // Not `from_str_radix` alone, which also takes a sign as in `%+1`
+            .filter(|hex| hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,161 +1,207 @@
-#[cfg(feature = "glow")]
-use eframe::glow;
+use pocket_core::Command;
 
+use crate::view::{About, Game, View, ViewRegistry};
 use crate::BackendPanel;
-use crate::is_mobile;
-use crate::view::About;
-//use crate::Tree;
 
 // ----------------------------------------------------------------------------
-// Applications
+// STATE
 // ----------------------------------------------------------------------------
 
-
+/// The state that we persist (serialize).
 #[derive(Default)]
-#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
-pub struct Game {}
-
-impl eframe::App for Game {
-    fn update(&mut self, _ctx: &egui::Context, _frame: &mut eframe::Frame) {
-    }
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct State {
+    backend_panel: BackendPanel,
+    /// The [`View::anchor`] of the view shown.
+    selected_view: String,
 }
 
-// ----------------------------------------------------------------------------
-// View
-// ----------------------------------------------------------------------------
+#[cfg(feature = "persistence")]
+impl pocket_core::persistence::Versioned for State {
+    const MIGRATIONS: &'static [pocket_core::persistence::Migration] = &[migrate_fixed_views];
+}
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
-enum View {
+/// The state from before views were registered, when the view shown was one of these.
+#[cfg(feature = "persistence")]
+#[derive(Debug, serde::Deserialize)]
+enum FixedView {
     About,
     Game,
 }
 
-impl View {
-    #[cfg(target_arch = "wasm32")]
-    fn all() -> Vec<Self> {
-        vec![
-            Self::About,
-            Self::Game,
-        ]
-    }
+#[cfg(feature = "persistence")]
+#[derive(serde::Deserialize)]
+struct FixedViewsState {
+    #[serde(default)]
+    backend_panel: BackendPanel,
+    selected_view: FixedView,
+    #[serde(default)]
+    about: Present,
+    #[serde(default)]
+    game: Present,
 }
 
-impl std::fmt::Display for View {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
-    }
-}
+/// Whether a field is there, whatever is in it.
+#[cfg(feature = "persistence")]
+#[derive(Default)]
+struct Present(bool);
 
-impl From<View> for egui::WidgetText {
-    fn from(value: View) -> Self {
-        Self::RichText(egui::RichText::new(value.to_string()))
+#[cfg(feature = "persistence")]
+impl<'de> serde::Deserialize<'de> for Present {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde::de::IgnoredAny::deserialize(deserializer).map(|_| Self(true))
     }
 }
 
-impl Default for View {
-    fn default() -> Self {
-        Self::About
+/// Version 0, from before there were versions, kept the view shown as a [`FixedView`]
+/// until views were registered, and as its [`View::anchor`] since.
+///
+/// The views kept their own state in here too, which is left behind with a warning.
+#[cfg(feature = "persistence")]
+fn migrate_fixed_views(ron: &str) -> Result<String, String> {
+    let Ok(old) = ron::from_str::<FixedViewsState>(ron) else {
+        // Already shaped like version 1, or not state at all
+        return match ron::from_str::<State>(ron) {
+            Ok(_) => Ok(ron.to_owned()),
+            Err(err) => Err(err.to_string()),
+        };
+    };
+    for (view, Present(present)) in [("About", old.about), ("Game", old.game)] {
+        if present {
+            log::warn!("Dropping the state of the {view} view, saved before views were registered");
+        }
     }
+    let state = State {
+        backend_panel: old.backend_panel,
+        selected_view: format!("{:?}", old.selected_view),
+    };
+    ron::to_string(&state).map_err(|err| err.to_string())
 }
 
-// ----------------------------------------------------------------------------
-// COMMAND
-// ----------------------------------------------------------------------------
-
-#[derive(Clone, Copy, Debug)]
-#[must_use]
-enum Command {
-    Nothing,
-    ResetEverything,
-}
+/// Interface to our applications
+pub struct Interface {
+    state: State,
+    views: ViewRegistry,
 
+    #[cfg(feature = "persistence")]
+    persistence: pocket_core::persistence::Persistence,
 
-// ----------------------------------------------------------------------------
-// STATE
-// ----------------------------------------------------------------------------
+    /// The `location.hash` we last routed into the views, so we only follow it when it changes.
+    #[cfg(target_arch = "wasm32")]
+    routed_hash: String,
 
-/// The state that we persist (serialize).
-#[derive(Default)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
-#[cfg_attr(feature = "serde", serde(default))]
-pub struct State { 
-    about: About,
-    game: Game,
-    backend_panel: BackendPanel,
-    selected_view: View
+    /// The `location.hash` we last asked the browser to show.
+    #[cfg(target_arch = "wasm32")]
+    written_hash: String,
 }
 
-/// Interface to our applications
-pub struct Interface { state: State }
-
-
 impl Interface {
+    /// The interface with the built-in views.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        #[allow(unused_mut)]
+        let mut slf = Self {
+            state: State::default(),
+            views: ViewRegistry::default(),
 
-    fn backend_panel(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) -> Command {
-        // The backend-panel can be toggled on/off.
-        // We show a little animation when the user switches it.
-        let is_open =
-            self.state.backend_panel.open || ctx.memory(|mem| mem.everything_is_visible());
+            #[cfg(feature = "persistence")]
+            persistence: Default::default(),
 
-        let mut cmd = Command::Nothing;
+            #[cfg(target_arch = "wasm32")]
+            routed_hash: String::new(),
 
-        egui::SidePanel::right("backend_panel")
-            .resizable(false)
-            .show_animated(ctx, is_open, |ui| {
-                ui.add_space(4.0);
-                ui.vertical_centered(|ui| {
-                    ui.heading("💻 Backend");
-                });
+            #[cfg(target_arch = "wasm32")]
+            written_hash: String::new(),
+        };
+
+        #[cfg(feature = "persistence")]
+        if let Some(storage) = cc.storage {
+            if let Some(state) = slf.persistence.load(storage) {
+                slf.state = state;
+            }
+        }
 
-                ui.separator();
-                self.backend_panel_contents(ui, frame, &mut cmd);
-            });
+        // With the rest of the app's files, not wherever it was started
+        #[cfg(all(feature = "persistence", not(target_arch = "wasm32")))]
+        slf.state
+            .backend_panel
+            .set_recording_dir(eframe::storage_dir(crate::APP_NAME));
 
-        cmd
+        slf.with_view(cc, About::default)
+            .with_view(cc, Game::default)
     }
 
-    fn backend_panel_contents(
-        &mut self,
-        ui: &mut egui::Ui,
-        frame: &mut eframe::Frame,
-        cmd: &mut Command,
-    ) {
-        self.state.backend_panel.ui(ui, frame);
+    /// Add a view to the top bar, after the ones already there.
+    ///
+    /// `make` creates the view, and is called again when the user resets everything.
+    /// A view with the same [`View::anchor`] as an existing one replaces it.
+    #[must_use]
+    pub fn with_view<V: View + 'static>(
+        mut self,
+        cc: &eframe::CreationContext<'_>,
+        make: impl Fn() -> V + 'static,
+    ) -> Self {
+        let view = self.views.add(make);
 
-        ui.separator();
+        #[cfg(feature = "persistence")]
+        if let Some(storage) = cc.storage {
+            view.restore_state(storage, &storage_key(view.anchor()), &mut self.persistence);
+        }
+        #[cfg(not(feature = "persistence"))]
+        let _ = (cc, view);
 
-        ui.horizontal(|ui| {
-            if ui
-                .button("Reset egui")
-                .on_hover_text("Forget scroll, positions, sizes etc")
-                .clicked()
-            {
-                ui.ctx().memory_mut(|mem| *mem = Default::default());
-                ui.close_menu();
-            }
+        self
+    }
 
-            if ui.button("Reset everything").clicked() {
-                *cmd = Command::ResetEverything;
-                ui.close_menu();
-            }
-        });
+    /// The anchor of the view to show, falling back to the first one.
+    fn selected_anchor(&self) -> String {
+        if self.views.get(&self.state.selected_view).is_some() {
+            self.state.selected_view.clone()
+        } else {
+            self.views.first_anchor().unwrap_or_default().to_owned()
+        }
     }
 
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        #[allow(unused_mut)]
-        let mut slf = Self {
-            state: State::default(),
+    /// Show the view and sub-path of a deep link like `#About/Section%201`,
+    /// see [`crate::deep_link`]. The leading `#` is optional.
+    ///
+    /// Returns `false` if there is no such view. If the view has nothing at the
+    /// sub-path, it is still shown.
+    pub fn open_deep_link(&mut self, link: &str) -> bool {
+        let (anchor, path) = crate::deep_link::parse(link);
+        let Some(view) = self.views.get_mut(&anchor) else {
+            log::warn!("No view called {anchor:?}");
+            return false;
         };
+        if !view.open_deep_link(&path) {
+            log::warn!("Nothing at {path:?} in {anchor}");
+        }
+        self.state.selected_view = anchor;
+        true
+    }
 
-        #[cfg(feature = "persistence")]
-        if let Some(storage) = cc.storage {
-            if let Some(state) = eframe::get_value(storage, eframe::APP_KEY) {
-                slf.state = state;
-            }
+    /// Follow a `#View/sub/path` deep link when the URL hash changes.
+    #[cfg(target_arch = "wasm32")]
+    fn route_location_hash(&mut self, frame: &eframe::Frame) {
+        let hash = &frame.info().web_info.location.hash;
+        if *hash == self.routed_hash {
+            return;
         }
+        self.routed_hash.clone_from(hash);
 
-        slf
+        self.open_deep_link(hash);
+    }
+
+    /// Keep the URL hash in sync with what is shown, so that it can be shared.
+    #[cfg(target_arch = "wasm32")]
+    fn write_location_hash(&mut self, ctx: &egui::Context) {
+        let anchor = self.selected_anchor();
+        let path = self.views.get(&anchor).and_then(|view| view.deep_link());
+        let hash = crate::deep_link::format(&anchor, path);
+        if hash != self.written_hash {
+            ctx.open_url(egui::OpenUrl::same_tab(&hash));
+            self.written_hash = hash;
+        }
     }
 
     fn run_cmd(&mut self, ctx: &egui::Context, cmd: Command) {
@@ -163,54 +209,41 @@ impl Interface {
             Command::Nothing => {}
             Command::ResetEverything => {
                 self.state = Default::default();
+                self.views.reset();
                 ctx.memory_mut(|mem| *mem = Default::default());
             }
         }
     }
 
     fn show_selected_view(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        let selected_view = self.state.selected_view;
-        for (_name, view, app) in self.view_iter_mut() {
-            if view == selected_view || ctx.memory(|mem| mem.everything_is_visible()) {
-                app.update(ctx, frame);
+        let selected_view = self.selected_anchor();
+        for view in self.views.iter_mut() {
+            if view.anchor() == selected_view || ctx.memory(|mem| mem.everything_is_visible()) {
+                view.update(ctx, frame);
             }
         }
     }
 
-    /// Interate over the possible views in the interface
-    fn view_iter_mut(&mut self) -> impl Iterator<Item = (&str, View, &mut dyn eframe::App)> {
-        vec![
-            ( "❔About", View::About, &mut self.state.about as &mut dyn eframe::App),
-            ( "🎮 Game", View::Game,  &mut self.state.game  as &mut dyn eframe::App),
-        ].into_iter()
-    }
-
-    fn view_menu_contents(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, cmd: &mut Command) {
-
+    fn view_menu_contents(
+        &mut self,
+        ui: &mut egui::Ui,
+        frame: &mut eframe::Frame,
+        cmd: &mut Command,
+    ) {
         //  Non-Backend Views
-        let mut selected_view = self.state.selected_view;
-        for (name, view, _app) in self.view_iter_mut() {
+        let mut selected_view = self.selected_anchor();
+        for view in self.views.iter() {
+            let label = format!("{} {}", view.icon(), view.name());
             if ui
-                .selectable_label(selected_view == view, name)
+                .selectable_label(selected_view == view.anchor(), label)
                 .clicked()
             {
-                selected_view = view;
-                if frame.is_web() {
-                    ui.ctx()
-                        .open_url(egui::OpenUrl::same_tab(format!("#{view}")));
-                }
+                view.anchor().clone_into(&mut selected_view);
             }
         }
 
         // Backend menu button
-        if is_mobile(ui.ctx()) {
-            ui.menu_button("💻 Backend", |ui| {
-                ui.set_style(ui.ctx().style()); // ignore the "menu" style set by `menu_button`.
-                self.backend_panel_contents(ui, frame, cmd);
-            });
-        } else {
-            ui.toggle_value(&mut self.state.backend_panel.open, "💻 Backend");
-        }
+        self.state.backend_panel.top_bar_ui(ui, frame, cmd);
 
         ui.separator();
 
@@ -229,7 +262,10 @@ impl Interface {
 impl eframe::App for Interface {
     #[cfg(feature = "persistence")]
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, &self.state);
+        self.persistence.save(storage, &self.state);
+        for view in self.views.iter() {
+            view.save_state(storage, &storage_key(view.anchor()), &mut self.persistence);
+        }
     }
 
     fn clear_color(&self, visuals: &egui::Visuals) -> [f32; 4] {
@@ -243,15 +279,9 @@ impl eframe::App for Interface {
     }
 
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-
-        // Check the url for an initial view
+        // Check the url for a view (and article) to show
         #[cfg(target_arch = "wasm32")]
-        if let Some(view) = frame.info().web_info.location.hash.strip_prefix('#') {
-            let view = View::all().into_iter().find(|v| v.to_string() == view);
-            if let Some(v) = view {
-                self.state.selected_view = v;
-            }
-        }
+        self.route_location_hash(frame);
 
         // On native allow full screen
         #[cfg(not(target_arch = "wasm32"))]
@@ -262,26 +292,27 @@ impl eframe::App for Interface {
 
         let mut cmd = Command::Nothing;
 
-        egui::TopBottomPanel::top("wrap_app_top_bar")
-            .frame(egui::Frame::none().inner_margin(4.0))
-            .show(ctx, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.visuals_mut().button_frame = false;
-                    self.view_menu_contents(ui, frame, &mut cmd);
-                });
-            });
+        pocket_core::top_bar(ctx, |ui| self.view_menu_contents(ui, frame, &mut cmd));
 
         // egui::Window::new("My Window")
         //     //.open(&mut self.state.about)
         //     .show(ctx, |ui| { ui.label("Hello World!");});
 
-
         self.state.backend_panel.update(ctx, frame);
-        if !is_mobile(ctx) {
-            cmd = self.backend_panel(ctx, frame);
-        }
+        self.state
+            .backend_panel
+            .side_panel(ctx, frame, egui::panel::Side::Right, &mut cmd);
         self.show_selected_view(ctx, frame);
         self.state.backend_panel.end_of_frame(ctx);
         self.run_cmd(ctx, cmd);
+
+        #[cfg(target_arch = "wasm32")]
+        self.write_location_hash(ctx);
     }
-}
\ No newline at end of file
+}
+
+/// Where the state of the view with the given anchor is stored.
+#[cfg(feature = "persistence")]
+fn storage_key(anchor: &str) -> String {
+    format!("{}/{anchor}", eframe::APP_KEY)
+}
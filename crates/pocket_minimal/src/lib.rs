@@ -1,24 +1,23 @@
 mod interface;
 #[doc(inline)]
-pub use interface::Interface;
+pub use interface::{Interface, State};
 
 #[cfg(target_arch = "wasm32")]
 mod web;
 #[cfg(target_arch = "wasm32")]
 pub use web::*;
 
-mod backend_panel;
+/// The name of the app, which is also where it keeps its state.
+pub const APP_NAME: &str = "pocket_minimal";
+
 #[doc(inline)]
-pub use backend_panel::BackendPanel;
+pub use pocket_core::{is_mobile, BackendPanel, FrameHistory, FrameTimes};
 
-mod frame_history;
-pub use frame_history::FrameHistory;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod deep_link;
 
-pub mod view;
+mod tree;
+pub use tree::{Node, Orphans, Tree, TreeState};
 
-/// Detect narrow screens. This is used to show a simpler UI on mobile devices,
-/// especially for the web demo at <https://egui.rs>.
-pub fn is_mobile(ctx: &egui::Context) -> bool {
-    let screen_size = ctx.screen_rect().size();
-    screen_size.x < 550.0
-}
+pub mod view;
@@ -0,0 +1,81 @@
+//! `pocket_minimal` on native
+
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
+#![allow(rustdoc::missing_crate_level_docs)] // it's an example
+#![allow(clippy::never_loop)] // False positive
+
+use pocket_minimal::cli::{self, Cli};
+use pocket_minimal::view::About;
+
+// When compiling natively:
+fn main() -> eframe::Result {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(Cli::Run(options)) => options,
+        Ok(Cli::ExportHtml(dir)) => {
+            let pages = About::default()
+                .write_site(&dir)
+                .unwrap_or_else(|err| panic!("Failed to export to {}: {err}", dir.display()));
+            println!("Wrote {pages} pages to {}", dir.display());
+            return Ok(());
+        }
+        Ok(Cli::ExportSingleHtml(path)) => {
+            std::fs::write(&path, About::default().export_single_page())
+                .unwrap_or_else(|err| panic!("Failed to write {}: {err}", path.display()));
+            println!("Wrote {}", path.display());
+            return Ok(());
+        }
+        Ok(Cli::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(err) => {
+            panic!("{err}\n\n{}", cli::USAGE);
+        }
+    };
+    let cli::Options {
+        view,
+        window_size,
+        persistence_path,
+        log_level,
+    } = options;
+
+    {
+        // Silence wgpu log spam (https://github.com/gfx-rs/wgpu/issues/3206)
+        let mut rust_log = log_level
+            .or_else(|| std::env::var("RUST_LOG").ok())
+            .unwrap_or_else(|| {
+                if cfg!(debug_assertions) {
+                    "debug".to_owned()
+                } else {
+                    "info".to_owned()
+                }
+            });
+        for loud_crate in ["naga", "wgpu_core", "wgpu_hal"] {
+            if !rust_log.contains(&format!("{loud_crate}=")) {
+                rust_log += &format!(",{loud_crate}=warn");
+            }
+        }
+        std::env::set_var("RUST_LOG", rust_log);
+    }
+
+    env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size(window_size.unwrap_or([1280.0, 1024.0]))
+            .with_drag_and_drop(true),
+        persistence_path,
+        ..Default::default()
+    };
+    eframe::run_native(
+        pocket_minimal::APP_NAME,
+        options,
+        Box::new(move |cc| {
+            let mut interface = pocket_minimal::Interface::new(cc);
+            if let Some(view) = &view {
+                interface.open_deep_link(view);
+            }
+            Ok(Box::new(interface))
+        }),
+    )
+}
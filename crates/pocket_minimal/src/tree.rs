@@ -1,65 +1,372 @@
-use egui::{CollapsingHeader, Ui};
+use std::collections::{BTreeMap, BTreeSet};
 
-#[derive(Clone)]
+use egui::collapsing_header::CollapsingState;
+use egui::{Id, Ui};
+
+// ----------------------------------------------------------------------------
+// Tree
+// ----------------------------------------------------------------------------
+
+/// A tree of titles, e.g. a table of contents.
+///
+/// Every node has an [`Id`] derived from the titles on its path from the root
+/// (and, for siblings sharing a title, their rank among them), so it stays the
+/// same when unrelated nodes are added, removed or reordered.
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Tree {
-    pub children: Vec<Tree>,
+    pub id: u64,
     pub root: String,
-    pub selected: Option<String>,
+    pub children: Vec<Tree>,
 }
 
 impl Default for Tree {
     fn default() -> Self {
-        Self::new("root")
+        Self::new("Root", vec![])
+    }
+}
+
+/// An entry for [`Tree::from_parents`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node<'a> {
+    pub title: &'a str,
+    pub parent: &'a str,
+    /// Position among its siblings. Siblings without one come last.
+    /// Ties are broken by title.
+    pub order: Option<i32>,
+}
+
+/// Titles that [`Tree::from_parents`] could not attach to the tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Orphans {
+    /// Nodes whose parent does not exist.
+    pub missing_parent: Vec<String>,
+
+    /// Nodes whose parent exists, but never connects to the root
+    /// (their ancestors form a cycle).
+    pub unreachable: Vec<String>,
+}
+
+impl Orphans {
+    pub fn is_empty(&self) -> bool {
+        self.missing_parent.is_empty() && self.unreachable.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.missing_parent.iter().chain(&self.unreachable)
     }
 }
 
 impl Tree {
+    pub fn new(root: &str, children: Vec<&str>) -> Self {
+        let mut tree = Self {
+            id: Id::new(root).value(),
+            root: root.to_owned(),
+            children: children
+                .into_iter()
+                .map(|c| Self {
+                    id: 0,
+                    root: c.to_owned(),
+                    children: vec![],
+                })
+                .collect(),
+        };
+        tree.assign_child_ids();
+        tree
+    }
+
+    /// Build the tree below `root` from a flat list of nodes naming their parents.
+    ///
+    /// Nodes are attached parent-first, whatever order they come in,
+    /// and the ones that cannot be attached are returned instead of being dropped.
+    /// Nodes sharing a title are all attached; the children naming that title
+    /// as their parent go below the first of them.
+    pub fn from_parents<'a>(
+        root: &str,
+        nodes: impl IntoIterator<Item = Node<'a>>,
+    ) -> (Self, Orphans) {
+        let nodes: Vec<Node<'a>> = nodes.into_iter().collect();
 
-    pub fn new(root: &str) -> Self {
-        Self { children: Vec::new(), root: root.to_string(), selected: None }
+        let mut children_of: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (index, node) in nodes.iter().enumerate() {
+            children_of.entry(node.parent).or_default().push(index);
+        }
+        for siblings in children_of.values_mut() {
+            siblings.sort_by(|&a, &b| {
+                let order = |node: &Node<'_>| node.order.map_or((1, 0), |order| (0, order));
+                let (a, b) = (&nodes[a], &nodes[b]);
+                order(a).cmp(&order(b)).then_with(|| a.title.cmp(b.title))
+            });
+        }
+
+        // Attach depth first from the root, so every parent comes before its children.
+        let mut attach = Attach {
+            nodes: &nodes,
+            children_of: &children_of,
+            attached: vec![false; nodes.len()],
+            expanded: BTreeSet::from([root]),
+        };
+        let mut tree = Self::new(root, vec![]);
+        attach.children(&mut tree);
+        tree.assign_child_ids();
+
+        let titles = nodes.iter().map(|node| node.title).collect::<BTreeSet<_>>();
+        let mut orphans = Orphans::default();
+        for (node, attached) in nodes.iter().zip(attach.attached) {
+            if attached {
+                continue;
+            }
+            if node.parent == root || titles.contains(node.parent) {
+                orphans.unreachable.push(node.title.to_owned());
+            } else {
+                orphans.missing_parent.push(node.title.to_owned());
+            }
+        }
+
+        (tree, orphans)
+    }
+
+    /// Give every descendant an [`Id`] based on its path from this node.
+    fn assign_child_ids(&mut self) {
+        let parent = Id::new(self.id);
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        for child in &mut self.children {
+            let rank = seen.entry(child.root.clone()).or_default();
+            child.id = parent.with((&child.root, *rank)).value();
+            *rank += 1;
+            child.assign_child_ids();
+        }
+    }
+
+    /// Is `title` this node or one of its descendants?
+    pub fn contains(&self, title: &str) -> bool {
+        self.root == title || self.children.iter().any(|c| c.contains(title))
+    }
+
+    /// Every node below the root, depth first, with its number (ex. `[1, 2]` for "1.2").
+    pub fn numbered(&self) -> Vec<(Vec<u32>, &Self)> {
+        fn walk<'a>(tree: &'a Tree, prefix: &[u32], out: &mut Vec<(Vec<u32>, &'a Tree)>) {
+            for (i, child) in tree.children.iter().enumerate() {
+                let number = prefix
+                    .iter()
+                    .copied()
+                    .chain([i as u32 + 1])
+                    .collect::<Vec<_>>();
+                out.push((number.clone(), child));
+                walk(child, &number, out);
+            }
+        }
+
+        let mut out = vec![];
+        walk(self, &[], &mut out);
+        out
     }
-    pub fn ui(&self, ui: &mut Ui, show_root: bool) {
-        if show_root {
-            self.ui_impl(ui, 0, &self.root);
+
+    /// The ids of this node and all its descendants.
+    pub fn ids(&self) -> Vec<u64> {
+        std::iter::once(self.id)
+            .chain(self.children.iter().flat_map(|c| c.ids()))
+            .collect()
+    }
+
+    /// Find the node with the given `id`.
+    pub fn find(&self, id: u64) -> Option<&Self> {
+        if self.id == id {
+            Some(self)
         } else {
-            self.children_ui(ui, 1)
+            self.children.iter().find_map(|c| c.find(id))
         }
     }
 
-    fn ui_impl(&self, ui: &mut Ui, depth: usize, name: &str) {
-        // If this node has children made collapsible header
-        if self.children.len() > 0 {
-            CollapsingHeader::new(name)
-                .default_open(depth < 1)
-                .show(ui, |ui| {
-                    self.children_ui(ui, depth)
-                });
-        } 
-        // Otherwise just indent it for alignment
-        else {
-            ui.indent(name, |ui| {
-                if ui
-                    .selectable_label(self.selected == Some(name.to_string()), name)
-                    .clicked() {}
-            });
+    /// Does this node, or any of its descendants, appear in `titles`?
+    fn contains_any(&self, titles: &BTreeSet<String>) -> bool {
+        titles.contains(&self.root) || self.children.iter().any(|c| c.contains_any(titles))
+    }
+}
+
+/// What [`Tree::from_parents`] has attached so far.
+struct Attach<'n, 'a> {
+    nodes: &'n [Node<'a>],
+    /// Indices into `nodes`, keyed by parent title.
+    children_of: &'n BTreeMap<&'a str, Vec<usize>>,
+    /// Keyed by index, since titles need not be unique.
+    attached: Vec<bool>,
+    /// Titles whose children were attached already.
+    ///
+    /// Children of a title that appears more than once go below its first node,
+    /// and this also breaks cycles through the root.
+    expanded: BTreeSet<&'a str>,
+}
+
+impl<'n, 'a> Attach<'n, 'a> {
+    fn children(&mut self, tree: &mut Tree) {
+        let Some(children) = self.children_of.get(tree.root.as_str()) else {
+            return;
+        };
+        for &index in children {
+            self.attached[index] = true;
+            let title = self.nodes[index].title;
+            let mut child = Tree {
+                id: 0,
+                root: title.to_owned(),
+                children: vec![],
+            };
+            if self.expanded.insert(title) {
+                self.children(&mut child);
+            }
+            tree.children.push(child);
         }
     }
+}
+
+// ----------------------------------------------------------------------------
+// TreeState
+// ----------------------------------------------------------------------------
+
+/// Which nodes of a [`Tree`] the user expanded or collapsed, keyed by [`Tree::id`].
+///
+/// Nodes that are not in here use the default of [`Tree::ui`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TreeState {
+    open: BTreeMap<u64, bool>,
+}
+
+impl TreeState {
+    pub fn is_open(&self, id: u64) -> Option<bool> {
+        self.open.get(&id).copied()
+    }
 
-    fn children_ui(&self, ui: &mut Ui, depth: usize) {
-        self
-            .children
-            .iter()
-            .for_each(|tree| { tree.ui_impl(ui, depth + 1, &tree.root);});
+    pub fn set_open(&mut self, id: u64, open: bool) {
+        self.open.insert(id, open);
     }
 
-    pub fn toc() -> Self {
-        let mut section_1  = Tree::new("Section 1");
-        let section_1_1    = Tree::new("Section 1.1");
-        section_1.children = vec![section_1_1];
-        let section_2      = Tree::new("Section 2");
-        let mut tree = Tree::new("Table of Contents");
-        tree.children      = vec![section_1, section_2];
-        tree
+    /// Expand or collapse `tree` and all of its descendants.
+    pub fn set_open_recursive(&mut self, tree: &Tree, open: bool) {
+        for id in tree.ids() {
+            self.set_open(id, open);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// UI
+// ----------------------------------------------------------------------------
+
+/// Everything [`Tree::ui_recursive`] needs besides the node itself.
+struct TreeUi<'a> {
+    default_depth: u32,
+    filter: Option<&'a BTreeSet<String>>,
+    state: &'a mut TreeState,
+    selected: String,
+    context_menu: &'a mut dyn FnMut(&mut Ui, &Tree),
+}
+
+impl Tree {
+    /// Show the tree and return the selected title.
+    ///
+    /// Nodes shallower than `default_depth` start out expanded; after that
+    /// `state` remembers what the user toggled.
+    /// If `filter` is set, only branches leading to one of its titles are shown, fully expanded.
+    /// `context_menu` fills the menu shown when right-clicking a node.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ui(
+        &self,
+        ui: &mut Ui,
+        show_root: bool,
+        default_depth: u32,
+        selected: &str,
+        filter: Option<&BTreeSet<String>>,
+        state: &mut TreeState,
+        context_menu: &mut dyn FnMut(&mut Ui, &Self),
+    ) -> String {
+        let mut tree_ui = TreeUi {
+            default_depth,
+            filter,
+            state,
+            selected: selected.to_owned(),
+            context_menu,
+        };
+
+        // If we don't want to show the root, we'll start the tree by iterating
+        // over children
+        let trees = if show_root {
+            vec![self]
+        } else {
+            self.children.iter().collect()
+        };
+        // Render each tree(s) UI
+        for (i, tree) in trees.into_iter().enumerate() {
+            if filter.map_or(true, |filter| tree.contains_any(filter)) {
+                tree.ui_recursive(ui, 0, &[i as u32], &mut tree_ui);
+            }
+        }
+
+        // Return the tree button that is selected
+        tree_ui.selected
+    }
+
+    fn ui_recursive(&self, ui: &mut Ui, depth: u32, prefix: &[u32], tree_ui: &mut TreeUi<'_>) {
+        let name = &self.root;
+
+        // Configure a numeric prefix (ex. "1", "1.1", "2.3.1")
+        let mut prefix = prefix.to_vec();
+        if let Some(last) = prefix.last_mut() {
+            *last += 1;
+        }
+
+        // Create a new collapsing element, remembering what the user toggled
+        let id = ui.make_persistent_id(self.id);
+        let default_open = depth < tree_ui.default_depth;
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, default_open);
+        if tree_ui.filter.is_some() {
+            state.set_open(true);
+        } else {
+            state.set_open(tree_ui.state.is_open(self.id).unwrap_or(default_open));
+        }
+
+        // Configure the header
+        let header = state.show_header(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    prefix
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<String>>()
+                        .join("."),
+                );
+                let button = ui.selectable_label(tree_ui.selected == *name, name);
+                button.context_menu(|ui| (tree_ui.context_menu)(ui, self));
+                if button.clicked() {
+                    tree_ui.selected.clone_from(name);
+                };
+            })
+        });
+        // Configure the body
+        let (collapse, _, _) = header.body(|ui| {
+            for (i, c) in self.children.iter().enumerate() {
+                // Skip branches without search hits, but keep their number
+                if tree_ui.filter.is_some_and(|filter| !c.contains_any(filter)) {
+                    continue;
+                }
+                let c_prefix = prefix
+                    .iter()
+                    .copied()
+                    .chain([i as u32])
+                    .collect::<Vec<u32>>();
+                c.ui_recursive(ui, depth + 1, &c_prefix, tree_ui);
+            }
+        });
+        collapse.context_menu(|ui| (tree_ui.context_menu)(ui, self));
+
+        // Remember if the user toggled the node
+        if tree_ui.filter.is_none() {
+            if let Some(state) = CollapsingState::load(ui.ctx(), id) {
+                if state.is_open() != tree_ui.state.is_open(self.id).unwrap_or(default_open) {
+                    tree_ui.state.set_open(self.id, state.is_open());
+                }
+            }
+        }
     }
-}
\ No newline at end of file
+}
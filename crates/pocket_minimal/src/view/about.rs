@@ -1,225 +1,1414 @@
 use egui::Ui;
-use egui::collapsing_header::CollapsingState;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::tree::{Node, Orphans, Tree, TreeState};
+
+use super::export;
+use super::markdown::{self, Block};
+use super::search::{self, SearchIndex};
+
+/// The title of the root of the table of contents.
+///
+/// Articles without a `parent` in their front matter are placed here.
+pub const ROOT: &str = "Table of Contents";
+
+/// The name of this view in deep links like `#About/Section%201`.
+const VIEW_NAME: &str = "About";
+
+/// Title given to articles added from the table of contents.
+const UNTITLED: &str = "Untitled";
+
+const BACK_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::ArrowLeft);
+const FORWARD_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::ArrowRight);
+
+// Markdown articles from `content/about`, embedded by `build.rs` as `EMBEDDED_ARTICLES`.
+include!(concat!(env!("OUT_DIR"), "/about_articles.rs"));
 
 // ----------------------------------------------------------------------------
 // Articles
 // ----------------------------------------------------------------------------
 
+/// Why a Markdown file could not be turned into an [`Article`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArticleError {
+    /// The file does not start with a `---` delimited front matter block.
+    MissingFrontMatter,
+
+    /// The front matter has no `title` key.
+    MissingTitle,
+}
+
+impl std::fmt::Display for ArticleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingFrontMatter => write!(f, "missing '---' front matter"),
+            Self::MissingTitle => write!(f, "front matter has no 'title'"),
+        }
+    }
+}
+
+impl std::error::Error for ArticleError {}
+
 pub struct Article {
-    title: String,
-    content: Box<dyn Fn(&mut Ui)>,
-    parent: String,
-    //children: Vec<Article>
+    pub title: String,
+    pub parent: String,
+    /// Position among the articles with the same parent, see [`Node::order`].
+    pub order: Option<i32>,
+    /// The file the article was read from, and is saved to.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub path: Option<std::path::PathBuf>,
+    /// The Markdown body, without the front matter.
+    pub source: String,
+    blocks: Vec<Block>,
 }
 
 impl Default for Article {
     fn default() -> Self {
+        Self::new("", ROOT, "")
+    }
+}
+
+impl Article {
+    pub fn new(title: &str, parent: &str, source: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            parent: parent.to_owned(),
+            order: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            path: None,
+            source: source.to_owned(),
+            blocks: markdown::parse(source),
+        }
+    }
+
+    /// Parse a Markdown file with a front matter block:
+    ///
+    /// ```text
+    /// ---
+    /// title: Section 1.1
+    /// parent: Section 1
+    /// order: 1
+    /// ---
+    /// This is an article about *Section 1.1*.
+    /// ```
+    ///
+    /// # Errors
+    /// If the front matter is missing or has no `title`.
+    pub fn from_markdown(text: &str) -> Result<Self, ArticleError> {
+        let mut lines = text.trim_start_matches('\u{feff}').lines();
+        if lines.next().map(str::trim) != Some("---") {
+            return Err(ArticleError::MissingFrontMatter);
+        }
+
+        let (mut title, mut parent, mut order) = (None, None, None);
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line.trim() == "---" {
+                closed = true;
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim().trim_matches('"').to_owned();
+                match key.trim() {
+                    "title" => title = Some(value),
+                    "parent" => parent = Some(value),
+                    "order" => order = value.parse().ok(),
+                    _ => {} // ignore unknown keys
+                }
+            }
+        }
+        if !closed {
+            return Err(ArticleError::MissingFrontMatter);
+        }
+
+        let body = lines.collect::<Vec<_>>().join("\n");
+        let title = title
+            .filter(|t| !t.is_empty())
+            .ok_or(ArticleError::MissingTitle)?;
+        let parent = parent
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| ROOT.to_owned());
+
+        Ok(Self {
+            order,
+            ..Self::new(&title, &parent, body.trim())
+        })
+    }
+
+    /// The inverse of [`Self::from_markdown`]: the front matter followed by the body.
+    pub fn to_markdown(&self) -> String {
+        let mut text = format!("---\ntitle: {}\n", self.title);
+        if self.parent != ROOT {
+            text += &format!("parent: {}\n", self.parent);
+        }
+        if let Some(order) = self.order {
+            text += &format!("order: {order}\n");
+        }
+        text += "---\n";
+        text += &self.source;
+        text.push('\n');
+        text
+    }
+
+    /// A copy with a different title, parent or body, keeping everything else.
+    fn with(&self, title: &str, parent: &str, source: &str) -> Self {
         Self {
-            title: String::new(),
-            content: Box::new(|_| {}),
-            parent: String::new(),
-            //children: Vec::new(),
+            order: self.order,
+            #[cfg(not(target_arch = "wasm32"))]
+            path: self.path.clone(),
+            ..Self::new(title, parent, source)
         }
     }
+
+    /// The plain text of the body, without any markup.
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(Block::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub(super) fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// The titles of the articles linked to with `[[title]]`.
+    pub fn links(&self) -> impl Iterator<Item = &str> {
+        self.blocks.iter().flat_map(Block::article_links)
+    }
+
+    /// Show the article, highlighting the search terms in `highlight`.
+    ///
+    /// Returns the title of the article whose `[[link]]` was clicked, if any.
+    pub fn ui(&self, ui: &mut Ui, highlight: &[String]) -> Option<String> {
+        markdown::ui(
+            ui,
+            &[Block::Heading {
+                level: 1,
+                spans: vec![markdown::Span {
+                    text: self.title.clone(),
+                    ..Default::default()
+                }],
+            }],
+            highlight,
+        );
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| markdown::ui(ui, &self.blocks, highlight))
+            .inner
+    }
 }
 
-impl Article {
-    pub fn ui(&self, ui: &mut Ui) {
-        (self.content)(ui);
+/// A problem found by [`About::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContentError {
+    /// `article` has a `[[target]]` link, but there is no article called `target`.
+    BrokenLink { article: String, target: String },
+
+    /// The `parent` of `article` is not an article.
+    MissingParent { article: String, parent: String },
+
+    /// The parent exists, but `article` still did not end up in the table of contents,
+    /// e.g. because its parents form a cycle.
+    NotInTableOfContents { article: String },
+
+    /// The file `skipped` has the same title as `kept`, which was read instead.
+    DuplicateTitle {
+        title: String,
+        kept: String,
+        skipped: String,
+    },
+}
+
+impl std::fmt::Display for ContentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BrokenLink { article, target } => {
+                write!(f, "{article:?} links to missing article {target:?}")
+            }
+            Self::MissingParent { article, parent } => {
+                write!(f, "{article:?} has missing parent {parent:?}")
+            }
+            Self::NotInTableOfContents { article } => {
+                write!(f, "{article:?} is not reachable from the table of contents")
+            }
+            Self::DuplicateTitle {
+                title,
+                kept,
+                skipped,
+            } => {
+                write!(
+                    f,
+                    "{skipped} is skipped: {kept} is already called {title:?}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+// ----------------------------------------------------------------------------
+// History
+// ----------------------------------------------------------------------------
+
+/// How many articles we remember in each direction.
+const MAX_HISTORY: usize = 100;
+
+/// The articles visited before and after the selected one.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+struct History {
+    back: Vec<String>,
+    forward: Vec<String>,
+}
+
+impl History {
+    /// Record that we left `from` for a new article.
+    fn visit(&mut self, from: String) {
+        self.back.push(from);
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    fn back(&mut self, current: &mut String) -> bool {
+        let Some(previous) = self.back.pop() else {
+            return false;
+        };
+        self.forward.push(std::mem::replace(current, previous));
+        true
+    }
+
+    /// Replace every visit of `old` by `new`.
+    fn rename(&mut self, old: &str, new: &str) {
+        for title in self.back.iter_mut().chain(&mut self.forward) {
+            if title == old {
+                new.clone_into(title);
+            }
+        }
+    }
+
+    /// Forget every visit of `title`.
+    fn remove(&mut self, title: &str) {
+        self.back.retain(|t| t != title);
+        self.forward.retain(|t| t != title);
+    }
+
+    fn forward(&mut self, current: &mut String) -> bool {
+        let Some(next) = self.forward.pop() else {
+            return false;
+        };
+        self.back.push(std::mem::replace(current, next));
+        true
     }
 }
 
+// ----------------------------------------------------------------------------
+// Table of contents actions
+// ----------------------------------------------------------------------------
+
+/// Something picked from the context menu of a table of contents entry.
+enum TocAction {
+    CopyLink(String),
+    Detach(String),
+    SetOpen { id: u64, open: bool },
+    Rename(String),
+    AddChild(String),
+    Delete(String),
+}
+
+/// A table of contents action waiting for the user to confirm it.
+enum TocDialog {
+    Rename { title: String, new_title: String },
+    Delete(String),
+}
+
+/// The context menu of the table of contents entry `node`.
+fn toc_context_menu(ui: &mut Ui, node: &Tree, editing: bool, actions: &mut Vec<TocAction>) {
+    let title = &node.root;
+    let mut action = None;
+    if ui.button("🔗 Copy link").clicked() {
+        action = Some(TocAction::CopyLink(title.clone()));
+    }
+    if ui.button("🗗 Open in new window").clicked() {
+        action = Some(TocAction::Detach(title.clone()));
+    }
+    if !node.children.is_empty() {
+        ui.separator();
+        if ui.button("⊞ Expand all").clicked() {
+            action = Some(TocAction::SetOpen {
+                id: node.id,
+                open: true,
+            });
+        }
+        if ui.button("⊟ Collapse all").clicked() {
+            action = Some(TocAction::SetOpen {
+                id: node.id,
+                open: false,
+            });
+        }
+    }
+    if editing {
+        ui.separator();
+        if ui.button("✏ Rename…").clicked() {
+            action = Some(TocAction::Rename(title.clone()));
+        }
+        if ui.button("➕ Add child").clicked() {
+            action = Some(TocAction::AddChild(title.clone()));
+        }
+        if ui.button("🗑 Delete…").clicked() {
+            action = Some(TocAction::Delete(title.clone()));
+        }
+    }
+    if let Some(action) = action {
+        actions.push(action);
+        ui.close_menu();
+    }
+}
+
+/// Point every `[[old]]` and `[[old|label]]` link in `source` at `new` instead.
+fn rename_links(source: &str, old: &str, new: &str) -> String {
+    source
+        .replace(&format!("[[{old}]]"), &format!("[[{new}]]"))
+        .replace(&format!("[[{old}|"), &format!("[[{new}|"))
+}
+
 // ----------------------------------------------------------------------------
 // About: View
 // ----------------------------------------------------------------------------
 
-pub struct About
-{
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct About {
+    #[cfg_attr(feature = "serde", serde(skip))]
     table_of_contents: Tree,
+    /// Articles that could not be placed in the table of contents.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    orphans: Orphans,
+    /// Files that were not read because another one has the same title.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    duplicates: Vec<ContentError>,
+    /// Which chapters of the table of contents are expanded.
+    tree_state: TreeState,
     selected: String,
+    history: History,
+    #[cfg_attr(feature = "serde", serde(skip))]
     articles: BTreeMap<String, Article>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    search_index: SearchIndex,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    query: String,
+
+    /// Whether articles can be renamed, added and deleted.
+    editing: bool,
+    /// Articles shown in their own window.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    detached: BTreeSet<String>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    toc_dialog: Option<TocDialog>,
+
+    /// Were the articles changed since they were last saved?
+    #[cfg_attr(feature = "serde", serde(skip))]
+    unsaved: bool,
+    /// The folder the articles were read from, and new ones are saved to.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    content_dir: Option<std::path::PathBuf>,
+    /// Files of deleted articles, removed on the next save.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    deleted_files: Vec<std::path::PathBuf>,
+    /// Every article as Markdown, when they were saved to the app storage
+    /// instead of a folder (always the case on the web).
+    saved: Option<Vec<String>>,
+    /// Have the `saved` articles replaced the default ones yet?
+    #[cfg_attr(feature = "serde", serde(skip))]
+    restored: bool,
+    /// The outcome of the last save.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    save_status: Option<String>,
+
+    /// Where the static site is exported to.
+    #[cfg(not(target_arch = "wasm32"))]
+    export_dir: String,
+    /// The outcome of the last export, shown in the export menu.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    export_status: Option<String>,
 }
 
 impl Default for About {
+    /// On native, the Markdown files are read from the directory in the
+    /// `POCKET_ABOUT_DIR` environment variable, or from `content/about` in
+    /// this crate, so that pages can be edited without recompiling.
+    /// Otherwise (and always on the web) the copies embedded at build time are used.
     fn default() -> Self {
-        let mut about = About::new();
-        about.articles = About::articles();
-        about.table_of_contents = about.table_of_contents();
-        about.selected = "Table of Contents".to_string();
-        about
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let dir = std::env::var_os("POCKET_ABOUT_DIR").map_or_else(
+                || std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/content/about")),
+                std::path::PathBuf::from,
+            );
+            if dir.is_dir() {
+                match Self::load_dir(&dir) {
+                    Ok((articles, duplicates)) => {
+                        let mut about = Self::from_articles(articles);
+                        about.content_dir = Some(dir);
+                        about.duplicates = duplicates;
+                        return about;
+                    }
+                    Err(err) => log::warn!("Failed to read articles from {}: {err}", dir.display()),
+                }
+            }
+        }
+
+        Self::from_articles(Self::embedded())
     }
 }
 
 impl About {
+    /// Build the view from a set of articles, keyed by title.
+    pub fn from_articles(articles: BTreeMap<String, Article>) -> Self {
+        let mut about = Self {
+            articles,
+            table_of_contents: Tree::new(ROOT, vec![]),
+            orphans: Orphans::default(),
+            duplicates: vec![],
+            tree_state: TreeState::default(),
+            selected: ROOT.to_owned(),
+            history: History::default(),
+            search_index: SearchIndex::default(),
+            query: String::new(),
+            editing: false,
+            detached: BTreeSet::new(),
+            toc_dialog: None,
+            unsaved: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            content_dir: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            deleted_files: vec![],
+            saved: None,
+            restored: false,
+            save_status: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            export_dir: "about_html".to_owned(),
+            export_status: None,
+        };
+        about.rebuild();
+        for problem in about.validate() {
+            log::warn!("{problem}");
+        }
+        about
+    }
 
-    fn new() -> Self {
-        Self {
-            articles: BTreeMap::new(),
-            table_of_contents: Tree::new("Table of Contents", vec![]),
-            selected: String::new(),
+    /// Rebuild the table of contents and search index after the articles changed.
+    fn rebuild(&mut self) {
+        (self.table_of_contents, self.orphans) = Tree::from_parents(
+            ROOT,
+            self.articles.values().map(|article| Node {
+                title: &article.title,
+                parent: &article.parent,
+                order: article.order,
+            }),
+        );
+        self.search_index = SearchIndex::new(&self.articles);
+    }
+
+    /// Check that every `[[link]]` and `parent` points at an existing article,
+    /// that every article made it into the table of contents,
+    /// and that no file was skipped for having the title of another.
+    pub fn validate(&self) -> Vec<ContentError> {
+        let exists = |title: &str| title == ROOT || self.articles.contains_key(title);
+
+        let mut problems = vec![];
+        for (title, article) in &self.articles {
+            for target in article.links() {
+                if !exists(target) {
+                    problems.push(ContentError::BrokenLink {
+                        article: title.clone(),
+                        target: target.to_owned(),
+                    });
+                }
+            }
         }
+
+        for title in &self.orphans.missing_parent {
+            problems.push(ContentError::MissingParent {
+                article: title.clone(),
+                parent: self.articles[title].parent.clone(),
+            });
+        }
+        for title in &self.orphans.unreachable {
+            problems.push(ContentError::NotInTableOfContents {
+                article: title.clone(),
+            });
+        }
+        problems.extend(self.duplicates.iter().cloned());
+        problems
     }
 
-    fn articles() -> BTreeMap<String, Article> {
+    /// The title of the article being shown.
+    pub fn selected(&self) -> &str {
+        &self.selected
+    }
 
-        let mut articles = BTreeMap::new();
+    /// Show the article called `title`, remembering the current one in the history.
+    pub fn select(&mut self, title: &str) {
+        if self.selected != title {
+            let from = std::mem::replace(&mut self.selected, title.to_owned());
+            self.history.visit(from);
+        }
+    }
 
-        let (title, parent) = ("Section 1".to_string(), "Table of Contents".to_string());
-        let mut article = Article { title: title.clone(), parent, ..Default::default() };
-        article.content = Box::new(move |ui: &mut Ui| {
-            ui.heading(&title);
-            ui.separator();
-            ui.label(format!("This is an article about {title}."));
-        });
-        articles.insert(article.title.clone(), article);
+    /// Go back to the previously shown article, if any.
+    pub fn back(&mut self) -> bool {
+        self.history.back(&mut self.selected)
+    }
 
-        let (title, parent) = ("Section 1.1".to_string(), "Section 1".to_string());
-        let mut article = Article { title: title.clone(), parent, ..Default::default() };
-        article.content = Box::new(move |ui: &mut Ui| {
-            ui.heading(&title);
-            ui.separator();
-            ui.label(format!("This is an article about {title}."));
-        });
-        articles.insert(article.title.clone(), article);
+    /// Undo a [`Self::back`], if possible.
+    pub fn forward(&mut self) -> bool {
+        self.history.forward(&mut self.selected)
+    }
 
-        // let (title, parent) = ("Usage".to_string(), "Background".to_string());
-        // let mut article = Article { title: title.clone(), parent, ..Default::default() };
-        // article.content = Box::new(move |ui: &mut Ui| {
-        //     ui.heading(&title);
-        //     ui.separator();
-        //     ui.label(format!("This is an article about {title}."));
-        // });
-        // articles.insert(article.title.clone(), article);
+    /// The sub-path of the deep link to the selected article, e.g. `Section 1.1`
+    /// in `#About/Section 1.1`.
+    ///
+    /// Returns `None` on the table of contents.
+    pub fn deep_link(&self) -> Option<&str> {
+        (self.selected != ROOT).then_some(self.selected.as_str())
+    }
 
-        return articles
+    /// Follow the sub-path of a deep link, see [`Self::deep_link`].
+    ///
+    /// Returns `false` if there is no such article.
+    pub fn open_deep_link(&mut self, path: &str) -> bool {
+        if !self.restored {
+            self.restore();
+        }
+        let title = if path.is_empty() { ROOT } else { path };
+        let exists = title == ROOT || self.articles.contains_key(title);
+        if exists {
+            self.select(title);
+        }
+        exists
     }
 
-    fn table_of_contents(&self) -> Tree {
-        let mut toc = Tree::new("Table of Contents", vec![]);
-        self.articles.iter().for_each(|(title, article)| {
-            toc.add_child(title, &article.parent)
-        });
-        toc
+    /// Rename the article `old` to `new`, updating its children and every link to it.
+    ///
+    /// Returns `false` if there is no article `old`, or `new` is not a free title.
+    pub fn rename(&mut self, old: &str, new: &str) -> bool {
+        let new = new.trim();
+        if new.is_empty() || new == ROOT || self.articles.contains_key(new) {
+            return false;
+        }
+        let Some(article) = self.articles.remove(old) else {
+            return false;
+        };
+        self.articles.insert(
+            new.to_owned(),
+            article.with(new, &article.parent, &article.source),
+        );
+        for article in self.articles.values_mut() {
+            let source = rename_links(&article.source, old, new);
+            let parent = if article.parent == old {
+                new
+            } else {
+                &article.parent
+            };
+            if source != article.source || parent != article.parent {
+                *article = article.with(&article.title, parent, &source);
+            }
+        }
+
+        if self.selected == old {
+            new.clone_into(&mut self.selected);
+        }
+        self.history.rename(old, new);
+        self.unsaved = true;
+        if self.detached.remove(old) {
+            self.detached.insert(new.to_owned());
+        }
+        self.rebuild();
+        true
+    }
+
+    /// Add an empty article below `parent` and return its title.
+    pub fn add_child(&mut self, parent: &str) -> String {
+        let mut title = UNTITLED.to_owned();
+        let mut n = 2;
+        while self.articles.contains_key(&title) {
+            title = format!("{UNTITLED} {n}");
+            n += 1;
+        }
+        self.articles
+            .insert(title.clone(), Article::new(&title, parent, ""));
+        self.unsaved = true;
+        self.rebuild();
+        title
+    }
+
+    /// Delete the article `title`. Its children move up to its parent.
+    ///
+    /// Returns `false` if there is no such article.
+    pub fn delete(&mut self, title: &str) -> bool {
+        let Some(deleted) = self.articles.remove(title) else {
+            return false;
+        };
+        for article in self.articles.values_mut() {
+            if article.parent == title {
+                article.parent.clone_from(&deleted.parent);
+            }
+        }
+
+        if self.selected == title {
+            self.selected = if self.articles.contains_key(&deleted.parent) {
+                deleted.parent
+            } else {
+                ROOT.to_owned()
+            };
+        }
+        self.history.remove(title);
+        self.detached.remove(title);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.deleted_files.extend(deleted.path);
+        self.unsaved = true;
+        self.rebuild();
+        true
+    }
+
+    /// Show the article `title` in its own window.
+    pub fn detach(&mut self, title: &str) {
+        if self.articles.contains_key(title) {
+            self.detached.insert(title.to_owned());
+        }
+    }
+
+    /// `(file name, HTML)` of a static site with one page per article and an `index.html`.
+    pub fn export_site(&self) -> Vec<(String, String)> {
+        export::site(&self.articles, &self.table_of_contents)
+    }
+
+    /// A single HTML page with every article, meant for printing.
+    pub fn export_single_page(&self) -> String {
+        export::single_page(&self.articles, &self.table_of_contents)
+    }
+
+    /// Write [`Self::export_site`] into `dir`, returning the number of pages written.
+    ///
+    /// # Errors
+    /// If `dir` cannot be created or written to.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_site(&self, dir: &std::path::Path) -> std::io::Result<usize> {
+        export::write_site(&self.articles, &self.table_of_contents, dir)
+    }
+
+    /// The articles embedded at build time from `content/about`.
+    pub fn embedded() -> BTreeMap<String, Article> {
+        Self::parse_files(EMBEDDED_ARTICLES.iter().copied())
+    }
+
+    /// Read every `.md` file in `dir`.
+    ///
+    /// Files that fail to parse are skipped with a warning. Of files with the same title,
+    /// the first by name is read and the others are returned as [`ContentError::DuplicateTitle`].
+    ///
+    /// # Errors
+    /// If the directory or one of its Markdown files cannot be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_dir(
+        dir: &std::path::Path,
+    ) -> std::io::Result<(BTreeMap<String, Article>, Vec<ContentError>)> {
+        let mut paths = vec![];
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "md") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut articles: BTreeMap<String, Article> = BTreeMap::new();
+        let mut duplicates = vec![];
+        for path in paths {
+            let text = std::fs::read_to_string(&path)?;
+            let Some(mut article) = Self::parse_file(&path.display().to_string(), &text) else {
+                continue;
+            };
+            if let Some(kept) = articles.get(&article.title) {
+                let duplicate = ContentError::DuplicateTitle {
+                    title: article.title,
+                    kept: kept
+                        .path
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default(),
+                    skipped: path.display().to_string(),
+                };
+                log::warn!("{duplicate}");
+                duplicates.push(duplicate);
+                continue;
+            }
+            article.path = Some(path);
+            articles.insert(article.title.clone(), article);
+        }
+        Ok((articles, duplicates))
+    }
+
+    fn parse_files<'a>(
+        files: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> BTreeMap<String, Article> {
+        files
+            .filter_map(|(name, text)| Self::parse_file(name, text))
+            .map(|article| (article.title.clone(), article))
+            .collect()
+    }
+
+    fn parse_file(name: &str, text: &str) -> Option<Article> {
+        Article::from_markdown(text)
+            .map_err(|err| log::warn!("Skipping article {name}: {err}"))
+            .ok()
+    }
+
+    /// Save every article: to the folder it was read from on native,
+    /// or else to the app storage (persisted with the rest of the app state).
+    ///
+    /// Returns where the articles went.
+    ///
+    /// # Errors
+    /// If an article file cannot be written or removed.
+    #[cfg_attr(target_arch = "wasm32", allow(clippy::unnecessary_wraps))] // Only files can fail
+    pub fn save(&mut self) -> std::io::Result<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(dir) = self.content_dir.clone() {
+            let written = self.save_dir(&dir)?;
+            self.unsaved = false;
+            return Ok(format!("Saved {written} files to {}", dir.display()));
+        }
+
+        self.saved = Some(self.articles.values().map(Article::to_markdown).collect());
+        self.unsaved = false;
+        Ok("Saved to the app storage".to_owned())
+    }
+
+    /// Write the changed articles to `dir`, giving new ones a file named after their title,
+    /// and remove the files of deleted ones. Returns the number of files written.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_dir(&mut self, dir: &std::path::Path) -> std::io::Result<usize> {
+        for path in self.deleted_files.drain(..) {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        let mut written = 0;
+        for article in self.articles.values_mut() {
+            let text = article.to_markdown();
+            let path = article.path.get_or_insert_with(|| {
+                let slug = export::slug(&article.title);
+                let mut path = dir.join(format!("{slug}.md"));
+                let mut n = 2;
+                while path.exists() {
+                    path = dir.join(format!("{slug}-{n}.md"));
+                    n += 1;
+                }
+                path
+            });
+            if std::fs::read_to_string(&*path).ok().as_deref() != Some(text.as_str()) {
+                std::fs::write(&*path, text)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Replace the default articles by the ones in the app storage, if any.
+    fn restore(&mut self) {
+        self.restored = true;
+        let Some(saved) = &self.saved else {
+            return;
+        };
+        self.articles =
+            Self::parse_files(saved.iter().map(|text| ("(app storage)", text.as_str())));
+        self.rebuild();
     }
 }
 
+impl super::View for About {
+    fn name(&self) -> &str {
+        "About"
+    }
+
+    fn icon(&self) -> &str {
+        "❔"
+    }
+
+    fn anchor(&self) -> &str {
+        VIEW_NAME
+    }
+
+    fn deep_link(&self) -> Option<&str> {
+        Self::deep_link(self)
+    }
+
+    fn open_deep_link(&mut self, path: &str) -> bool {
+        Self::open_deep_link(self, path)
+    }
+
+    #[cfg(feature = "persistence")]
+    fn restore_state(
+        &mut self,
+        storage: &dyn eframe::Storage,
+        key: &str,
+        persistence: &mut pocket_core::persistence::Persistence,
+    ) {
+        if let Some(about) = persistence.load_key(storage, key) {
+            *self = about;
+        }
+    }
+
+    #[cfg(feature = "persistence")]
+    fn save_state(
+        &self,
+        storage: &mut dyn eframe::Storage,
+        key: &str,
+        persistence: &mut pocket_core::persistence::Persistence,
+    ) {
+        persistence.save_key(storage, key, self);
+    }
+}
+
+/// Versioned since the views were registered, when the About view got a key of its own.
+#[cfg(feature = "persistence")]
+impl pocket_core::persistence::Versioned for About {
+    const MIGRATIONS: &'static [pocket_core::persistence::Migration] = &[];
+}
+
 impl eframe::App for About {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if !self.restored {
+            self.restore();
+        }
+        self.history_shortcuts(ctx);
+        let mut actions = vec![];
+
         // Left Panel: Table of Contents
         egui::SidePanel::left("left_panel")
             .resizable(false)
             .show(ctx, |ui| {
                 ui.add_space(4.0);
                 ui.vertical_centered(|ui| {
-                    ui.heading("Table of Contents");
+                    ui.heading(ROOT);
                 });
                 ui.separator();
+                ui.horizontal(|ui| {
+                    ui.menu_button("⬇ Export", |ui| self.export_menu(ui));
+                    ui.toggle_value(&mut self.editing, "✏ Edit")
+                        .on_hover_text("Rename, add and delete articles from the context menu");
+                    if self.editing {
+                        if ui.button("➕ New").clicked() {
+                            actions.push(TocAction::AddChild(ROOT.to_owned()));
+                        }
+                        let save = ui.add_enabled(self.unsaved, egui::Button::new("💾 Save"));
+                        if save.clicked() {
+                            self.save_status = Some(match self.save() {
+                                Ok(status) => status,
+                                Err(err) => format!("Failed to save: {err}"),
+                            });
+                        }
+                    }
+                });
+                self.search_ui(ui);
+                ui.separator();
+
                 let (show_root, default_depth) = (false, 1);
-                self.selected = self.table_of_contents.ui(ui, show_root, default_depth, &self.selected);
+                let editing = self.editing;
+                let mut context_menu =
+                    |ui: &mut Ui, node: &Tree| toc_context_menu(ui, node, editing, &mut actions);
+                let selected = if self.query.trim().is_empty() {
+                    self.table_of_contents.ui(
+                        ui,
+                        show_root,
+                        default_depth,
+                        &self.selected,
+                        None,
+                        &mut self.tree_state,
+                        &mut context_menu,
+                    )
+                } else {
+                    // Only show the branches leading to a hit, fully expanded
+                    let hits = self
+                        .search_index
+                        .search(&self.query)
+                        .into_iter()
+                        .map(|hit| hit.title)
+                        .collect::<BTreeSet<_>>();
+                    ui.push_id("search_results", |ui| {
+                        self.table_of_contents.ui(
+                            ui,
+                            show_root,
+                            u32::MAX,
+                            &self.selected,
+                            Some(&hits),
+                            &mut self.tree_state,
+                            &mut context_menu,
+                        )
+                    })
+                    .inner
+                };
+                self.select(&selected);
+                self.orphans_ui(ui);
             });
         // Central Panel: Articles
         egui::CentralPanel::default().show(ctx, |ui| {
-            if let Some(article) = self.articles.get(&self.selected) {
-                article.ui(ui);
+            self.history_ui(ui);
+            let clicked = if self.editing {
+                self.editor_ui(ui)
+            } else {
+                self.articles
+                    .get(&self.selected)
+                    .and_then(|article| article.ui(ui, &search::terms(&self.query)))
+            };
+            if let Some(title) = clicked {
+                if !self.open_deep_link(&title) {
+                    log::warn!("Broken link to {title:?} in {:?}", self.selected);
+                }
             }
         });
+
+        for action in actions {
+            self.run_toc_action(ctx, frame, action);
+        }
+        self.toc_dialog_ui(ctx);
+        self.detached_ui(ctx);
     }
 }
 
-#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
-pub struct Tree {
-    pub root: String,
-    pub children: Vec<Tree>,
-}
+impl About {
+    /// Back and forward buttons.
+    fn history_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let back = ui
+                .add_enabled(!self.history.back.is_empty(), egui::Button::new("⏴"))
+                .on_hover_text(format!(
+                    "Back ({})",
+                    ui.ctx().format_shortcut(&BACK_SHORTCUT)
+                ));
+            if back.clicked() {
+                self.back();
+            }
+            let forward = ui
+                .add_enabled(!self.history.forward.is_empty(), egui::Button::new("⏵"))
+                .on_hover_text(format!(
+                    "Forward ({})",
+                    ui.ctx().format_shortcut(&FORWARD_SHORTCUT)
+                ));
+            if forward.clicked() {
+                self.forward();
+            }
+        });
+    }
 
-impl Default for Tree {
-    fn default() -> Self {
-        Self::new("Root", Vec::new())
+    fn run_toc_action(&mut self, ctx: &egui::Context, frame: &eframe::Frame, action: TocAction) {
+        match action {
+            TocAction::CopyLink(title) => {
+                let link = crate::deep_link::format(VIEW_NAME, Some(&title));
+                // On the web, make it a full URL that can be shared.
+                #[cfg(target_arch = "wasm32")]
+                let link = format!("{}{link}", frame.info().web_info.location.url);
+                #[cfg(not(target_arch = "wasm32"))]
+                let _ = frame;
+                ctx.copy_text(link);
+            }
+            TocAction::Detach(title) => self.detach(&title),
+            TocAction::SetOpen { id, open } => {
+                if let Some(node) = self.table_of_contents.find(id) {
+                    self.tree_state.set_open_recursive(node, open);
+                }
+            }
+            TocAction::Rename(title) => {
+                self.toc_dialog = Some(TocDialog::Rename {
+                    new_title: title.clone(),
+                    title,
+                });
+            }
+            TocAction::AddChild(parent) => {
+                // Show the new article in the table of contents
+                let parent_id = self
+                    .table_of_contents
+                    .numbered()
+                    .into_iter()
+                    .find(|(_, node)| node.root == parent)
+                    .map(|(_, node)| node.id);
+                if let Some(id) = parent_id {
+                    self.tree_state.set_open(id, true);
+                }
+                let title = self.add_child(&parent);
+                self.select(&title);
+                self.toc_dialog = Some(TocDialog::Rename {
+                    new_title: title.clone(),
+                    title,
+                });
+            }
+            TocAction::Delete(title) => self.toc_dialog = Some(TocDialog::Delete(title)),
+        }
     }
-}
 
-impl Tree {
+    /// The Markdown source of the selected article next to a live preview.
+    ///
+    /// Returns the `[[link]]` clicked in the preview, if any.
+    fn editor_ui(&mut self, ui: &mut Ui) -> Option<String> {
+        let title = self.selected.clone();
+        let Some(article) = self.articles.get(&title) else {
+            ui.label("Select an article to edit it, or add one with ➕ New.");
+            return None;
+        };
+        let (mut parent, mut source) = (article.parent.clone(), article.source.clone());
 
+        ui.horizontal(|ui| {
+            ui.label("Parent:");
+            egui::ComboBox::from_id_salt("about_parent")
+                .selected_text(&parent)
+                .show_ui(ui, |ui| {
+                    for (label, candidate) in self.parent_candidates(&title) {
+                        ui.selectable_value(&mut parent, candidate, label);
+                    }
+                });
+            if self.unsaved {
+                ui.weak("Unsaved changes");
+            } else if let Some(status) = &self.save_status {
+                ui.weak(status);
+            }
+        });
+        ui.separator();
 
-    pub fn add_child(&mut self, child: &str, parent: &str) {
-        if &self.root == parent {
-            self.children.push(Tree::new(child, vec![]))
+        let highlight = search::terms(&self.query);
+        let mut clicked = None;
+        ui.columns(2, |columns| {
+            let edited = egui::ScrollArea::vertical()
+                .id_salt("about_source")
+                .show(&mut columns[0], |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut source)
+                            .code_editor()
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(20),
+                    )
+                })
+                .inner
+                .changed();
+            if edited || parent != self.articles[&title].parent {
+                self.edit(&title, &parent, &source);
+            }
+
+            egui::ScrollArea::vertical()
+                .id_salt("about_preview")
+                .show(&mut columns[1], |ui| {
+                    clicked = self.articles[&title].ui(ui, &highlight);
+                });
+        });
+        clicked
+    }
+
+    /// Replace the parent and body of the article `title`.
+    ///
+    /// This runs on every keystroke, so the table of contents is only rebuilt when the
+    /// parent changed, and otherwise just the article is searched anew.
+    fn edit(&mut self, title: &str, parent: &str, source: &str) {
+        let Some(article) = self.articles.get_mut(title) else {
+            return;
+        };
+        let moved = article.parent != parent;
+        *article = article.with(title, parent, source);
+        self.unsaved = true;
+        if moved {
+            self.rebuild();
         } else {
-            self.children.iter_mut().for_each(|c| {c.add_child(child, parent)});
+            self.search_index.update(article);
         }
     }
 
-    pub fn new(root: &str, children: Vec<&str>) -> Self {
-        Self {
-            root: root.to_string(),
-            children: children.iter().map(|c| Tree::new(c, vec![])).collect()
+    /// `(label, title)` of every article that can be the parent of `title`,
+    /// in table of contents order: anything but itself and its descendants.
+    fn parent_candidates(&self, title: &str) -> Vec<(String, String)> {
+        let numbered = self.table_of_contents.numbered();
+        let descendants = numbered
+            .iter()
+            .find(|(_, node)| node.root == title)
+            .map(|(_, node)| {
+                node.numbered()
+                    .into_iter()
+                    .map(|(_, child)| child.root.as_str())
+                    .collect::<BTreeSet<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut candidates = vec![(ROOT.to_owned(), ROOT.to_owned())];
+        for (number, node) in &numbered {
+            if node.root != title && !descendants.contains(node.root.as_str()) {
+                let number = number
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                candidates.push((format!("{number} {}", node.root), node.root.clone()));
+            }
         }
+        candidates
     }
 
-    pub fn ui(&self, ui: &mut Ui, show_root: bool, default_depth: u32, selected: &str) -> String {
-        // Keep track of why tree button is selected
-        let mut selected = selected.to_string();
-
-        // If we don't want to show the root, we'll start the tree by iterating
-        // over children
-        let trees = match show_root {
-            true  => vec![self],
-            false => self.children.iter().collect(),
+    /// Ask for a new title, or for confirmation before deleting.
+    fn toc_dialog_ui(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.toc_dialog else {
+            return;
         };
-        // Render each tree(s) UI
-        trees.into_iter().enumerate().for_each(|(i, tree)| {
-            let (depth, name, prefix) = (0, &tree.root, &vec![i as u32]);
-            selected = tree.ui_recursive(ui, depth, name, default_depth, &selected, prefix);
-        });
+        let (mut confirmed, mut cancelled) = (false, false);
+        let heading = match dialog {
+            TocDialog::Rename { .. } => "Rename article",
+            TocDialog::Delete(_) => "Delete article",
+        };
+        egui::Window::new(heading)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                let ok = match dialog {
+                    TocDialog::Rename { title, new_title } => {
+                        let response = ui.text_edit_singleline(new_title);
+                        response.request_focus();
+                        let new_title = new_title.trim();
+                        let taken = new_title != title && self.articles.contains_key(new_title);
+                        if taken {
+                            ui.colored_label(ui.visuals().warn_fg_color, "That title is taken");
+                        }
+                        confirmed |=
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        !new_title.is_empty() && new_title != ROOT && !taken
+                    }
+                    TocDialog::Delete(title) => {
+                        ui.label(format!("Delete {title:?}? Its children move up one level."));
+                        true
+                    }
+                };
+                confirmed &= ok;
+                ui.horizontal(|ui| {
+                    confirmed |= ui.add_enabled(ok, egui::Button::new("OK")).clicked();
+                    cancelled |= ui.button("Cancel").clicked();
+                });
+                cancelled |= ui.input(|i| i.key_pressed(egui::Key::Escape));
+            });
 
-        // Return the tree button that is selected
-        return selected
+        if confirmed {
+            match self.toc_dialog.take() {
+                Some(TocDialog::Rename { title, new_title }) => {
+                    if new_title.trim() != title {
+                        self.rename(&title, &new_title);
+                    }
+                }
+                Some(TocDialog::Delete(title)) => {
+                    self.delete(&title);
+                }
+                None => {}
+            }
+        } else if cancelled {
+            self.toc_dialog = None;
+        }
     }
 
-    fn ui_recursive(&self, ui: &mut Ui, depth: u32, name: &str, default_depth: u32, selected: &str, prefix: &[u32]) -> String {
+    /// Articles opened in their own window, which can be dragged out of the
+    /// main window on native.
+    fn detached_ui(&mut self, ctx: &egui::Context) {
+        let mut closed = vec![];
+        let mut clicked = None;
+        for title in &self.detached {
+            let Some(article) = self.articles.get(title) else {
+                closed.push(title.clone());
+                continue;
+            };
+            let highlight = search::terms(&self.query);
+            let viewport_id = egui::ViewportId::from_hash_of(("about_article", title));
+            let builder = egui::ViewportBuilder::default()
+                .with_title(title)
+                .with_inner_size([480.0, 400.0]);
+            ctx.show_viewport_immediate(viewport_id, builder, |ctx, class| {
+                let mut article_ui = |ui: &mut Ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        if let Some(link) = article.ui(ui, &highlight) {
+                            clicked = Some(link);
+                        }
+                    });
+                };
+                if class == egui::ViewportClass::Embedded {
+                    // No native windows (e.g. on the web), so use an egui window instead
+                    let mut open = true;
+                    egui::Window::new(title)
+                        .id(egui::Id::new(viewport_id))
+                        .open(&mut open)
+                        .default_size([480.0, 400.0])
+                        .show(ctx, &mut article_ui);
+                    if !open {
+                        closed.push(title.clone());
+                    }
+                } else {
+                    egui::CentralPanel::default().show(ctx, &mut article_ui);
+                    if ctx.input(|i| i.viewport().close_requested()) {
+                        closed.push(title.clone());
+                    }
+                }
+            });
+        }
 
-        // If the user selects a new tree button
-        let mut selected = selected.to_string();
+        for title in closed {
+            self.detached.remove(&title);
+        }
+        // Links in a detached article are followed in the main window
+        if let Some(title) = clicked {
+            self.open_deep_link(&title);
+        }
+    }
 
-        // Configure a numeric prefix (ex. "1", "1.1", "2.3.1")
-        let mut prefix = prefix.to_vec();
-        if prefix.len() > 0 {
-            let l = prefix.len() - 1;
-            prefix[l] = prefix[l] + 1;
+    /// The articles missing from the table of contents, so they can still be reached.
+    fn orphans_ui(&mut self, ui: &mut Ui) {
+        if self.orphans.is_empty() {
+            return;
         }
+        ui.separator();
+        ui.collapsing("⚠ Orphaned articles", |ui| {
+            let orphans = self.orphans.iter().cloned().collect::<Vec<_>>();
+            for title in orphans {
+                let parent = &self.articles[&title].parent;
+                let response = ui
+                    .selectable_label(self.selected == title, &title)
+                    .on_hover_text(format!("Parent {parent:?} is missing or not reachable"));
+                if response.clicked() {
+                    self.select(&title);
+                }
+            }
+        });
+    }
 
-        // Create a new collapsing element
-        let state = CollapsingState::load_with_default_open(
-            ui.ctx(), 
-            ui.make_persistent_id(format!("tree_{name}")), 
-            depth < default_depth
-        );
+    /// Alt+Left/Right and the back/forward mouse buttons.
+    fn history_shortcuts(&mut self, ctx: &egui::Context) {
+        let (back, forward) = ctx.input_mut(|i| {
+            (
+                i.consume_shortcut(&BACK_SHORTCUT)
+                    || i.pointer.button_pressed(egui::PointerButton::Extra1),
+                i.consume_shortcut(&FORWARD_SHORTCUT)
+                    || i.pointer.button_pressed(egui::PointerButton::Extra2),
+            )
+        });
+        if back {
+            self.back();
+        }
+        if forward {
+            self.forward();
+        }
+    }
 
-        // THIS WORKS BELOW! BUT WE WANT: CUSTOM ICONS AND DEFAULT OPEN/CLOSE
-        // Configure the header
-        let header = state.show_header(ui, |ui| {
+    fn export_menu(&mut self, ui: &mut Ui) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
             ui.horizontal(|ui| {
-                ui.label(prefix.iter().map(|i| i.to_string()).collect::<Vec<String>>().join("."));
-                let button = ui.selectable_label(selected == name, name);
-                button.context_menu(|ui| {ui.label("You right clicked a header!");});
-                if button.clicked() { selected = name.to_string();};
-            })
-        });
-        // Configure the body
-        let (collapse, _, _) = header.body(|ui| {
-            self
-                .children
-                .iter()
-                .enumerate()
-                .for_each(|(i, c)| {
-                    let c_prefix = prefix.clone().into_iter().chain([i as u32]).collect::<Vec<u32>>();
-                    let (c_depth, c_name) = (depth + 1, &c.root);
-                    selected = c.ui_recursive(ui, c_depth, c_name, default_depth, &selected, &c_prefix);
-                })
+                ui.label("Folder:");
+                ui.text_edit_singleline(&mut self.export_dir);
+            });
+            if ui
+                .button("🗐 Static site")
+                .on_hover_text("One page per article, plus index.html")
+                .clicked()
+            {
+                let dir = std::path::Path::new(&self.export_dir);
+                self.export_status = Some(match self.write_site(dir) {
+                    Ok(pages) => format!("Wrote {pages} pages to {}", dir.display()),
+                    Err(err) => format!("Failed to export to {}: {err}", dir.display()),
+                });
+            }
+            if ui
+                .button("🖶 Single page")
+                .on_hover_text("Every article in one file, for printing")
+                .clicked()
+            {
+                let path = std::path::Path::new(&self.export_dir).join("about.html");
+                let result = std::fs::create_dir_all(&self.export_dir)
+                    .and_then(|()| std::fs::write(&path, self.export_single_page()));
+                self.export_status = Some(match result {
+                    Ok(()) => format!("Wrote {}", path.display()),
+                    Err(err) => format!("Failed to write {}: {err}", path.display()),
+                });
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if ui
+            .button("🖶 Single page")
+            .on_hover_text("Download every article in one file, for printing")
+            .clicked()
+        {
+            let html = self.export_single_page();
+            self.export_status = Some(
+                match pocket_core::web::download("about.html", "text/html", html.as_bytes()) {
+                    Ok(()) => "Downloaded about.html".to_owned(),
+                    Err(err) => format!("Download failed: {err:?}"),
+                },
+            );
+        }
+
+        if let Some(status) = &self.export_status {
+            ui.separator();
+            ui.label(status);
+        }
+    }
+
+    fn search_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("🔍 Search")
+                    .desired_width(ui.available_width() - 24.0),
+            );
+            if ui
+                .add_enabled(!self.query.is_empty(), egui::Button::new("✖").frame(false))
+                .on_hover_text("Clear search")
+                .clicked()
+            {
+                self.query.clear();
+            }
         });
-        collapse.context_menu(|ui| {ui.label("You right-clicked the collapse icon!");});
 
-        return selected.to_string()
+        if self.query.trim().is_empty() {
+            return;
+        }
+
+        let hits = self.search_index.search(&self.query);
+        if hits.is_empty() {
+            ui.weak("No results");
+            return;
+        }
+
+        ui.weak(format!(
+            "{} result{}",
+            hits.len(),
+            if hits.len() == 1 { "" } else { "s" }
+        ));
+        egui::ScrollArea::vertical()
+            .id_salt("search_hits")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for hit in hits {
+                    let response = ui.selectable_label(self.selected == hit.title, &hit.title);
+                    let response = match &hit.snippet {
+                        Some(snippet) => response.on_hover_text(snippet),
+                        None => response,
+                    };
+                    if response.clicked() {
+                        self.select(&hit.title);
+                    }
+                }
+            });
     }
 }
@@ -0,0 +1,224 @@
+//! Export the [`super::About`] articles to HTML, for people who won't run the app.
+//!
+//! There are two flavors:
+//! * a static site, with one page per article and an `index.html` table of contents
+//! * a single, self-contained page with every article, meant for printing (e.g. to PDF)
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use super::about::{Article, ROOT};
+use super::markdown::{self, escape_html};
+use crate::tree::Tree;
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 48em; margin: 2em auto; padding: 0 1em; line-height: 1.5; }
+nav { margin-bottom: 2em; }
+pre { background: #f4f4f4; padding: 0.5em; overflow-x: auto; }
+code { background: #f4f4f4; }
+.number { color: #888; margin-right: 0.5em; }
+.toc ul { list-style: none; }
+@media print {
+    nav { display: none; }
+    section { page-break-before: always; }
+}
+";
+
+/// `(file name, HTML)` of every page of the static site.
+pub fn site(articles: &BTreeMap<String, Article>, toc: &Tree) -> Vec<(String, String)> {
+    let numbers = numbers(toc);
+    let file_names = file_names(articles);
+    let href = |title: &str| {
+        file_names
+            .get(title)
+            .cloned()
+            .unwrap_or_else(|| "index.html".to_owned())
+    };
+
+    let mut pages = vec![];
+
+    let mut body = "<h1>Table of Contents</h1>\n<div class=\"toc\">\n".to_owned();
+    toc_html(&mut body, toc, &numbers, &href);
+    body += "</div>\n";
+    pages.push(("index.html".to_owned(), page(ROOT, &body)));
+
+    for (title, article) in articles {
+        let mut body = "<nav><a href=\"index.html\">⏶ Table of Contents</a></nav>\n".to_owned();
+        body += &heading(title, numbers.get(title.as_str()));
+        body += &markdown::to_html(article.blocks(), &href);
+        pages.push((href(title), page(title, &body)));
+    }
+
+    pages
+}
+
+/// One HTML page with every article, in table of contents order.
+///
+/// Articles that are not in the table of contents come last.
+pub fn single_page(articles: &BTreeMap<String, Article>, toc: &Tree) -> String {
+    let numbers = numbers(toc);
+    let anchors = file_names(articles)
+        .into_iter()
+        .map(|(title, file_name)| (title, file_name.trim_end_matches(".html").to_owned()))
+        .collect::<BTreeMap<_, _>>();
+    let href = |title: &str| {
+        anchors
+            .get(title)
+            .map_or_else(|| "#".to_owned(), |anchor| format!("#{anchor}"))
+    };
+
+    let mut order = toc
+        .numbered()
+        .into_iter()
+        .map(|(_, tree)| tree.root.as_str())
+        .collect::<Vec<_>>();
+    order.extend(
+        articles
+            .keys()
+            .map(String::as_str)
+            .filter(|title| !numbers.contains_key(title)),
+    );
+
+    let mut body = "<h1>Table of Contents</h1>\n<div class=\"toc\">\n".to_owned();
+    toc_html(&mut body, toc, &numbers, &href);
+    body += "</div>\n";
+
+    for title in order {
+        let Some(article) = articles.get(title) else {
+            continue;
+        };
+        writeln!(body, "<section id=\"{}\">", escape_html(&anchors[title])).ok();
+        body += &heading(title, numbers.get(title));
+        body += &markdown::to_html(article.blocks(), &href);
+        body += "</section>\n";
+    }
+
+    page(ROOT, &body)
+}
+
+/// Write the static site into `dir`, creating it if needed.
+///
+/// Returns the number of pages written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_site(
+    articles: &BTreeMap<String, Article>,
+    toc: &Tree,
+    dir: &std::path::Path,
+) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dir)?;
+    let pages = site(articles, toc);
+    for (file_name, html) in &pages {
+        std::fs::write(dir.join(file_name), html)?;
+    }
+    Ok(pages.len())
+}
+
+// ----------------------------------------------------------------------------
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+        <html lang=\"en\">\n\
+        <head>\n\
+        <meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+        <title>{}</title>\n\
+        <style>{STYLE}</style>\n\
+        </head>\n\
+        <body>\n{body}</body>\n\
+        </html>\n",
+        escape_html(title)
+    )
+}
+
+fn heading(title: &str, number: Option<&String>) -> String {
+    let number = number.map_or_else(String::new, |number| {
+        format!("<span class=\"number\">{number}</span>")
+    });
+    format!("<h1>{number}{}</h1>\n", escape_html(title))
+}
+
+/// Nested lists mirroring the table of contents.
+fn toc_html(
+    html: &mut String,
+    tree: &Tree,
+    numbers: &BTreeMap<&str, String>,
+    href: &dyn Fn(&str) -> String,
+) {
+    if tree.children.is_empty() {
+        return;
+    }
+    *html += "<ul>\n";
+    for child in &tree.children {
+        let number = numbers
+            .get(child.root.as_str())
+            .cloned()
+            .unwrap_or_default();
+        writeln!(
+            html,
+            "<li><span class=\"number\">{number}</span><a href=\"{}\">{}</a>",
+            escape_html(&href(&child.root)),
+            escape_html(&child.root)
+        )
+        .ok();
+        toc_html(html, child, numbers, href);
+        *html += "</li>\n";
+    }
+    *html += "</ul>\n";
+}
+
+/// The "1", "1.1" prefixes of the table of contents, keyed by title.
+fn numbers(toc: &Tree) -> BTreeMap<&str, String> {
+    toc.numbered()
+        .into_iter()
+        .map(|(number, tree)| {
+            let number = number
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            (tree.root.as_str(), number)
+        })
+        .collect()
+}
+
+/// A unique, URL-friendly file name for every article, e.g. `section-1-1.html`.
+fn file_names(articles: &BTreeMap<String, Article>) -> BTreeMap<String, String> {
+    let mut taken = vec!["index".to_owned()];
+    let mut file_names = BTreeMap::new();
+    for title in articles.keys() {
+        let slug = slug(title);
+        let mut unique = slug.clone();
+        let mut n = 2;
+        while taken.contains(&unique) {
+            unique = format!("{slug}-{n}");
+            n += 1;
+        }
+        taken.push(unique.clone());
+        file_names.insert(title.clone(), format!("{unique}.html"));
+    }
+    file_names
+}
+
+/// A URL and file name friendly version of `title`, e.g. `section-1-1`.
+pub(super) fn slug(title: &str) -> String {
+    let slug = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "article".to_owned()
+    } else {
+        slug
+    }
+}
@@ -0,0 +1,66 @@
+//! A fixed-timestep loop, so the simulation runs the same whatever the frame rate.
+
+/// Simulation steps per second.
+pub const TICK_RATE: u32 = 60;
+
+/// Seconds per simulation step.
+pub const STEP: f32 = 1.0 / TICK_RATE as f32;
+
+/// Most steps taken in one frame. After a long hitch (e.g. a background tab)
+/// we drop the missing time rather than freeze while catching up.
+pub const MAX_STEPS_PER_FRAME: u32 = 8;
+
+/// Turns frame times into a whole number of simulation steps.
+#[derive(Clone, Debug)]
+pub struct GameLoop {
+    pub paused: bool,
+    /// Frame time not yet simulated, in seconds.
+    accumulator: f32,
+    /// Take one step on the next frame, even if paused.
+    step_once: bool,
+}
+
+impl Default for GameLoop {
+    fn default() -> Self {
+        Self {
+            paused: true,
+            accumulator: 0.0,
+            step_once: false,
+        }
+    }
+}
+
+impl GameLoop {
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.accumulator = 0.0;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Take a single step, then stay paused.
+    pub fn step(&mut self) {
+        self.pause();
+        self.step_once = true;
+    }
+
+    /// The number of steps to simulate for a frame that took `dt` seconds.
+    pub fn advance(&mut self, dt: f32) -> u32 {
+        if std::mem::take(&mut self.step_once) {
+            return 1;
+        }
+        if self.paused {
+            return 0;
+        }
+
+        self.accumulator += dt;
+        let steps = (self.accumulator / STEP) as u32;
+        self.accumulator -= steps as f32 * STEP;
+        if steps > MAX_STEPS_PER_FRAME {
+            self.accumulator = 0.0;
+        }
+        steps.min(MAX_STEPS_PER_FRAME)
+    }
+}
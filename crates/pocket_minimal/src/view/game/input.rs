@@ -0,0 +1,294 @@
+//! Turn keyboard, pointer and touch input into named game [`Action`]s.
+//!
+//! The gamepad-like input is the on-screen direction pad of [`touch_controls`].
+//! Physical gamepads can't be bound: egui gets no events from them, on native or on the web.
+
+use std::collections::BTreeSet;
+
+use egui::{Key, PointerButton, Rect, Ui, Vec2};
+
+/// Something the player can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    PlayPause,
+    Step,
+    Restart,
+}
+
+impl Action {
+    pub const ALL: [Self; 7] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::PlayPause,
+        Self::Step,
+        Self::Restart,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Up => "Move up",
+            Self::Down => "Move down",
+            Self::Left => "Move left",
+            Self::Right => "Move right",
+            Self::PlayPause => "Play / pause",
+            Self::Step => "Step",
+            Self::Restart => "Restart",
+        }
+    }
+
+    /// The direction this action moves the player in, if any.
+    fn direction(self) -> Vec2 {
+        match self {
+            Self::Up => -Vec2::Y,
+            Self::Down => Vec2::Y,
+            Self::Left => -Vec2::X,
+            Self::Right => Vec2::X,
+            Self::PlayPause | Self::Step | Self::Restart => Vec2::ZERO,
+        }
+    }
+}
+
+/// A key or button that triggers an [`Action`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub enum Binding {
+    Key(Key),
+    Pointer(PointerButton),
+}
+
+impl Binding {
+    pub fn name(self) -> String {
+        match self {
+            Self::Key(key) => key.symbol_or_name().to_owned(),
+            Self::Pointer(button) => format!("{button:?} button"),
+        }
+    }
+}
+
+/// Which [`Binding`]s trigger which [`Action`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "persistence", serde(default))]
+pub struct Bindings {
+    bindings: Vec<(Action, Binding)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let keys = [
+            (Action::Up, Key::ArrowUp),
+            (Action::Up, Key::W),
+            (Action::Down, Key::ArrowDown),
+            (Action::Down, Key::S),
+            (Action::Left, Key::ArrowLeft),
+            (Action::Left, Key::A),
+            (Action::Right, Key::ArrowRight),
+            (Action::Right, Key::D),
+            (Action::PlayPause, Key::Space),
+            (Action::PlayPause, Key::P),
+            (Action::Step, Key::N),
+            (Action::Restart, Key::R),
+        ];
+        Self {
+            bindings: keys
+                .into_iter()
+                .map(|(action, key)| (action, Binding::Key(key)))
+                .collect(),
+        }
+    }
+}
+
+impl Bindings {
+    pub fn of(&self, action: Action) -> impl Iterator<Item = Binding> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(a, _)| *a == action)
+            .map(|(_, binding)| *binding)
+    }
+
+    /// Make `binding` trigger `action`, and nothing else.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        self.bindings.retain(|(_, b)| *b != binding);
+        self.bindings.push((action, binding));
+    }
+
+    pub fn unbind(&mut self, action: Action, binding: Binding) {
+        self.bindings.retain(|b| *b != (action, binding));
+    }
+
+    /// The actions triggered by the keyboard and pointer this frame.
+    pub fn read(&self, input: &egui::InputState) -> ActionState {
+        let mut state = ActionState::default();
+        for &(action, binding) in &self.bindings {
+            let (down, pressed) = match binding {
+                Binding::Key(key) => (input.key_down(key), input.key_pressed(key)),
+                Binding::Pointer(button) => (
+                    input.pointer.button_down(button),
+                    input.pointer.button_pressed(button),
+                ),
+            };
+            if down {
+                state.hold(action);
+            }
+            if pressed {
+                state.press(action);
+            }
+        }
+        state
+    }
+
+    /// The controls window: every action with its bindings, which can be changed.
+    ///
+    /// `rebinding` is the action waiting for a new binding, see [`Self::capture`].
+    pub fn ui(&mut self, ui: &mut Ui, rebinding: &mut Option<Action>) {
+        egui::Grid::new("game_bindings")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for action in Action::ALL {
+                    ui.label(action.name());
+                    ui.horizontal(|ui| {
+                        for binding in self.of(action).collect::<Vec<_>>() {
+                            if ui
+                                .button(binding.name())
+                                .on_hover_text("Click to remove")
+                                .clicked()
+                            {
+                                self.unbind(action, binding);
+                            }
+                        }
+                        let waiting = *rebinding == Some(action);
+                        let label = if waiting { "Press a key…" } else { "➕" };
+                        if ui
+                            .selectable_label(waiting, label)
+                            .on_hover_text("Add a key or mouse button (Escape to cancel)")
+                            .clicked()
+                        {
+                            *rebinding = (!waiting).then_some(action);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+        if ui.button("Reset to defaults").clicked() {
+            *self = Self::default();
+            *rebinding = None;
+        }
+    }
+
+    /// Bind the next key or (non-primary) mouse button pressed to the `rebinding` action.
+    ///
+    /// The input is consumed, so it does not also trigger an action.
+    pub fn capture(&mut self, ctx: &egui::Context, rebinding: &mut Option<Action>) {
+        let Some(action) = *rebinding else {
+            return;
+        };
+        let binding = ctx.input_mut(|i| {
+            let key = i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            });
+            if let Some((key, modifiers)) = key {
+                i.consume_key(modifiers, key);
+                return Some(Binding::Key(key));
+            }
+            // The primary button is needed to click around the settings
+            [
+                PointerButton::Secondary,
+                PointerButton::Middle,
+                PointerButton::Extra1,
+                PointerButton::Extra2,
+            ]
+            .into_iter()
+            .find(|&button| i.pointer.button_pressed(button))
+            .map(Binding::Pointer)
+        });
+
+        match binding {
+            Some(Binding::Key(Key::Escape)) => *rebinding = None,
+            Some(binding) => {
+                self.bind(action, binding);
+                *rebinding = None;
+            }
+            None => {}
+        }
+    }
+}
+
+/// The actions triggered this frame.
+#[derive(Clone, Debug, Default)]
+pub struct ActionState {
+    held: BTreeSet<Action>,
+    pressed: BTreeSet<Action>,
+    /// An analog direction (from a pointer or the touch stick), overriding the movement actions.
+    pub steer: Option<Vec2>,
+}
+
+impl ActionState {
+    pub fn hold(&mut self, action: Action) {
+        self.held.insert(action);
+    }
+
+    pub fn press(&mut self, action: Action) {
+        self.pressed.insert(action);
+    }
+
+    /// Was `action` triggered this frame (and not the one before)?
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Where the player wants to go.
+    pub fn direction(&self) -> Vec2 {
+        self.steer.unwrap_or_else(|| {
+            self.held
+                .iter()
+                .fold(Vec2::ZERO, |sum, action| sum + action.direction())
+        })
+    }
+}
+
+/// On-screen buttons for touch screens: a direction pad in the bottom left
+/// corner of `rect` and a play/pause button in the bottom right one.
+pub fn touch_controls(ui: &mut Ui, rect: Rect, state: &mut ActionState) {
+    let size = (rect.width().min(rect.height()) / 7.0).max(40.0);
+    let margin = size / 4.0;
+    let center = rect.left_bottom() + egui::vec2(margin + 1.5 * size, -margin - 1.5 * size);
+
+    let pad = [
+        (Action::Up, "⏶"),
+        (Action::Down, "⏷"),
+        (Action::Left, "⏴"),
+        (Action::Right, "⏵"),
+    ];
+    for (action, label) in pad {
+        let button_rect =
+            Rect::from_center_size(center + action.direction() * size, Vec2::splat(size));
+        let response = ui.put(button_rect, egui::Button::new(label).small());
+        if response.is_pointer_button_down_on() {
+            state.hold(action);
+        }
+    }
+
+    let play_pause = Rect::from_center_size(
+        rect.right_bottom() + egui::vec2(-margin - size / 2.0, -margin - size / 2.0),
+        Vec2::splat(size),
+    );
+    if ui.put(play_pause, egui::Button::new("⏯")).clicked() {
+        state.press(Action::PlayPause);
+    }
+}
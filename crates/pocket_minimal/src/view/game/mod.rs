@@ -0,0 +1,577 @@
+//! The Game view: a small arcade game running on a fixed-timestep loop.
+//!
+//! The simulation is deterministic: it only uses its seeded [`rng::Rng`] and
+//! fixed-size steps, so a [`Recording`] of the input replays identically,
+//! tick for tick, on native and on the web.
+
+mod game_loop;
+mod input;
+mod replay;
+mod rng;
+mod scoreboard;
+mod world;
+
+use std::collections::BTreeMap;
+
+pub use game_loop::{GameLoop, MAX_STEPS_PER_FRAME, STEP};
+pub use input::{Action, ActionState, Binding, Bindings};
+pub use replay::{Recording, Replay, Snapshot};
+pub use scoreboard::{Scoreboard, Session, TOP_N};
+pub use world::{Mode, World};
+
+use crate::is_mobile;
+
+/// Dodge the falling blocks and collect the coins.
+///
+/// The simulation advances in fixed steps of [`STEP`], independent
+/// of how often egui repaints, and repaints continuously only while running.
+///
+/// Every step is recorded, so the game can be saved to a named slot and
+/// replayed later. Finished games go on the leaderboard of their [`Mode`].
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "persistence", serde(default))]
+pub struct Game {
+    world: World,
+    /// How `world` came to be, from its seed.
+    recording: Recording,
+    bindings: Bindings,
+
+    /// Named save slots.
+    slots: BTreeMap<String, Snapshot>,
+    /// Is the saves window open?
+    saves_open: bool,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    slot_name: String,
+    /// A replay being imported in the saves window.
+    #[cfg(feature = "persistence")]
+    #[serde(skip)]
+    import: String,
+    /// The outcome of the last save, load or replay.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    status: Option<String>,
+
+    /// Saved under its own key, so that it outlives the rest of the state.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    scoreboard: Scoreboard,
+    /// Is the results window open?
+    results_open: bool,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    results: scoreboard::Results,
+
+    /// Replaces the player's input while replaying,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    replay: Option<Replay>,
+    /// and where it should end up.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    replay_of: Option<Snapshot>,
+
+    /// Always starts paused.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    game_loop: GameLoop,
+
+    /// Is the controls window open?
+    controls_open: bool,
+    /// The action waiting for a new binding in the controls window.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    rebinding: Option<Action>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        let seed = rng::random_seed();
+        Self {
+            world: World::new(seed, Mode::default()),
+            recording: Recording::new(seed, Mode::default()),
+            bindings: Bindings::default(),
+            slots: BTreeMap::new(),
+            saves_open: false,
+            slot_name: String::new(),
+            #[cfg(feature = "persistence")]
+            import: String::new(),
+            status: None,
+            scoreboard: Scoreboard::default(),
+            results_open: false,
+            results: scoreboard::Results::default(),
+            replay: None,
+            replay_of: None,
+            game_loop: GameLoop::default(),
+            controls_open: false,
+            rebinding: None,
+        }
+    }
+}
+
+impl Game {
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.game_loop.paused && (self.is_replaying() || !self.world.is_over())
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    pub fn scoreboard(&self) -> &Scoreboard {
+        &self.scoreboard
+    }
+
+    /// Start a new game in the same mode with a random seed, paused.
+    pub fn restart(&mut self) {
+        self.start(rng::random_seed(), self.world.mode());
+    }
+
+    /// Start a new game with the given seed and mode, paused.
+    pub fn start(&mut self, seed: u64, mode: Mode) {
+        self.world = World::new(seed, mode);
+        self.recording = Recording::new(seed, mode);
+        self.game_loop = GameLoop::default();
+        self.replay = None;
+        self.replay_of = None;
+    }
+
+    /// The current game, with how it came to be.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            world: self.world.clone(),
+            recording: self.recording.clone(),
+        }
+    }
+
+    pub fn slots(&self) -> &BTreeMap<String, Snapshot> {
+        &self.slots
+    }
+
+    /// Save the current game in the slot `name`, replacing what was there.
+    pub fn save(&mut self, name: &str) {
+        self.slots.insert(name.to_owned(), self.snapshot());
+    }
+
+    /// Continue the game saved in the slot `name`, paused.
+    pub fn load(&mut self, name: &str) -> bool {
+        let Some(snapshot) = self.slots.get(name).cloned() else {
+            return false;
+        };
+        self.start(snapshot.recording.seed, snapshot.recording.mode);
+        self.world = snapshot.world;
+        self.recording = snapshot.recording;
+        true
+    }
+
+    /// Play `snapshot` back from its first tick, checking that it ends up in the saved world.
+    pub fn replay(&mut self, snapshot: Snapshot) {
+        self.start(snapshot.recording.seed, snapshot.recording.mode);
+        self.replay = Some(snapshot.recording.replay());
+        self.replay_of = Some(snapshot);
+        self.game_loop.resume();
+    }
+
+    /// Advance the simulation by one step, with the player's or the replay's input.
+    fn step(&mut self) {
+        let direction = match self.replay.as_mut().map(Iterator::next) {
+            Some(Some(direction)) => direction,
+            Some(None) => return self.finish_replay(),
+            None => self.world.direction,
+        };
+        if self.world.is_over() {
+            return;
+        }
+        self.world.direction = direction;
+        self.recording.push(direction);
+        self.world.step(STEP);
+        if self.world.is_over() && !self.is_replaying() {
+            self.finish_session();
+        }
+    }
+
+    /// Put the game that just ended on the leaderboard, and show the results.
+    fn finish_session(&mut self) {
+        let session = Session::of(&self.world, &self.recording);
+        let rank = self.scoreboard.submit(session.clone());
+        self.results.finished(session, rank);
+        self.results_open = true;
+    }
+
+    /// Pause at the end of a replay, and report whether it went as recorded.
+    ///
+    /// The game can then be continued from there.
+    fn finish_replay(&mut self) {
+        self.replay = None;
+        self.game_loop.pause();
+        if let Some(snapshot) = self.replay_of.take() {
+            self.status = Some(if snapshot.matches(&self.world) {
+                format!(
+                    "Replayed {} ticks, identical to the recording",
+                    self.world.tick
+                )
+            } else {
+                log::warn!("Replay diverged from its recording");
+                format!(
+                    "Replay diverged from the recording by tick {}",
+                    self.world.tick
+                )
+            });
+        }
+    }
+
+    /// Run the actions triggered this frame.
+    fn apply(&mut self, actions: &ActionState) {
+        if actions.pressed(Action::Restart) {
+            self.restart();
+        }
+        if self.is_replaying() {
+            // Only the replay steers
+            return;
+        }
+        if actions.pressed(Action::PlayPause) && !self.world.is_over() {
+            if self.game_loop.paused {
+                self.game_loop.resume();
+            } else {
+                self.game_loop.pause();
+            }
+        }
+        if actions.pressed(Action::Step) && !self.world.is_over() {
+            self.game_loop.step();
+        }
+        self.world.direction = actions.direction();
+    }
+
+    /// Play, pause, step and restart buttons, and the score.
+    fn controls_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let over = self.world.is_over();
+            if self.is_running() {
+                if ui.button("⏸ Pause").clicked() {
+                    self.game_loop.pause();
+                }
+            } else if ui.add_enabled(!over, egui::Button::new("▶ Play")).clicked() {
+                self.game_loop.resume();
+            }
+            if ui
+                .add_enabled(!over, egui::Button::new("⏭ Step"))
+                .on_hover_text("Advance a single tick")
+                .clicked()
+            {
+                self.game_loop.step();
+            }
+            if ui.button("⟲ Restart").clicked() {
+                self.restart();
+            }
+            let mut mode = self.world.mode();
+            egui::ComboBox::from_id_salt("game_mode")
+                .selected_text(mode.name())
+                .show_ui(ui, |ui| {
+                    for m in Mode::ALL {
+                        ui.selectable_value(&mut mode, m, m.name())
+                            .on_hover_text(m.description());
+                    }
+                })
+                .response
+                .on_hover_text("Changing the mode starts a new game");
+            if mode != self.world.mode() {
+                self.start(rng::random_seed(), mode);
+            }
+            ui.toggle_value(&mut self.controls_open, "⌨ Controls");
+            ui.toggle_value(&mut self.saves_open, "💾 Saves");
+            ui.toggle_value(&mut self.results_open, "🏆 Results");
+
+            if let Some(replay) = &self.replay {
+                ui.separator();
+                ui.label(format!(
+                    "⟳ Replaying {}/{}",
+                    replay.position(),
+                    replay.recording().len()
+                ));
+                if ui.button("⏹ Stop").clicked() {
+                    self.replay = None;
+                    self.replay_of = None;
+                    self.game_loop.pause();
+                }
+            }
+
+            ui.separator();
+            let session = Session::of(&self.world, &self.recording);
+            ui.label(format!("Score: {}", self.world.score));
+            ui.label(format!("Lives: {}", self.world.lives));
+            match self.world.ticks_left() {
+                Some(ticks) => ui.label(format!(
+                    "Time left: {}",
+                    scoreboard::format_duration(ticks as f32 * STEP)
+                )),
+                None => ui.label(format!(
+                    "Time: {}",
+                    scoreboard::format_duration(session.duration())
+                )),
+            };
+            ui.label(format!("APM: {:.0}", session.apm()))
+                .on_hover_text("Actions per minute: setting off or turning");
+            ui.weak(format!("Tick {}", self.world.tick));
+        });
+    }
+
+    /// The save slots, and copying replays from and to the clipboard.
+    fn saves_ui(&mut self, ui: &mut egui::Ui) {
+        let default_name = format!("Slot {}", self.slots.len() + 1);
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.slot_name)
+                    .hint_text(&default_name)
+                    .desired_width(120.0),
+            );
+            if ui
+                .add_enabled(!self.is_replaying(), egui::Button::new("Save"))
+                .clicked()
+            {
+                let name = if self.slot_name.trim().is_empty() {
+                    default_name.clone()
+                } else {
+                    self.slot_name.trim().to_owned()
+                };
+                self.save(&name);
+                self.status = Some(format!("Saved to {name:?}"));
+                self.slot_name.clear();
+            }
+        });
+
+        enum SlotAction {
+            Load(String),
+            Replay(String),
+            Delete(String),
+        }
+        let mut action = None;
+        if !self.slots.is_empty() {
+            ui.separator();
+            egui::Grid::new("game_slots")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (name, snapshot) in &self.slots {
+                        ui.label(name);
+                        ui.weak(format!(
+                            "Score {}, tick {}",
+                            snapshot.world.score, snapshot.world.tick
+                        ));
+                        if ui.button("Load").clicked() {
+                            action = Some(SlotAction::Load(name.clone()));
+                        }
+                        if ui
+                            .button("⟳ Replay")
+                            .on_hover_text("Play the recorded input back from the start")
+                            .clicked()
+                        {
+                            action = Some(SlotAction::Replay(name.clone()));
+                        }
+                        if ui.button("🗑").on_hover_text("Delete").clicked() {
+                            action = Some(SlotAction::Delete(name.clone()));
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
+        match action {
+            Some(SlotAction::Load(name)) => {
+                self.load(&name);
+                self.status = Some(format!("Loaded {name:?}"));
+            }
+            Some(SlotAction::Replay(name)) => {
+                if let Some(snapshot) = self.slots.get(&name).cloned() {
+                    self.replay(snapshot);
+                    self.status = None;
+                }
+            }
+            Some(SlotAction::Delete(name)) => {
+                self.slots.remove(&name);
+            }
+            None => {}
+        }
+
+        #[cfg(feature = "persistence")]
+        self.clipboard_ui(ui);
+
+        if let Some(status) = &self.status {
+            ui.separator();
+            ui.label(status);
+        }
+    }
+
+    /// Share a game as text: its RON-serialized [`Snapshot`].
+    #[cfg(feature = "persistence")]
+    fn clipboard_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        if ui
+            .button("📋 Copy replay")
+            .on_hover_text("Copy the current game, and how to replay it, as text")
+            .clicked()
+        {
+            match ron::to_string(&self.snapshot()) {
+                Ok(text) => ui.ctx().copy_text(text),
+                Err(err) => self.status = Some(format!("Failed to copy the replay: {err}")),
+            }
+        }
+        ui.add(
+            egui::TextEdit::multiline(&mut self.import)
+                .hint_text("Paste a copied replay here")
+                .desired_rows(2)
+                .code_editor(),
+        );
+        if ui
+            .add_enabled(
+                !self.import.trim().is_empty(),
+                egui::Button::new("⟳ Replay pasted"),
+            )
+            .clicked()
+        {
+            match ron::from_str::<Snapshot>(self.import.trim()) {
+                Ok(snapshot) => {
+                    self.replay(snapshot);
+                    self.import.clear();
+                    self.status = None;
+                }
+                Err(err) => self.status = Some(format!("Not a replay: {err}")),
+            }
+        }
+    }
+}
+
+impl eframe::App for Game {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.bindings.capture(ctx, &mut self.rebinding);
+        let mut actions = if ctx.wants_keyboard_input() || self.rebinding.is_some() {
+            ActionState::default()
+        } else {
+            ctx.input(|i| self.bindings.read(i))
+        };
+
+        egui::TopBottomPanel::top("game_controls").show(ctx, |ui| self.controls_ui(ui));
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::drag());
+            self.world.paint(&painter, response.rect);
+
+            // Steer towards the pointer while it is held down on the arena
+            if let Some(pointer) = response
+                .is_pointer_button_down_on()
+                .then(|| response.interact_pointer_pos())
+                .flatten()
+            {
+                let target = world::to_screen(response.rect).inverse() * pointer;
+                let offset = target - self.world.player();
+                actions.steer = Some(if offset.length() > 0.1 {
+                    offset
+                } else {
+                    egui::Vec2::ZERO
+                });
+            }
+            if is_mobile(ui.ctx()) {
+                input::touch_controls(ui, response.rect, &mut actions);
+            }
+
+            let message = if self.world.ticks_left() == Some(0) {
+                Some("Time's up")
+            } else if self.world.is_over() {
+                Some("Game over")
+            } else if self.game_loop.paused {
+                Some("Paused")
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                painter.text(
+                    response.rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    message,
+                    egui::TextStyle::Heading.resolve(ui.style()),
+                    ui.visuals().strong_text_color(),
+                );
+            }
+        });
+
+        let mut controls_open = self.controls_open;
+        egui::Window::new("⌨ Controls")
+            .open(&mut controls_open)
+            .resizable(false)
+            .show(ctx, |ui| self.bindings.ui(ui, &mut self.rebinding));
+        self.controls_open = controls_open;
+        if !controls_open {
+            self.rebinding = None;
+        }
+
+        let mut saves_open = self.saves_open;
+        egui::Window::new("💾 Saves")
+            .open(&mut saves_open)
+            .resizable(false)
+            .show(ctx, |ui| self.saves_ui(ui));
+        self.saves_open = saves_open;
+
+        let mut results_open = self.results_open;
+        egui::Window::new("🏆 Results")
+            .open(&mut results_open)
+            .resizable(false)
+            .show(ctx, |ui| self.results.ui(ui, &mut self.scoreboard));
+        self.results_open = results_open;
+
+        self.apply(&actions);
+        // Clamp, so that a long pause between frames does not look like a long frame
+        let dt = ctx.input(|i| i.unstable_dt).min(0.25);
+        for _ in 0..self.game_loop.advance(dt) {
+            self.step();
+        }
+
+        // Only keep repainting while there is something moving,
+        // so that the reactive run mode can let the computer rest otherwise.
+        if self.is_running() {
+            ctx.request_repaint();
+        }
+    }
+}
+
+impl super::View for Game {
+    fn name(&self) -> &str {
+        "Game"
+    }
+
+    fn icon(&self) -> &str {
+        "🎮"
+    }
+
+    fn anchor(&self) -> &str {
+        "Game"
+    }
+
+    #[cfg(feature = "persistence")]
+    fn restore_state(
+        &mut self,
+        storage: &dyn eframe::Storage,
+        key: &str,
+        persistence: &mut pocket_core::persistence::Persistence,
+    ) {
+        if let Some(game) = persistence.load_key(storage, key) {
+            *self = game;
+        }
+        if let Some(scoreboard) = persistence.load_key(storage, &format!("{key}/scoreboard")) {
+            self.scoreboard = scoreboard;
+        }
+    }
+
+    #[cfg(feature = "persistence")]
+    fn save_state(
+        &self,
+        storage: &mut dyn eframe::Storage,
+        key: &str,
+        persistence: &mut pocket_core::persistence::Persistence,
+    ) {
+        persistence.save_key(storage, key, self);
+        persistence.save_key(storage, &format!("{key}/scoreboard"), &self.scoreboard);
+    }
+}
+
+/// Versioned since the views were registered, when the Game view got a key of its own.
+#[cfg(feature = "persistence")]
+impl pocket_core::persistence::Versioned for Game {
+    const MIGRATIONS: &'static [pocket_core::persistence::Migration] = &[];
+}
@@ -0,0 +1,156 @@
+//! Recording the input of a game, so that it can be replayed tick for tick.
+//!
+//! A [`World`] only depends on its seed and on the direction the player
+//! wants to go each tick, so that is all a [`Recording`] stores.
+
+use egui::Vec2;
+
+use super::{Mode, World};
+
+/// The seed, mode and per-tick input of a game.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "persistence", serde(default))]
+pub struct Recording {
+    pub seed: u64,
+    pub mode: Mode,
+
+    /// Run-length encoded: the same direction for this many ticks.
+    inputs: Vec<(u32, Vec2)>,
+}
+
+impl Recording {
+    pub fn new(seed: u64, mode: Mode) -> Self {
+        Self {
+            seed,
+            mode,
+            inputs: vec![],
+        }
+    }
+
+    /// Record the input of one more tick.
+    pub fn push(&mut self, direction: Vec2) {
+        match self.inputs.last_mut() {
+            Some((ticks, last)) if *last == direction && *ticks < u32::MAX => *ticks += 1,
+            _ => self.inputs.push((1, direction)),
+        }
+    }
+
+    /// The number of ticks recorded.
+    pub fn len(&self) -> u64 {
+        self.inputs.iter().map(|(ticks, _)| u64::from(*ticks)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How often the player set off or turned, counting the eight compass directions.
+    ///
+    /// Stopping is not an action, and neither are the tiny changes of steering with the pointer.
+    pub fn actions(&self) -> u32 {
+        let mut actions = 0;
+        let mut heading = None;
+        for &(_, direction) in &self.inputs {
+            let new_heading = compass(direction);
+            if new_heading.is_some() && new_heading != heading {
+                actions += 1;
+            }
+            heading = new_heading;
+        }
+        actions
+    }
+
+    /// The recorded input, one direction per tick.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            recording: self.clone(),
+            run: 0,
+            used: 0,
+        }
+    }
+
+    /// Play the whole recording, steps of `dt` seconds, from a new world.
+    pub fn play(&self, dt: f32) -> World {
+        let mut world = World::new(self.seed, self.mode);
+        for direction in self.replay() {
+            world.direction = direction;
+            world.step(dt);
+        }
+        world
+    }
+}
+
+/// Which of the eight compass directions `direction` is closest to, if any.
+fn compass(direction: Vec2) -> Option<i32> {
+    (direction.length_sq() > 1e-6)
+        .then(|| (direction.angle() / std::f32::consts::TAU * 8.0).round() as i32)
+        .map(|octant| octant.rem_euclid(8))
+}
+
+/// Plays back a [`Recording`], one direction per tick.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    recording: Recording,
+    /// Index into `recording.inputs`,
+    run: usize,
+    /// and how many of its ticks were replayed.
+    used: u32,
+}
+
+impl Replay {
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// The number of ticks replayed so far.
+    pub fn position(&self) -> u64 {
+        let done: u64 = self.recording.inputs[..self.run]
+            .iter()
+            .map(|(ticks, _)| u64::from(*ticks))
+            .sum();
+        done + u64::from(self.used)
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Vec2> {
+        // `push` never makes empty runs, but a loaded recording may have them
+        let inputs = &self.recording.inputs;
+        while inputs.get(self.run).is_some_and(|&(ticks, _)| ticks == 0) {
+            self.run += 1;
+        }
+        let &(ticks, direction) = inputs.get(self.run)?;
+        self.used += 1;
+        if self.used == ticks {
+            self.run += 1;
+            self.used = 0;
+        }
+        Some(direction)
+    }
+}
+
+/// A saved game: where it was, and how it got there.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub struct Snapshot {
+    pub world: World,
+    pub recording: Recording,
+}
+
+impl Snapshot {
+    /// Is `world` in exactly the same state as the saved one?
+    pub fn matches(&self, world: &World) -> bool {
+        // The direction is the input for the next tick, not part of the state
+        let mut world = world.clone();
+        world.direction = self.world.direction;
+        world == self.world
+    }
+
+    /// Does playing the recording end up in exactly the saved world?
+    pub fn verify(&self, dt: f32) -> bool {
+        self.matches(&self.recording.play(dt))
+    }
+}
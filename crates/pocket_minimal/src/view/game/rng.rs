@@ -0,0 +1,44 @@
+//! A small seeded random number generator, identical on every platform.
+
+/// `SplitMix64`: fast, tiny state, and good enough for spawning things.
+///
+/// Only integer arithmetic is used, so the same seed gives the same numbers
+/// on native and on the web.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..1`.
+    pub fn next_f32(&mut self) -> f32 {
+        // 24 bits fit exactly in the mantissa of an f32
+        (self.next_u64() >> 40) as f32 / (1_u32 << 24) as f32
+    }
+}
+
+/// A seed that differs from run to run.
+pub fn random_seed() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+
+    #[cfg(target_arch = "wasm32")]
+    let now = js_sys::Date::now() as u64 ^ (js_sys::Math::random() * u64::MAX as f64) as u64;
+
+    Rng::new(now).next_u64()
+}
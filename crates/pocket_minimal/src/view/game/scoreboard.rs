@@ -0,0 +1,379 @@
+//! Statistics of finished games, and the best of them per [`Mode`].
+
+use std::collections::BTreeMap;
+
+use egui::Ui;
+
+use super::{Mode, Recording, World, STEP};
+
+/// The number of games kept on each leaderboard.
+pub const TOP_N: usize = 10;
+
+/// The statistics of one game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub struct Session {
+    pub mode: Mode,
+    pub seed: u64,
+    pub score: u32,
+    /// Simulation steps played, which does not count time spent paused.
+    pub ticks: u64,
+    /// See [`Recording::actions`].
+    pub actions: u32,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+}
+
+impl Session {
+    /// The statistics of the game in `world` so far.
+    pub fn of(world: &World, recording: &Recording) -> Self {
+        Self {
+            mode: world.mode(),
+            seed: world.seed(),
+            score: world.score,
+            ticks: world.tick,
+            actions: recording.actions(),
+            finished_at: now(),
+        }
+    }
+
+    /// Seconds played.
+    pub fn duration(&self) -> f32 {
+        self.ticks as f32 * STEP
+    }
+
+    /// Actions per minute.
+    pub fn apm(&self) -> f32 {
+        if self.ticks == 0 {
+            0.0
+        } else {
+            self.actions as f32 * 60.0 / self.duration()
+        }
+    }
+
+    /// Does this session rank above `other`: a higher score, or the same score sooner?
+    fn beats(&self, other: &Self) -> bool {
+        (self.score, std::cmp::Reverse(self.ticks)) > (other.score, std::cmp::Reverse(other.ticks))
+    }
+}
+
+/// The [`TOP_N`] best sessions of each mode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "persistence", serde(default))]
+pub struct Scoreboard {
+    boards: BTreeMap<Mode, Vec<Session>>,
+}
+
+impl Scoreboard {
+    /// The leaderboard of `mode`, best first.
+    pub fn top(&self, mode: Mode) -> &[Session] {
+        self.boards.get(&mode).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add a finished session, returning its rank (from 0) if it made the leaderboard.
+    pub fn submit(&mut self, session: Session) -> Option<usize> {
+        let board = self.boards.entry(session.mode).or_default();
+        let rank = board
+            .iter()
+            .position(|other| session.beats(other))
+            .unwrap_or(board.len());
+        if rank >= TOP_N {
+            return None;
+        }
+        board.insert(rank, session);
+        board.truncate(TOP_N);
+        Some(rank)
+    }
+
+    pub fn clear(&mut self, mode: Mode) {
+        self.boards.remove(&mode);
+    }
+}
+
+/// Versioned since the Game view got a key of its own, and its scoreboard one next to it.
+#[cfg(feature = "persistence")]
+impl pocket_core::persistence::Versioned for Scoreboard {
+    const MIGRATIONS: &'static [pocket_core::persistence::Migration] = &[];
+}
+
+/// Leaderboard rows, each a rank (from 0) and a session, as comma-separated values.
+pub fn to_csv(sessions: &[(usize, &Session)]) -> String {
+    let mut csv = "rank,mode,score,duration_s,apm,actions,seed,finished_utc\n".to_owned();
+    for (rank, session) in sessions {
+        csv += &format!(
+            "{},{},{},{:.2},{:.1},{},{},{}\n",
+            rank + 1,
+            session.mode.name(),
+            session.score,
+            session.duration(),
+            session.apm(),
+            session.actions,
+            session.seed,
+            format_time(session.finished_at),
+        );
+    }
+    csv
+}
+
+/// Seconds since the Unix epoch.
+fn now() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    #[cfg(target_arch = "wasm32")]
+    let now = (js_sys::Date::now() / 1000.0) as u64;
+
+    now
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC, without pulling in a date library.
+fn format_time(unix_seconds: u64) -> String {
+    // Howard Hinnant's `civil_from_days`
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let minutes = unix_seconds % 86_400 / 60;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// `m:ss`
+pub(super) fn format_duration(seconds: f32) -> String {
+    let seconds = seconds as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// A column of the results table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Rank,
+    Score,
+    Duration,
+    Apm,
+    Date,
+}
+
+impl Column {
+    const ALL: [Self; 5] = [
+        Self::Rank,
+        Self::Score,
+        Self::Duration,
+        Self::Apm,
+        Self::Date,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rank => "#",
+            Self::Score => "Score",
+            Self::Duration => "Time",
+            Self::Apm => "APM",
+            Self::Date => "Date (UTC)",
+        }
+    }
+
+    fn cmp(self, a: &(usize, &Session), b: &(usize, &Session)) -> std::cmp::Ordering {
+        match self {
+            Self::Rank => a.0.cmp(&b.0),
+            Self::Score => a.1.score.cmp(&b.1.score),
+            Self::Duration => a.1.ticks.cmp(&b.1.ticks),
+            Self::Apm => a.1.apm().total_cmp(&b.1.apm()),
+            Self::Date => a.1.finished_at.cmp(&b.1.finished_at),
+        }
+    }
+}
+
+/// The state of the results screen.
+#[derive(Debug)]
+pub struct Results {
+    /// The leaderboard shown.
+    pub mode: Mode,
+    sort_by: Column,
+    descending: bool,
+    /// The session just finished, and its rank if it made the leaderboard.
+    pub last: Option<(Session, Option<usize>)>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    export_path: String,
+    status: Option<String>,
+}
+
+impl Default for Results {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            sort_by: Column::Rank,
+            descending: false,
+            last: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            export_path: "scores.csv".to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl Results {
+    /// Show that `session` just finished, with `rank` on its leaderboard.
+    pub fn finished(&mut self, session: Session, rank: Option<usize>) {
+        self.mode = session.mode;
+        self.sort_by = Column::Rank;
+        self.descending = false;
+        self.last = Some((session, rank));
+        self.status = None;
+    }
+
+    /// The leaderboard of [`Self::mode`], in the chosen order.
+    fn sorted<'a>(&self, scoreboard: &'a Scoreboard) -> Vec<(usize, &'a Session)> {
+        let mut rows: Vec<_> = scoreboard.top(self.mode).iter().enumerate().collect();
+        rows.sort_by(|a, b| {
+            let ordering = self.sort_by.cmp(a, b);
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        rows
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, scoreboard: &mut Scoreboard) {
+        if let Some((session, rank)) = &self.last {
+            ui.heading(match rank {
+                Some(rank) => format!("#{} on the {} leaderboard!", rank + 1, session.mode.name()),
+                None => "Game over".to_owned(),
+            });
+            ui.label(format!(
+                "Score {} in {}, {:.0} actions per minute",
+                session.score,
+                format_duration(session.duration()),
+                session.apm()
+            ));
+            ui.separator();
+        }
+
+        ui.horizontal(|ui| {
+            for mode in Mode::ALL {
+                ui.selectable_value(&mut self.mode, mode, mode.name())
+                    .on_hover_text(mode.description());
+            }
+        });
+
+        let rows = self.sorted(scoreboard);
+        if rows.is_empty() {
+            ui.weak("No finished games yet.");
+        } else {
+            self.table_ui(ui, &rows);
+        }
+
+        ui.separator();
+        let clear = ui
+            .horizontal(|ui| {
+                ui.add_enabled_ui(!rows.is_empty(), |ui| self.export_ui(ui, &rows));
+                ui.add_enabled(!rows.is_empty(), egui::Button::new("🗑 Clear"))
+                    .on_hover_text(format!("Forget the {} leaderboard", self.mode.name()))
+                    .clicked()
+            })
+            .inner;
+        if clear {
+            scoreboard.clear(self.mode);
+            self.last = None;
+        }
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+    }
+
+    fn table_ui(&mut self, ui: &mut Ui, rows: &[(usize, &Session)]) {
+        let highlight = self
+            .last
+            .as_ref()
+            .filter(|(session, _)| session.mode == self.mode)
+            .and_then(|(_, rank)| *rank);
+
+        egui::Grid::new("game_results")
+            .num_columns(Column::ALL.len())
+            .striped(true)
+            .show(ui, |ui| {
+                for column in Column::ALL {
+                    let sorted = self.sort_by == column;
+                    let arrow = match (sorted, self.descending) {
+                        (false, _) => "",
+                        (true, false) => " ⏶",
+                        (true, true) => " ⏷",
+                    };
+                    if ui
+                        .selectable_label(sorted, format!("{}{arrow}", column.name()))
+                        .on_hover_text("Click to sort")
+                        .clicked()
+                    {
+                        if sorted {
+                            self.descending = !self.descending;
+                        } else {
+                            self.sort_by = column;
+                            // Best first, except for the rank, where the best is the lowest
+                            self.descending = column != Column::Rank;
+                        }
+                    }
+                }
+                ui.end_row();
+
+                for (rank, session) in rows {
+                    let text = |text: String| {
+                        if highlight == Some(*rank) {
+                            egui::RichText::new(text).strong()
+                        } else {
+                            egui::RichText::new(text)
+                        }
+                    };
+                    ui.label(text(format!("{}", rank + 1)));
+                    ui.label(text(session.score.to_string()));
+                    ui.label(text(format_duration(session.duration())));
+                    ui.label(text(format!("{:.0}", session.apm())));
+                    ui.label(text(format_time(session.finished_at)));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn export_ui(&mut self, ui: &mut Ui, rows: &[(usize, &Session)]) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(120.0));
+            if ui.button("Export CSV").clicked() {
+                self.status = Some(match std::fs::write(&self.export_path, to_csv(rows)) {
+                    Ok(()) => format!("Wrote {}", self.export_path),
+                    Err(err) => format!("Failed to write {}: {err}", self.export_path),
+                });
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if ui.button("Download CSV").clicked() {
+            let file_name = format!("scores-{}.csv", crate::view::export::slug(self.mode.name()));
+            self.status = Some(
+                match pocket_core::web::download(&file_name, "text/csv", to_csv(rows).as_bytes()) {
+                    Ok(()) => format!("Downloaded {file_name}"),
+                    Err(err) => format!("Download failed: {err:?}"),
+                },
+            );
+        }
+    }
+}
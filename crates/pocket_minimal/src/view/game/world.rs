@@ -0,0 +1,264 @@
+//! The game simulation: dodge the falling blocks, collect the coins.
+//!
+//! Everything is in arena units, with `(0, 0)` in the top left corner
+//! and [`ARENA`] in the bottom right one.
+
+use egui::{emath::RectTransform, pos2, vec2, Color32, Painter, Pos2, Rect, Stroke, Vec2};
+
+use super::{game_loop::TICK_RATE, rng::Rng};
+
+/// The size of the arena.
+pub const ARENA: Vec2 = vec2(16.0, 12.0);
+
+const PLAYER_RADIUS: f32 = 0.4;
+/// Arena units per second.
+const PLAYER_SPEED: f32 = 8.0;
+const HAZARD_SIZE: f32 = 0.8;
+const COIN_RADIUS: f32 = 0.3;
+const MAX_COINS: usize = 3;
+
+/// Ticks between two hazards at the start, and at the fastest.
+const SPAWN_INTERVAL: (u64, u64) = (40, 10);
+/// Ticks for the spawn interval to shrink by one.
+const SPAWN_SPEEDUP: u64 = 300;
+/// Ticks between two coins, while there are fewer than [`MAX_COINS`].
+const COIN_INTERVAL: u64 = 90;
+/// Ticks the player cannot be hit after losing a life.
+const INVULNERABLE_TICKS: u32 = 90;
+
+const COIN_SCORE: u32 = 10;
+const DODGE_SCORE: u32 = 1;
+
+/// The rules a game is played by, each with its own leaderboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub enum Mode {
+    /// Three lives.
+    #[default]
+    Classic,
+    /// A single life.
+    OneLife,
+    /// Three lives, but only 90 seconds.
+    TimeAttack,
+}
+
+impl Mode {
+    pub const ALL: [Self; 3] = [Self::Classic, Self::OneLife, Self::TimeAttack];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::OneLife => "One life",
+            Self::TimeAttack => "Time attack",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Classic => "Three lives",
+            Self::OneLife => "A single hit ends the game",
+            Self::TimeAttack => "Score as much as you can in 90 seconds",
+        }
+    }
+
+    fn lives(self) -> u32 {
+        match self {
+            Self::Classic | Self::TimeAttack => 3,
+            Self::OneLife => 1,
+        }
+    }
+
+    /// The number of ticks a game lasts at most.
+    pub fn time_limit(self) -> Option<u64> {
+        match self {
+            Self::Classic | Self::OneLife => None,
+            Self::TimeAttack => Some(90 * u64::from(TICK_RATE)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+struct Hazard {
+    pos: Pos2,
+    /// Arena units per second.
+    speed: f32,
+}
+
+/// The state of one game.
+///
+/// Everything random comes from a generator seeded with [`Self::seed`],
+/// so the same seed and the same input per tick always give the same game.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub struct World {
+    /// Number of simulation steps so far.
+    pub tick: u64,
+    pub score: u32,
+    pub lives: u32,
+
+    /// Where the player wants to go; the length is ignored.
+    pub direction: Vec2,
+
+    mode: Mode,
+    seed: u64,
+    rng: Rng,
+
+    player: Pos2,
+    invulnerable_ticks: u32,
+    hazards: Vec<Hazard>,
+    coins: Vec<Pos2>,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new(0, Mode::default())
+    }
+}
+
+impl World {
+    pub fn new(seed: u64, mode: Mode) -> Self {
+        Self {
+            tick: 0,
+            score: 0,
+            lives: mode.lives(),
+            direction: Vec2::ZERO,
+            mode,
+            seed,
+            rng: Rng::new(seed),
+            player: pos2(ARENA.x / 2.0, ARENA.y - 2.0 * PLAYER_RADIUS),
+            invulnerable_ticks: 0,
+            hazards: vec![],
+            coins: vec![],
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn player(&self) -> Pos2 {
+        self.player
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.lives == 0 || self.ticks_left() == Some(0)
+    }
+
+    /// The ticks until the time runs out, if there is a time limit.
+    pub fn ticks_left(&self) -> Option<u64> {
+        self.mode
+            .time_limit()
+            .map(|limit| limit.saturating_sub(self.tick))
+    }
+
+    /// Advance the simulation by `dt` seconds.
+    pub fn step(&mut self, dt: f32) {
+        if self.is_over() {
+            return;
+        }
+        self.tick += 1;
+        self.invulnerable_ticks = self.invulnerable_ticks.saturating_sub(1);
+
+        // Move the player, staying inside the arena
+        let velocity = self.direction.normalized() * PLAYER_SPEED;
+        if velocity.is_finite() {
+            self.player += velocity * dt;
+        }
+        self.player = self.player.clamp(
+            pos2(PLAYER_RADIUS, PLAYER_RADIUS),
+            (ARENA - Vec2::splat(PLAYER_RADIUS)).to_pos2(),
+        );
+
+        // Spawn ever more hazards, and coins
+        let interval = SPAWN_INTERVAL
+            .0
+            .saturating_sub(self.tick / SPAWN_SPEEDUP)
+            .max(SPAWN_INTERVAL.1);
+        if self.tick % interval == 0 {
+            let x = HAZARD_SIZE / 2.0 + self.rng.next_f32() * (ARENA.x - HAZARD_SIZE);
+            let speed = 3.0 + 4.0 * self.rng.next_f32();
+            self.hazards.push(Hazard {
+                pos: pos2(x, -HAZARD_SIZE),
+                speed,
+            });
+        }
+        if self.tick % COIN_INTERVAL == 0 && self.coins.len() < MAX_COINS {
+            let x = COIN_RADIUS + self.rng.next_f32() * (ARENA.x - 2.0 * COIN_RADIUS);
+            let y = ARENA.y / 2.0 + self.rng.next_f32() * (ARENA.y / 2.0 - COIN_RADIUS);
+            self.coins.push(pos2(x, y));
+        }
+
+        // Move the hazards, scoring the ones that fell out of the arena
+        for hazard in &mut self.hazards {
+            hazard.pos.y += hazard.speed * dt;
+        }
+        let before = self.hazards.len();
+        self.hazards
+            .retain(|hazard| hazard.pos.y < ARENA.y + HAZARD_SIZE);
+        self.score += DODGE_SCORE * (before - self.hazards.len()) as u32;
+
+        // Collisions
+        let player = self.player;
+        let coins = self.coins.len();
+        self.coins
+            .retain(|coin| coin.distance(player) > PLAYER_RADIUS + COIN_RADIUS);
+        self.score += COIN_SCORE * (coins - self.coins.len()) as u32;
+
+        if self.invulnerable_ticks == 0 {
+            let hit = self
+                .hazards
+                .iter()
+                .position(|hazard| hazard_rect(hazard).distance_to_pos(player) < PLAYER_RADIUS);
+            if let Some(hit) = hit {
+                self.hazards.remove(hit);
+                self.lives -= 1;
+                self.invulnerable_ticks = INVULNERABLE_TICKS;
+            }
+        }
+    }
+
+    /// Draw the world, scaled to fit in `rect`.
+    pub fn paint(&self, painter: &Painter, rect: Rect) {
+        let visuals = painter.ctx().style().visuals.clone();
+        let to_screen = to_screen(rect);
+        let arena = *to_screen.to();
+        let scale = arena.width() / ARENA.x;
+
+        painter.rect_filled(arena, 0.0, visuals.extreme_bg_color);
+        let painter = painter.with_clip_rect(arena);
+
+        for coin in &self.coins {
+            painter.circle_filled(to_screen * *coin, COIN_RADIUS * scale, Color32::GOLD);
+        }
+        for hazard in &self.hazards {
+            let rect = to_screen.transform_rect(hazard_rect(hazard));
+            painter.rect_filled(rect, 0.1 * scale, visuals.error_fg_color);
+        }
+
+        // Blink while invulnerable
+        if self.invulnerable_ticks / 10 % 2 == 0 {
+            painter.circle(
+                to_screen * self.player,
+                PLAYER_RADIUS * scale,
+                visuals.selection.bg_fill,
+                Stroke::new(2.0, visuals.selection.stroke.color),
+            );
+        }
+    }
+}
+
+/// From arena units to the largest rectangle of the same shape that fits in `rect`.
+pub fn to_screen(rect: Rect) -> RectTransform {
+    let scale = (rect.width() / ARENA.x).min(rect.height() / ARENA.y);
+    let arena = Rect::from_center_size(rect.center(), ARENA * scale);
+    RectTransform::from_to(Rect::from_min_size(Pos2::ZERO, ARENA), arena)
+}
+
+fn hazard_rect(hazard: &Hazard) -> Rect {
+    Rect::from_center_size(hazard.pos, Vec2::splat(HAZARD_SIZE))
+}
@@ -0,0 +1,479 @@
+//! A small Markdown subset used for the articles in [`super::About`].
+//!
+//! Supported syntax:
+//!
+//! * `# Heading` (levels 1-3)
+//! * paragraphs separated by blank lines
+//! * `- item`, `* item` and `1. item` lists
+//! * fenced code blocks (` ``` `)
+//! * `---` horizontal rules
+//! * inline `**strong**`, `*emphasis*`, `` `code` `` and `[text](url)`
+//! * links to other articles: `[[Section 1.1]]` or `[[Section 1.1|label]]`
+
+use egui::{RichText, Ui};
+
+use super::search;
+
+// ----------------------------------------------------------------------------
+// Document
+// ----------------------------------------------------------------------------
+
+/// Where a link leads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Link {
+    /// An external `[text](url)` link.
+    Url(String),
+
+    /// An internal `[[title]]` link to another article.
+    Article(String),
+}
+
+/// A run of text sharing the same style.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub strong: bool,
+    pub emphasis: bool,
+    pub code: bool,
+    pub link: Option<Link>,
+}
+
+/// A top-level element of a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        spans: Vec<Span>,
+    },
+    Paragraph(Vec<Span>),
+    /// `number` is `None` for bullet lists.
+    ListItem {
+        number: Option<u32>,
+        spans: Vec<Span>,
+    },
+    Code {
+        language: String,
+        code: String,
+    },
+    Rule,
+}
+
+impl Block {
+    fn spans(&self) -> &[Span] {
+        match self {
+            Self::Heading { spans, .. } | Self::Paragraph(spans) | Self::ListItem { spans, .. } => {
+                spans
+            }
+            Self::Code { .. } | Self::Rule => &[],
+        }
+    }
+
+    /// The plain text of this block, without any markup.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Code { code, .. } => code.clone(),
+            _ => self.spans().iter().map(|span| span.text.as_str()).collect(),
+        }
+    }
+
+    /// The titles of the articles this block links to.
+    pub fn article_links(&self) -> impl Iterator<Item = &str> {
+        self.spans().iter().filter_map(|span| match &span.link {
+            Some(Link::Article(title)) => Some(title.as_str()),
+            Some(Link::Url(_)) | None => None,
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Parsing
+// ----------------------------------------------------------------------------
+
+/// Parse a Markdown document into blocks.
+pub fn parse(source: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut lines = source.lines();
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some(language) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            let code = lines
+                .by_ref()
+                .take_while(|l| !l.trim_start().starts_with("```"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            blocks.push(Block::Code {
+                language: language.trim().to_owned(),
+                code,
+            });
+        } else if trimmed.chars().all(|c| c == '-') && trimmed.len() >= 3 {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+        } else if let Some((level, text)) = heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading {
+                level,
+                spans: parse_inline(text),
+            });
+        } else if let Some(text) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem {
+                number: None,
+                spans: parse_inline(text),
+            });
+        } else if let Some((number, text)) = numbered_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem {
+                number: Some(number),
+                spans: parse_inline(text),
+            });
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    blocks
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=3).contains(&level).then(|| (level as u8, text.trim()))
+}
+
+fn numbered_item(line: &str) -> Option<(u32, &str)> {
+    let (number, text) = line.split_once(". ")?;
+    Some((number.parse().ok()?, text))
+}
+
+/// Parse inline markup (`**strong**`, `*emphasis*`, `` `code` `` and links).
+pub fn parse_inline(text: &str) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut style = Span::default();
+    let mut rest = text;
+
+    let push = |spans: &mut Vec<Span>, style: &Span, text: &str| {
+        if !text.is_empty() {
+            spans.push(Span {
+                text: text.to_owned(),
+                ..style.clone()
+            });
+        }
+    };
+
+    while !rest.is_empty() {
+        let Some(i) = rest.find(['*', '`', '[']) else {
+            push(&mut spans, &style, rest);
+            break;
+        };
+        push(&mut spans, &style, &rest[..i]);
+        rest = &rest[i..];
+
+        if let Some(after) = rest.strip_prefix("**") {
+            style.strong = !style.strong;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            style.emphasis = !style.emphasis;
+            rest = after;
+        } else if let Some((code, after)) = rest.strip_prefix('`').and_then(|r| r.split_once('`')) {
+            spans.push(Span {
+                text: code.to_owned(),
+                code: true,
+                ..Default::default()
+            });
+            rest = after;
+        } else if let Some((title, label, after)) = article_link(rest) {
+            spans.push(Span {
+                text: label.to_owned(),
+                link: Some(Link::Article(title.to_owned())),
+                ..style.clone()
+            });
+            rest = after;
+        } else if let Some((label, url, after)) = link(rest) {
+            spans.push(Span {
+                text: label.to_owned(),
+                link: Some(Link::Url(url.to_owned())),
+                ..style.clone()
+            });
+            rest = after;
+        } else {
+            // Not markup after all, e.g. a lone `[` or an unterminated `` ` ``
+            push(&mut spans, &style, &rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+
+    spans
+}
+
+/// Split `[[title|label]]rest` (or `[[title]]rest`) into its parts.
+fn article_link(text: &str) -> Option<(&str, &str, &str)> {
+    let (inner, rest) = text.strip_prefix("[[")?.split_once("]]")?;
+    let (title, label) = inner.split_once('|').unwrap_or((inner, inner));
+    Some((title.trim(), label.trim(), rest))
+}
+
+/// Split `[label](url)rest` into its parts.
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let (label, rest) = text.strip_prefix('[')?.split_once("](")?;
+    let (url, rest) = rest.split_once(')')?;
+    Some((label, url, rest))
+}
+
+// ----------------------------------------------------------------------------
+// Rendering
+// ----------------------------------------------------------------------------
+
+/// Show a parsed document.
+///
+/// Case-insensitive occurrences of the `highlight` terms are highlighted.
+/// Returns the title of the article whose `[[link]]` was clicked, if any.
+pub fn ui(ui: &mut Ui, blocks: &[Block], highlight: &[String]) -> Option<String> {
+    let mut clicked = None;
+    for block in blocks {
+        match block {
+            Block::Heading { level, spans } => {
+                let size = match level {
+                    1 => 22.0,
+                    2 => 18.0,
+                    _ => 16.0,
+                };
+                ui.add_space(6.0);
+                ui.horizontal_wrapped(|ui| {
+                    for span in spans {
+                        span_ui(ui, span, highlight, &mut clicked, |text| {
+                            text.size(size).strong()
+                        });
+                    }
+                });
+            }
+            Block::Paragraph(spans) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    for span in spans {
+                        span_ui(ui, span, highlight, &mut clicked, |text| text);
+                    }
+                });
+                ui.add_space(4.0);
+            }
+            Block::ListItem { number, spans } => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    let bullet = number.map_or_else(|| "• ".to_owned(), |n| format!("{n}. "));
+                    ui.label(format!("  {bullet}"));
+                    for span in spans {
+                        span_ui(ui, span, highlight, &mut clicked, |text| text);
+                    }
+                });
+            }
+            Block::Code { code, .. } => {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing = egui::Vec2::ZERO;
+                        let span = Span {
+                            text: code.clone(),
+                            ..Default::default()
+                        };
+                        span_ui(ui, &span, highlight, &mut clicked, RichText::monospace);
+                    });
+                });
+            }
+            Block::Rule => {
+                ui.separator();
+            }
+        }
+    }
+
+    clicked
+}
+
+fn span_ui(
+    ui: &mut Ui,
+    span: &Span,
+    highlight: &[String],
+    clicked: &mut Option<String>,
+    block_style: impl Fn(RichText) -> RichText,
+) {
+    let selection = ui.visuals().selection;
+    let style = |text: &str, highlighted: bool| {
+        let mut text = block_style(RichText::new(text));
+        if span.strong {
+            text = text.strong();
+        }
+        if span.emphasis {
+            text = text.italics();
+        }
+        if span.code {
+            text = text.code();
+        }
+        if highlighted {
+            text = text
+                .background_color(selection.bg_fill)
+                .color(selection.stroke.color);
+        }
+        text
+    };
+
+    let ranges = search::match_ranges(&span.text, highlight);
+
+    match &span.link {
+        Some(Link::Url(url)) => {
+            ui.hyperlink_to(style(&span.text, !ranges.is_empty()), url);
+            return;
+        }
+        Some(Link::Article(title)) => {
+            if ui
+                .link(style(&span.text, !ranges.is_empty()))
+                .on_hover_text(title)
+                .clicked()
+            {
+                *clicked = Some(title.clone());
+            }
+            return;
+        }
+        None => {}
+    }
+
+    // Split the span into alternating plain and highlighted pieces:
+    let mut end = 0;
+    for range in ranges {
+        if end < range.start {
+            ui.label(style(&span.text[end..range.start], false));
+        }
+        ui.label(style(&span.text[range.clone()], true));
+        end = range.end;
+    }
+    if end < span.text.len() {
+        ui.label(style(&span.text[end..], false));
+    }
+}
+
+// ----------------------------------------------------------------------------
+// HTML
+// ----------------------------------------------------------------------------
+
+/// Render a parsed document as HTML.
+///
+/// `article_href` turns the title of a `[[link]]` target into a URL.
+pub fn to_html(blocks: &[Block], article_href: &dyn Fn(&str) -> String) -> String {
+    use std::fmt::Write as _;
+
+    let mut html = String::new();
+    let mut open_list: Option<&str> = None;
+
+    for block in blocks {
+        // Group consecutive list items into one list:
+        let list = match block {
+            Block::ListItem { number: None, .. } => Some("ul"),
+            Block::ListItem {
+                number: Some(_), ..
+            } => Some("ol"),
+            _ => None,
+        };
+        if open_list != list {
+            if let Some(tag) = open_list {
+                writeln!(html, "</{tag}>").ok();
+            }
+            if let Some(tag) = list {
+                writeln!(html, "<{tag}>").ok();
+            }
+            open_list = list;
+        }
+
+        match block {
+            Block::Heading { level, spans } => {
+                // `h1` is reserved for the article title
+                let level = level + 1;
+                writeln!(
+                    html,
+                    "<h{level}>{}</h{level}>",
+                    spans_html(spans, article_href)
+                )
+                .ok();
+            }
+            Block::Paragraph(spans) => {
+                writeln!(html, "<p>{}</p>", spans_html(spans, article_href)).ok();
+            }
+            Block::ListItem { spans, .. } => {
+                writeln!(html, "<li>{}</li>", spans_html(spans, article_href)).ok();
+            }
+            Block::Code { language, code } => {
+                writeln!(
+                    html,
+                    "<pre><code class=\"language-{}\">{}</code></pre>",
+                    escape_html(language),
+                    escape_html(code)
+                )
+                .ok();
+            }
+            Block::Rule => html += "<hr>\n",
+        }
+    }
+    if let Some(tag) = open_list {
+        writeln!(html, "</{tag}>").ok();
+    }
+
+    html
+}
+
+fn spans_html(spans: &[Span], article_href: &dyn Fn(&str) -> String) -> String {
+    let mut html = String::new();
+    for span in spans {
+        let mut text = escape_html(&span.text);
+        if span.code {
+            text = format!("<code>{text}</code>");
+        }
+        if span.emphasis {
+            text = format!("<em>{text}</em>");
+        }
+        if span.strong {
+            text = format!("<strong>{text}</strong>");
+        }
+        match &span.link {
+            Some(Link::Url(url)) => {
+                html += &format!("<a href=\"{}\">{text}</a>", escape_html(url));
+            }
+            Some(Link::Article(title)) => {
+                html += &format!(
+                    "<a href=\"{}\">{text}</a>",
+                    escape_html(&article_href(title))
+                );
+            }
+            None => html += &text,
+        }
+    }
+    html
+}
+
+/// Escape text for use in HTML content and attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&#39;",
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,3 +1,14 @@
 mod about;
+mod export;
+mod game;
+mod markdown;
+mod registry;
+mod search;
 
-pub use about::About;
\ No newline at end of file
+pub use about::{About, Article, ArticleError, ContentError};
+pub use game::{
+    Action, Binding, Bindings, Game, GameLoop, Mode, Recording, Replay, Scoreboard, Session,
+    Snapshot, World, MAX_STEPS_PER_FRAME, STEP, TOP_N,
+};
+pub use registry::{View, ViewRegistry};
+pub use search::{match_ranges, Hit, SearchIndex};
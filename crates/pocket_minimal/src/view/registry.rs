@@ -0,0 +1,114 @@
+//! The screens [`crate::Interface`] can switch between.
+
+/// A screen of the [`crate::Interface`], listed in its top bar.
+///
+/// Register your own with [`crate::Interface::with_view`].
+pub trait View: eframe::App {
+    /// Shown in the top bar, e.g. `About`.
+    fn name(&self) -> &str;
+
+    /// Shown before the name in the top bar, e.g. `❔`.
+    fn icon(&self) -> &str;
+
+    /// Identifies the view in deep links (`#About/…`) and in the app storage.
+    /// Must be unique.
+    fn anchor(&self) -> &str;
+
+    /// The view-specific part of the deep link to what is shown, see [`crate::deep_link`].
+    fn deep_link(&self) -> Option<&str> {
+        None
+    }
+
+    /// Follow the view-specific part of a deep link.
+    ///
+    /// Returns `false` if it points at nothing.
+    fn open_deep_link(&mut self, path: &str) -> bool {
+        path.is_empty()
+    }
+
+    /// Load the state stored under `key`, e.g. with
+    /// [`Persistence::load_key`](pocket_core::persistence::Persistence::load_key).
+    #[cfg(feature = "persistence")]
+    fn restore_state(
+        &mut self,
+        _storage: &dyn eframe::Storage,
+        _key: &str,
+        _persistence: &mut pocket_core::persistence::Persistence,
+    ) {
+    }
+
+    /// Store the state under `key`, e.g. with
+    /// [`Persistence::save_key`](pocket_core::persistence::Persistence::save_key).
+    #[cfg(feature = "persistence")]
+    fn save_state(
+        &self,
+        _storage: &mut dyn eframe::Storage,
+        _key: &str,
+        _persistence: &mut pocket_core::persistence::Persistence,
+    ) {
+    }
+}
+
+struct Registered {
+    view: Box<dyn View>,
+    /// Makes a fresh view, for when everything is reset.
+    make: Box<dyn Fn() -> Box<dyn View>>,
+}
+
+/// The registered [`View`]s, in top bar order.
+#[derive(Default)]
+pub struct ViewRegistry {
+    views: Vec<Registered>,
+}
+
+impl ViewRegistry {
+    /// Add the view made by `make`. It replaces any view with the same anchor.
+    pub fn add<V: View + 'static>(&mut self, make: impl Fn() -> V + 'static) -> &mut dyn View {
+        let view = Box::new(make());
+        let make: Box<dyn Fn() -> Box<dyn View>> = Box::new(move || Box::new(make()));
+        let registered = Registered { view, make };
+
+        let index = if let Some(index) = self.position(registered.view.anchor()) {
+            self.views[index] = registered;
+            index
+        } else {
+            self.views.push(registered);
+            self.views.len() - 1
+        };
+        self.views[index].view.as_mut()
+    }
+
+    fn position(&self, anchor: &str) -> Option<usize> {
+        self.views.iter().position(|r| r.view.anchor() == anchor)
+    }
+
+    pub fn get_mut(&mut self, anchor: &str) -> Option<&mut dyn View> {
+        let index = self.position(anchor)?;
+        Some(self.views[index].view.as_mut())
+    }
+
+    pub fn get(&self, anchor: &str) -> Option<&dyn View> {
+        let index = self.position(anchor)?;
+        Some(self.views[index].view.as_ref())
+    }
+
+    /// The anchor of the first view, the one shown by default.
+    pub fn first_anchor(&self) -> Option<&str> {
+        self.views.first().map(|r| r.view.anchor())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn View> {
+        self.views.iter().map(|r| r.view.as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut (dyn View + 'static)> {
+        self.views.iter_mut().map(|r| r.view.as_mut())
+    }
+
+    /// Replace every view by a fresh one.
+    pub fn reset(&mut self) {
+        for registered in &mut self.views {
+            registered.view = (registered.make)();
+        }
+    }
+}
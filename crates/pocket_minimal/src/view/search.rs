@@ -0,0 +1,168 @@
+//! Full-text search over the [`super::Article`]s of [`super::About`].
+
+use std::collections::BTreeMap;
+
+use super::about::Article;
+
+/// How much more a match in the title is worth than one in the body.
+const TITLE_WEIGHT: f32 = 10.0;
+
+/// Number of characters of context shown around the first hit in a snippet.
+const SNIPPET_CONTEXT: usize = 40;
+
+/// The lowercased text of one article.
+struct Entry {
+    title: String,
+    title_lower: String,
+    body: String,
+    body_lower: String,
+}
+
+impl Entry {
+    fn new(article: &Article) -> Self {
+        let body = article.text();
+        Self {
+            title_lower: article.title.to_ascii_lowercase(),
+            title: article.title.clone(),
+            body_lower: body.to_ascii_lowercase(),
+            body,
+        }
+    }
+}
+
+/// A search result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hit {
+    pub title: String,
+    pub score: f32,
+    /// A bit of body text around the first match, if the body matched.
+    pub snippet: Option<String>,
+}
+
+/// Index of the titles and body text of a set of articles.
+#[derive(Default)]
+pub struct SearchIndex {
+    entries: Vec<Entry>,
+}
+
+impl SearchIndex {
+    pub fn new(articles: &BTreeMap<String, Article>) -> Self {
+        let entries = articles.values().map(Entry::new).collect();
+        Self { entries }
+    }
+
+    /// Index the new text of `article`, in place of the article with the same title.
+    ///
+    /// Cheaper than indexing every article again after one was edited.
+    pub fn update(&mut self, article: &Article) {
+        match self
+            .entries
+            .binary_search_by(|entry| entry.title.as_str().cmp(&article.title))
+        {
+            Ok(index) => self.entries[index] = Entry::new(article),
+            Err(index) => self.entries.insert(index, Entry::new(article)),
+        }
+    }
+
+    /// Find the articles containing every term of `query`, best match first.
+    pub fn search(&self, query: &str) -> Vec<Hit> {
+        let terms = terms(query);
+        if terms.is_empty() {
+            return vec![];
+        }
+
+        let mut hits: Vec<Hit> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let mut score = 0.0;
+                for term in &terms {
+                    let in_title = entry.title_lower.matches(term.as_str()).count();
+                    let in_body = entry.body_lower.matches(term.as_str()).count();
+                    if in_title + in_body == 0 {
+                        return None;
+                    }
+                    score += TITLE_WEIGHT * in_title as f32 + in_body as f32;
+                }
+                if entry.title_lower == query.trim().to_ascii_lowercase() {
+                    score += TITLE_WEIGHT;
+                }
+                Some(Hit {
+                    title: entry.title.clone(),
+                    score,
+                    snippet: snippet(&entry.body, &entry.body_lower, &terms),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        hits
+    }
+}
+
+/// Split a query into lowercase search terms.
+pub fn terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// Byte ranges of every case-insensitive occurrence of any of the `terms` in `text`.
+///
+/// Overlapping ranges are merged.
+pub fn match_ranges(text: &str, terms: &[String]) -> Vec<std::ops::Range<usize>> {
+    let lower = text.to_ascii_lowercase();
+    let mut ranges: Vec<_> = terms
+        .iter()
+        .filter(|term| !term.is_empty())
+        .flat_map(|term| {
+            lower
+                .match_indices(term.as_str())
+                .map(|(start, m)| start..start + m.len())
+        })
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<std::ops::Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn snippet(body: &str, body_lower: &str, terms: &[String]) -> Option<String> {
+    let start = terms
+        .iter()
+        .filter_map(|term| body_lower.find(term.as_str()))
+        .min()?;
+
+    let from = floor_char_boundary(body, start.saturating_sub(SNIPPET_CONTEXT));
+    let to = floor_char_boundary(body, (start + SNIPPET_CONTEXT).min(body.len()));
+
+    let mut snippet = body[from..to]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < body.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
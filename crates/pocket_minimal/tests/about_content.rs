@@ -0,0 +1,105 @@
+//! Catch broken links and parents in the About articles before users do.
+
+use std::collections::BTreeMap;
+
+use pocket_minimal::view::{About, Article, ContentError};
+
+#[test]
+fn embedded_articles_are_valid() {
+    let about = About::from_articles(About::embedded());
+    let problems = about.validate();
+    assert!(problems.is_empty(), "{problems:#?}");
+}
+
+#[test]
+fn broken_links_and_parents_are_reported() {
+    let articles = [
+        Article::new("A", "Table of Contents", "See [[B]] and [[Nowhere]]."),
+        Article::new("B", "Missing", "Back to [[A|the start]]."),
+    ];
+    let articles: BTreeMap<_, _> = articles
+        .into_iter()
+        .map(|article| (article.title.clone(), article))
+        .collect();
+
+    let problems = About::from_articles(articles).validate();
+    assert_eq!(
+        problems,
+        vec![
+            ContentError::BrokenLink {
+                article: "A".to_owned(),
+                target: "Nowhere".to_owned(),
+            },
+            ContentError::MissingParent {
+                article: "B".to_owned(),
+                parent: "Missing".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn renaming_and_deleting_keep_links_and_parents_valid() {
+    let articles = [
+        Article::new("A", "Table of Contents", "See [[B]] and [[B|the child]]."),
+        Article::new("B", "A", "Back to [[A]]."),
+        Article::new("C", "B", "A grandchild."),
+    ];
+    let mut about = About::from_articles(
+        articles
+            .into_iter()
+            .map(|article| (article.title.clone(), article))
+            .collect(),
+    );
+
+    assert!(about.rename("B", "Renamed"));
+    assert!(!about.rename("A", "Renamed"), "title is taken");
+    assert!(about.validate().is_empty(), "{:#?}", about.validate());
+
+    assert!(about.delete("Renamed"));
+    // C moved up to A, so only the links to the deleted article are broken
+    assert_eq!(
+        about.validate(),
+        vec![
+            ContentError::BrokenLink {
+                article: "A".to_owned(),
+                target: "Renamed".to_owned(),
+            };
+            2
+        ]
+    );
+}
+
+#[test]
+fn files_with_the_same_title_are_reported() {
+    let temp = tempfile::tempdir().unwrap();
+    let write = |name: &str, body: &str| {
+        let text = format!("---\ntitle: A\nparent: Table of Contents\n---\n{body}\n");
+        std::fs::write(temp.path().join(name), text).unwrap();
+    };
+    write("b.md", "The second file.");
+    write("a.md", "The first file.");
+
+    let (articles, duplicates) = About::load_dir(temp.path()).unwrap();
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles["A"].source.trim(), "The first file.");
+    assert_eq!(
+        duplicates,
+        vec![ContentError::DuplicateTitle {
+            title: "A".to_owned(),
+            kept: temp.path().join("a.md").display().to_string(),
+            skipped: temp.path().join("b.md").display().to_string(),
+        }]
+    );
+}
+
+#[test]
+fn articles_survive_saving() {
+    for article in About::embedded().values() {
+        let saved = Article::from_markdown(&article.to_markdown()).unwrap();
+        assert_eq!(saved.title, article.title);
+        assert_eq!(saved.parent, article.parent);
+        assert_eq!(saved.order, article.order);
+        assert_eq!(saved.source, article.source);
+    }
+}
@@ -0,0 +1,70 @@
+//! The command line of the native binary is parsed into what it asks for.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::PathBuf;
+
+use pocket_minimal::cli::{parse, Cli, Options};
+
+fn parse_args(args: &[&str]) -> Result<Cli, String> {
+    parse(args.iter().map(|&arg| arg.to_owned()))
+}
+
+#[test]
+fn no_arguments_run_with_defaults() {
+    assert_eq!(parse_args(&[]), Ok(Cli::Run(Options::default())));
+}
+
+#[test]
+fn options_are_read() {
+    let cli = parse_args(&[
+        "--view",
+        "About/Section 1",
+        "--window-size",
+        "800X600",
+        "--persistence-dir",
+        "state",
+        "--log-level",
+        "trace",
+    ]);
+    assert_eq!(
+        cli,
+        Ok(Cli::Run(Options {
+            view: Some("About/Section 1".to_owned()),
+            window_size: Some([800.0, 600.0]),
+            persistence_path: Some(PathBuf::from("state")),
+            log_level: Some("trace".to_owned()),
+        }))
+    );
+}
+
+#[test]
+fn exports_and_help_stop_parsing() {
+    assert_eq!(
+        parse_args(&["--view", "Game", "--export-html", "site", "--unknown"]),
+        Ok(Cli::ExportHtml(PathBuf::from("site")))
+    );
+    assert_eq!(
+        parse_args(&["--export-single-html", "all.html"]),
+        Ok(Cli::ExportSingleHtml(PathBuf::from("all.html")))
+    );
+    assert_eq!(parse_args(&["-h", "--unknown"]), Ok(Cli::Help));
+}
+
+#[test]
+fn bad_arguments_are_errors() {
+    for args in [
+        &["--unknown"][..],
+        &["--view"],
+        &["--window-size", "1280"],
+        &["--window-size", "0x1024"],
+        &["--window-size", "wide x tall"],
+        &["--log-level", "loud"],
+        &["--export-html"],
+    ] {
+        assert!(parse_args(args).is_err(), "{args:?}");
+    }
+    assert_eq!(
+        parse_args(&["--view"]),
+        Err("Usage: --view <VIEW>".to_owned())
+    );
+}
@@ -0,0 +1,123 @@
+//! Deep links survive encoding, and About remembers where it has been.
+
+use std::collections::BTreeMap;
+
+use pocket_minimal::deep_link::{format, parse};
+use pocket_minimal::view::{About, Article};
+
+#[test]
+fn links_round_trip() {
+    for (view, path) in [
+        ("About", "Section 1.1"),
+        ("About", "100% sure? #yes & no"),
+        ("About", "a/b/c"),
+        ("About", "Ünïcödé ✓"),
+        ("Game", ""),
+    ] {
+        let link = format(view, Some(path));
+        assert!(
+            link[1..]
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || b"%-._~/".contains(&byte)),
+            "{link}"
+        );
+        assert_eq!(parse(&link), (view.to_owned(), path.to_owned()), "{link}");
+    }
+
+    assert_eq!(format("About", None), "#About");
+    assert_eq!(format("About", Some("Section 1")), "#About/Section%201");
+    // The `#` is optional
+    assert_eq!(parse("About/x"), ("About".to_owned(), "x".to_owned()));
+}
+
+#[test]
+fn malformed_escapes_are_kept() {
+    let path = |hash: &str| parse(hash).1;
+    assert_eq!(path("#About/%41%62"), "Ab");
+    assert_eq!(path("#About/%+1"), "%+1");
+    assert_eq!(path("#About/%-1x"), "%-1x");
+    assert_eq!(path("#About/%zz"), "%zz");
+    assert_eq!(path("#About/100%"), "100%");
+    assert_eq!(path("#About/%4"), "%4");
+    assert_eq!(path("#About/%C3%A9"), "é");
+}
+
+fn about(titles: &[&str]) -> About {
+    let articles: BTreeMap<_, _> = titles
+        .iter()
+        .map(|title| {
+            let article = Article::new(title, "Table of Contents", "");
+            (article.title.clone(), article)
+        })
+        .collect();
+    About::from_articles(articles)
+}
+
+#[test]
+fn back_and_forward() {
+    let mut about = about(&["A", "B", "C"]);
+    assert!(!about.back());
+    about.select("A");
+    about.select("B");
+    about.select("B"); // Not a visit
+    about.select("C");
+
+    assert!(about.back());
+    assert_eq!(about.selected(), "B");
+    assert!(about.back());
+    assert_eq!(about.selected(), "A");
+    assert!(about.forward());
+    assert_eq!(about.selected(), "B");
+
+    // Visiting somewhere new forgets the way forward
+    about.select("A");
+    assert!(!about.forward());
+    assert!(about.back());
+    assert_eq!(about.selected(), "B");
+}
+
+#[test]
+fn history_follows_renames_and_deletes() {
+    let mut about = about(&["A", "B", "C"]);
+    about.select("A");
+    about.select("B");
+    about.select("C");
+
+    assert!(about.rename("A", "Alpha"));
+    assert!(about.delete("B"));
+    assert!(about.back());
+    assert_eq!(about.selected(), "Alpha");
+    assert!(about.back());
+    assert_eq!(about.selected(), "Table of Contents");
+    assert!(!about.back());
+}
+
+#[test]
+fn history_is_bounded() {
+    let titles: Vec<String> = (0..150).map(|i| format!("Article {i}")).collect();
+    let titles: Vec<&str> = titles.iter().map(String::as_str).collect();
+    let mut about = about(&titles);
+    for title in &titles {
+        about.select(title);
+    }
+
+    let mut steps = 0;
+    while about.back() {
+        steps += 1;
+    }
+    assert_eq!(steps, 100);
+    assert_eq!(about.selected(), "Article 49");
+}
+
+#[test]
+fn deep_links_select_articles() {
+    let mut about = about(&["A"]);
+    assert!(about.open_deep_link("A"));
+    assert_eq!(about.deep_link(), Some("A"));
+    assert!(!about.open_deep_link("Nowhere"));
+    assert_eq!(about.selected(), "A");
+    assert!(about.open_deep_link(""));
+    assert_eq!(about.deep_link(), None);
+    assert!(about.back());
+    assert_eq!(about.selected(), "A");
+}
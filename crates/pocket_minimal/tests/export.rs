@@ -0,0 +1,132 @@
+//! The exported HTML is what people without the app read, so check it closely.
+
+use std::collections::BTreeMap;
+
+use pocket_minimal::view::{About, Article};
+
+fn about() -> About {
+    let articles = [
+        Article::new(
+            "Getting Started",
+            "Table of Contents",
+            "# Hi\n\n\
+            See [[Notes & <Details>|the details]] or [the site](https://example.com).\n\n\
+            - one\n\
+            - two\n\n\
+            ```\nlet x = 1 < 2;\n```\n",
+        ),
+        Article::new(
+            "Notes & <Details>",
+            "Getting Started",
+            "Back to [[Getting Started]].",
+        ),
+        // Same slug as "Getting Started"
+        Article::new("Getting-Started", "Table of Contents", "Again."),
+        Article::new("Orphan", "Missing", "Nobody links here."),
+    ];
+    About::from_articles(
+        articles
+            .into_iter()
+            .map(|article| (article.title.clone(), article))
+            .collect(),
+    )
+}
+
+/// What comes after the shared `<head>`.
+fn body(html: &str) -> &str {
+    let (head, body) = html.split_once("</head>\n").unwrap();
+    assert!(head.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n"));
+    body
+}
+
+#[test]
+fn site_has_a_page_per_article() {
+    let pages: BTreeMap<_, _> = about().export_site().into_iter().collect();
+    assert_eq!(
+        pages.keys().map(String::as_str).collect::<Vec<_>>(),
+        [
+            "getting-started-2.html",
+            "getting-started.html",
+            "index.html",
+            "notes-details.html",
+            "orphan.html",
+        ]
+    );
+
+    assert_eq!(
+        body(&pages["getting-started.html"]),
+        "<body>\n\
+        <nav><a href=\"index.html\">⏶ Table of Contents</a></nav>\n\
+        <h1><span class=\"number\">1</span>Getting Started</h1>\n\
+        <h2>Hi</h2>\n\
+        <p>See <a href=\"notes-details.html\">the details</a> or \
+        <a href=\"https://example.com\">the site</a>.</p>\n\
+        <ul>\n<li>one</li>\n<li>two</li>\n</ul>\n\
+        <pre><code class=\"language-\">let x = 1 &lt; 2;</code></pre>\n\
+        </body>\n\
+        </html>\n"
+    );
+    assert!(pages["notes-details.html"].contains("<title>Notes &amp; &lt;Details&gt;</title>"));
+    assert!(pages["notes-details.html"].contains(
+        "<h1><span class=\"number\">1.1</span>Notes &amp; &lt;Details&gt;</h1>\n\
+        <p>Back to <a href=\"getting-started.html\">Getting Started</a>.</p>\n"
+    ));
+    // Not in the table of contents, so not numbered
+    assert!(pages["orphan.html"].contains("<h1>Orphan</h1>"));
+
+    assert_eq!(
+        body(&pages["index.html"]),
+        "<body>\n\
+        <h1>Table of Contents</h1>\n\
+        <div class=\"toc\">\n\
+        <ul>\n\
+        <li><span class=\"number\">1</span><a href=\"getting-started.html\">Getting Started</a>\n\
+        <ul>\n\
+        <li><span class=\"number\">1.1</span><a href=\"notes-details.html\">Notes &amp; &lt;Details&gt;</a>\n\
+        </li>\n\
+        </ul>\n\
+        </li>\n\
+        <li><span class=\"number\">2</span><a href=\"getting-started-2.html\">Getting-Started</a>\n\
+        </li>\n\
+        </ul>\n\
+        </div>\n\
+        </body>\n\
+        </html>\n"
+    );
+}
+
+#[test]
+fn single_page_follows_the_table_of_contents() {
+    let html = about().export_single_page();
+    let sections = html
+        .match_indices("<section id=\"")
+        .map(|(i, _)| html[i..].split('"').nth(1).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        [
+            "getting-started",
+            "notes-details",
+            "getting-started-2",
+            "orphan"
+        ]
+    );
+
+    // Links stay on the page
+    assert!(html.contains("<a href=\"#notes-details\">the details</a>"));
+    assert!(html.contains("<a href=\"#getting-started\">Getting Started</a>"));
+    assert!(!html.contains(".html\""));
+    assert!(html.contains("@media print"));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn site_is_written_to_disk() {
+    let temp = tempfile::tempdir().unwrap();
+    let dir = temp.path().join("site");
+    let about = about();
+    assert_eq!(about.write_site(&dir).unwrap(), 5);
+    for (file_name, html) in about.export_site() {
+        assert_eq!(std::fs::read_to_string(dir.join(file_name)).unwrap(), html);
+    }
+}
@@ -0,0 +1,119 @@
+//! Rebinding controls must never leave one key driving two actions.
+
+use egui::{Key, Modifiers, PointerButton, Vec2};
+use pocket_minimal::view::{Action, Binding, Bindings};
+
+fn keys(bindings: &Bindings, action: Action) -> Vec<Binding> {
+    bindings.of(action).collect()
+}
+
+/// Run one frame with `key` pressed, calling `frame` with the context.
+fn press(ctx: &egui::Context, key: Key, mut frame: impl FnMut(&egui::Context)) {
+    let input = egui::RawInput {
+        events: vec![egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        }],
+        ..Default::default()
+    };
+    let _ = ctx.run(input, |ctx| frame(ctx));
+}
+
+#[test]
+fn binding_moves_a_key_between_actions() {
+    let mut bindings = Bindings::default();
+    assert_eq!(
+        keys(&bindings, Action::Up),
+        [Binding::Key(Key::ArrowUp), Binding::Key(Key::W)]
+    );
+
+    // W now restarts, and no longer moves up
+    bindings.bind(Action::Restart, Binding::Key(Key::W));
+    assert_eq!(keys(&bindings, Action::Up), [Binding::Key(Key::ArrowUp)]);
+    assert_eq!(
+        keys(&bindings, Action::Restart),
+        [Binding::Key(Key::R), Binding::Key(Key::W)]
+    );
+
+    // Binding twice does not duplicate
+    bindings.bind(Action::Restart, Binding::Key(Key::W));
+    assert_eq!(keys(&bindings, Action::Restart).len(), 2);
+
+    let middle = Binding::Pointer(PointerButton::Middle);
+    bindings.bind(Action::Step, middle);
+    bindings.unbind(Action::Up, middle);
+    assert!(keys(&bindings, Action::Step).contains(&middle));
+    bindings.unbind(Action::Step, middle);
+    assert_eq!(keys(&bindings, Action::Step), [Binding::Key(Key::N)]);
+
+    for action in Action::ALL {
+        for binding in bindings.of(action) {
+            let actions = Action::ALL
+                .into_iter()
+                .filter(|&other| bindings.of(other).any(|b| b == binding))
+                .count();
+            assert_eq!(actions, 1, "{binding:?}");
+        }
+    }
+}
+
+#[test]
+fn keys_trigger_their_actions() {
+    let ctx = egui::Context::default();
+    let mut bindings = Bindings::default();
+    bindings.bind(Action::PlayPause, Binding::Key(Key::W));
+
+    let mut state = None;
+    press(&ctx, Key::W, |ctx| {
+        state = Some(ctx.input(|i| bindings.read(i)));
+    });
+    let state = state.unwrap();
+    assert!(state.pressed(Action::PlayPause));
+    assert!(!state.pressed(Action::Up));
+    assert_eq!(state.direction(), Vec2::ZERO);
+
+    let mut state = None;
+    press(&ctx, Key::ArrowLeft, |ctx| {
+        state = Some(ctx.input(|i| bindings.read(i)));
+    });
+    assert_eq!(state.unwrap().direction(), -Vec2::X);
+}
+
+#[test]
+fn capture_rebinds_the_next_key() {
+    let ctx = egui::Context::default();
+    let mut bindings = Bindings::default();
+
+    // Nothing is captured unless an action waits for a binding
+    let mut rebinding = None;
+    press(&ctx, Key::Q, |ctx| bindings.capture(ctx, &mut rebinding));
+    assert_eq!(bindings, Bindings::default());
+
+    rebinding = Some(Action::Left);
+    press(&ctx, Key::Q, |ctx| {
+        bindings.capture(ctx, &mut rebinding);
+        // Consumed, so it does not also trigger anything
+        assert!(!ctx.input(|i| i.key_pressed(Key::Q)));
+    });
+    assert_eq!(rebinding, None);
+    assert!(keys(&bindings, Action::Left).contains(&Binding::Key(Key::Q)));
+
+    // Capturing a key that is in use takes it from its action
+    rebinding = Some(Action::Left);
+    press(&ctx, Key::Space, |ctx| {
+        bindings.capture(ctx, &mut rebinding);
+    });
+    assert_eq!(keys(&bindings, Action::PlayPause), [Binding::Key(Key::P)]);
+
+    // Escape cancels
+    let before = bindings.clone();
+    rebinding = Some(Action::Down);
+    press(&ctx, Key::Escape, |ctx| {
+        bindings.capture(ctx, &mut rebinding);
+    });
+    assert_eq!(rebinding, None);
+    assert_eq!(bindings, before);
+}
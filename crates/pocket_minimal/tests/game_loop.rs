@@ -0,0 +1,50 @@
+//! Frame times become whole steps, without losing time or catching up forever.
+
+use pocket_minimal::view::{GameLoop, MAX_STEPS_PER_FRAME, STEP};
+
+fn running() -> GameLoop {
+    let mut game_loop = GameLoop::default();
+    game_loop.resume();
+    game_loop
+}
+
+#[test]
+fn leftover_time_carries_over() {
+    let mut game_loop = running();
+    assert_eq!(game_loop.advance(2.5 * STEP), 2);
+    // Half a step was left over, so three quarters more make one
+    assert_eq!(game_loop.advance(0.75 * STEP), 1);
+    assert_eq!(game_loop.advance(0.25 * STEP), 0);
+    assert_eq!(game_loop.advance(0.6 * STEP), 1);
+}
+
+#[test]
+fn long_frames_are_clamped() {
+    let mut game_loop = running();
+    assert_eq!(game_loop.advance(100.0 * STEP), MAX_STEPS_PER_FRAME);
+    // The rest of the hitch is dropped rather than caught up on
+    assert_eq!(game_loop.advance(0.5 * STEP), 0);
+    assert_eq!(
+        game_loop.advance(MAX_STEPS_PER_FRAME as f32 * STEP + 0.25 * STEP),
+        MAX_STEPS_PER_FRAME
+    );
+}
+
+#[test]
+fn pausing_and_stepping() {
+    let mut game_loop = GameLoop::default();
+    assert!(game_loop.paused);
+    assert_eq!(game_loop.advance(10.0 * STEP), 0);
+
+    game_loop.resume();
+    assert_eq!(game_loop.advance(0.5 * STEP), 0);
+    // Pausing forgets the partial step
+    game_loop.pause();
+    game_loop.resume();
+    assert_eq!(game_loop.advance(0.75 * STEP), 0);
+
+    game_loop.step();
+    assert!(game_loop.paused);
+    assert_eq!(game_loop.advance(0.0), 1);
+    assert_eq!(game_loop.advance(10.0 * STEP), 0);
+}
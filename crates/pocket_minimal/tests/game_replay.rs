@@ -0,0 +1,103 @@
+//! A recorded game must replay identically, tick for tick.
+
+use egui::{vec2, Vec2};
+use pocket_minimal::view::{Game, Mode, Recording, Snapshot, World, STEP};
+
+/// Play `ticks` ticks from `seed`, weaving around, and record the input.
+fn play(seed: u64, ticks: u32) -> Snapshot {
+    let mut world = World::new(seed, Mode::Classic);
+    let mut recording = Recording::new(seed, Mode::Classic);
+    for tick in 0..ticks {
+        world.direction = match tick / 25 % 5 {
+            0 => Vec2::X,
+            1 => vec2(-1.0, -0.5),
+            2 => Vec2::ZERO,
+            3 => vec2(0.3, 1.0),
+            _ => -Vec2::X,
+        };
+        if world.is_over() {
+            break;
+        }
+        recording.push(world.direction);
+        world.step(STEP);
+    }
+    Snapshot { world, recording }
+}
+
+#[test]
+fn replays_are_identical() {
+    for seed in [0, 1, 0xDEAD_BEEF, u64::MAX] {
+        let snapshot = play(seed, 3_000);
+        assert!(snapshot.world.tick > 100, "the game should last a while");
+        assert_eq!(snapshot.recording.len(), snapshot.world.tick);
+        assert!(snapshot.verify(STEP), "seed {seed} diverged");
+
+        let mut replayed = World::new(seed, Mode::Classic);
+        for direction in snapshot.recording.replay() {
+            replayed.direction = direction;
+            replayed.step(STEP);
+        }
+        assert!(snapshot.matches(&replayed));
+    }
+
+    assert_ne!(play(1, 1_000).world, play(2, 1_000).world);
+}
+
+#[test]
+fn save_slots() {
+    let mut game = Game::default();
+    game.save("first");
+    game.restart();
+    game.save("second");
+    assert_eq!(game.slots().keys().collect::<Vec<_>>(), ["first", "second"]);
+
+    let first = game.slots()["first"].clone();
+    assert!(game.load("first"));
+    assert_eq!(game.snapshot(), first);
+    assert!(!game.load("missing"));
+
+    game.replay(first);
+    assert!(game.is_replaying());
+}
+
+#[cfg(feature = "persistence")]
+#[test]
+fn replays_survive_serialization() {
+    let snapshot = play(42, 2_000);
+    let text = ron::to_string(&snapshot).unwrap();
+    let parsed: Snapshot = ron::from_str(&text).unwrap();
+    assert_eq!(parsed, snapshot);
+    assert!(parsed.verify(STEP));
+}
+
+#[cfg(feature = "persistence")]
+#[test]
+fn empty_runs_are_skipped() {
+    // `Recording::push` never makes runs of zero ticks, but a saved game may be edited
+    let recording: Recording = ron::from_str(
+        "(seed: 7, mode: Classic, inputs: [
+            (0, (x: 1.0, y: 0.0)),
+            (2, (x: 0.0, y: 1.0)),
+            (0, (x: -1.0, y: 0.0)),
+            (0, (x: -1.0, y: 0.0)),
+            (1, (x: -1.0, y: 0.0)),
+            (0, (x: 0.0, y: -1.0)),
+        ])",
+    )
+    .unwrap();
+    assert_eq!(recording.len(), 3);
+    assert!(!recording.is_empty());
+
+    let mut replay = recording.replay();
+    assert_eq!(
+        replay.by_ref().take(10).collect::<Vec<_>>(),
+        [Vec2::Y, Vec2::Y, -Vec2::X]
+    );
+    assert_eq!(replay.position(), 3);
+    assert_eq!(replay.next(), None);
+
+    let recording: Recording =
+        ron::from_str("(seed: 7, mode: Classic, inputs: [(0, (x: 1.0, y: 0.0))])").unwrap();
+    assert!(recording.is_empty());
+    assert_eq!(recording.replay().next(), None);
+}
@@ -0,0 +1,98 @@
+//! Views are replaced by anchor, and a reset starts them over.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use pocket_minimal::view::{View, ViewRegistry};
+
+/// Remembers the last deep link it opened.
+struct Page {
+    name: &'static str,
+    anchor: &'static str,
+    link: Option<String>,
+}
+
+impl eframe::App for Page {
+    fn update(&mut self, _ctx: &egui::Context, _frame: &mut eframe::Frame) {}
+}
+
+impl View for Page {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn icon(&self) -> &str {
+        "#"
+    }
+
+    fn anchor(&self) -> &str {
+        self.anchor
+    }
+
+    fn deep_link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    fn open_deep_link(&mut self, path: &str) -> bool {
+        self.link = Some(path.to_owned());
+        true
+    }
+}
+
+fn page(name: &'static str, anchor: &'static str) -> impl Fn() -> Page {
+    move || Page {
+        name,
+        anchor,
+        link: None,
+    }
+}
+
+fn names(views: &ViewRegistry) -> Vec<&str> {
+    views.iter().map(View::name).collect()
+}
+
+#[test]
+fn same_anchor_replaces_in_place() {
+    let mut views = ViewRegistry::default();
+    assert_eq!(views.first_anchor(), None);
+    views.add(page("One", "one"));
+    views.add(page("Two", "two"));
+    views.add(page("Three", "three"));
+    assert_eq!(names(&views), ["One", "Two", "Three"]);
+
+    let replaced = views.add(page("Second", "two"));
+    assert_eq!(replaced.name(), "Second");
+    assert_eq!(names(&views), ["One", "Second", "Three"]);
+    assert_eq!(views.get("two").map(View::name), Some("Second"));
+    assert_eq!(views.first_anchor(), Some("one"));
+    assert!(views.get("four").is_none());
+}
+
+#[test]
+fn reset_makes_fresh_views() {
+    let made = Rc::new(Cell::new(0));
+    let mut views = ViewRegistry::default();
+    views.add(page("One", "one"));
+    views.add({
+        let made = Rc::clone(&made);
+        move || {
+            made.set(made.get() + 1);
+            Page {
+                name: "Two",
+                anchor: "two",
+                link: None,
+            }
+        }
+    });
+    assert_eq!(made.get(), 1);
+
+    for view in views.iter_mut() {
+        view.open_deep_link("somewhere");
+    }
+    assert_eq!(views.get("two").unwrap().deep_link(), Some("somewhere"));
+
+    views.reset();
+    assert_eq!(made.get(), 2);
+    assert_eq!(names(&views), ["One", "Two"]);
+    assert!(views.iter().all(|view| view.deep_link().is_none()));
+}
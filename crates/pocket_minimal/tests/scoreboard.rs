@@ -0,0 +1,73 @@
+//! Leaderboards keep the best games of each mode, best first.
+
+use egui::Vec2;
+use pocket_minimal::view::{Mode, Recording, Scoreboard, Session, World, STEP, TOP_N};
+
+fn session(mode: Mode, score: u32, ticks: u64) -> Session {
+    Session {
+        mode,
+        seed: 0,
+        score,
+        ticks,
+        actions: 0,
+        finished_at: 0,
+    }
+}
+
+#[test]
+fn leaderboards_are_ranked_and_capped() {
+    let mut scoreboard = Scoreboard::default();
+    assert_eq!(scoreboard.submit(session(Mode::Classic, 10, 600)), Some(0));
+    assert_eq!(scoreboard.submit(session(Mode::Classic, 30, 600)), Some(0));
+    // The same score sooner ranks higher
+    assert_eq!(scoreboard.submit(session(Mode::Classic, 10, 300)), Some(1));
+    assert_eq!(scoreboard.submit(session(Mode::OneLife, 5, 60)), Some(0));
+
+    let scores: Vec<_> = scoreboard
+        .top(Mode::Classic)
+        .iter()
+        .map(|session| (session.score, session.ticks))
+        .collect();
+    assert_eq!(scores, [(30, 600), (10, 300), (10, 600)]);
+    assert_eq!(scoreboard.top(Mode::OneLife).len(), 1);
+    assert!(scoreboard.top(Mode::TimeAttack).is_empty());
+
+    for score in 100..100 + TOP_N as u32 {
+        assert!(scoreboard
+            .submit(session(Mode::Classic, score, 60))
+            .is_some());
+    }
+    assert_eq!(scoreboard.top(Mode::Classic).len(), TOP_N);
+    assert_eq!(scoreboard.submit(session(Mode::Classic, 1, 60)), None);
+
+    scoreboard.clear(Mode::Classic);
+    assert!(scoreboard.top(Mode::Classic).is_empty());
+    assert_eq!(scoreboard.top(Mode::OneLife).len(), 1);
+}
+
+#[test]
+fn session_statistics() {
+    let mut world = World::new(7, Mode::TimeAttack);
+    let mut recording = Recording::new(7, Mode::TimeAttack);
+    let limit = Mode::TimeAttack.time_limit().unwrap();
+    // Bounce between the walls, with some rests, to survive until the time is up
+    for tick in 0..limit + 10 {
+        world.direction = match tick / 30 % 4 {
+            0 => Vec2::X,
+            2 => -Vec2::X,
+            _ => Vec2::ZERO,
+        };
+        if world.is_over() {
+            break;
+        }
+        recording.push(world.direction);
+        world.step(STEP);
+    }
+    assert!(world.is_over());
+    assert!(world.tick <= limit);
+
+    let session = Session::of(&world, &recording);
+    assert_eq!(session.ticks, world.tick);
+    assert_eq!(session.actions, (world.tick as u32 + 59) / 60);
+    assert!(session.apm() > 0.0);
+}
@@ -0,0 +1,115 @@
+//! Search ranks title matches first and shows where the body matched.
+
+use std::collections::BTreeMap;
+
+use pocket_minimal::view::{match_ranges, Article, SearchIndex};
+
+fn index(articles: &[(&str, &str)]) -> SearchIndex {
+    let articles: BTreeMap<_, _> = articles
+        .iter()
+        .map(|(title, source)| {
+            let article = Article::new(title, "Table of Contents", source);
+            (article.title.clone(), article)
+        })
+        .collect();
+    SearchIndex::new(&articles)
+}
+
+fn titles(index: &SearchIndex, query: &str) -> Vec<String> {
+    index
+        .search(query)
+        .into_iter()
+        .map(|hit| hit.title)
+        .collect()
+}
+
+#[test]
+fn titles_outrank_bodies() {
+    let index = index(&[
+        (
+            "Rivers",
+            "Water flows downhill. Water is wet. Water, water.",
+        ),
+        ("Water", "Mostly about rivers."),
+        ("Water cycle", "Rain."),
+        ("Deserts", "Dry."),
+    ]);
+    // An exact title beats a title containing the term, which beats bodies
+    assert_eq!(titles(&index, "water"), ["Water", "Water cycle", "Rivers"]);
+    // Case doesn't matter, nor does extra whitespace
+    assert_eq!(
+        titles(&index, "  WATER  "),
+        ["Water", "Water cycle", "Rivers"]
+    );
+}
+
+#[test]
+fn every_term_must_match() {
+    let index = index(&[("A", "red green blue"), ("B", "red blue"), ("C", "green")]);
+    assert_eq!(titles(&index, "red blue"), ["A", "B"]);
+    assert_eq!(titles(&index, "red green"), ["A"]);
+    assert!(titles(&index, "purple").is_empty());
+    assert!(titles(&index, "   ").is_empty());
+}
+
+#[test]
+fn ties_are_alphabetical() {
+    let index = index(&[("Beta", "term"), ("Alpha", "term"), ("Gamma", "term")]);
+    assert_eq!(titles(&index, "term"), ["Alpha", "Beta", "Gamma"]);
+}
+
+#[test]
+fn snippets_surround_the_first_match() {
+    let before = "word ".repeat(20);
+    let after = " word".repeat(20);
+    let index = index(&[
+        ("Long", &format!("{before}needle{after}")),
+        ("Short", "A needle\nin   a haystack."),
+        ("Needle", "Nothing here."),
+    ]);
+    let hits = index.search("needle");
+    let snippet = |title: &str| {
+        hits.iter()
+            .find(|hit| hit.title == title)
+            .unwrap()
+            .snippet
+            .clone()
+    };
+
+    let long = snippet("Long").unwrap();
+    assert!(long.starts_with('…') && long.ends_with('…'), "{long}");
+    assert!(long.contains("needle"), "{long}");
+    assert!(long.chars().count() < before.len() + after.len(), "{long}");
+
+    // Short bodies are shown whole, with whitespace collapsed
+    assert_eq!(snippet("Short").as_deref(), Some("A needle in a haystack."));
+
+    // Only the title matched
+    assert_eq!(snippet("Needle"), None);
+}
+
+#[test]
+fn overlapping_matches_are_merged() {
+    let terms = ["ab".to_owned(), "bc".to_owned(), "x".to_owned()];
+    assert_eq!(match_ranges("ABC abc x", &terms), [0..3, 4..7, 8..9]);
+    assert!(match_ranges("nothing", &terms).is_empty());
+}
+
+#[test]
+fn updated_articles_are_searched_anew() {
+    let mut index = index(&[("Lakes", "Still water."), ("Rivers", "Running water.")]);
+    assert_eq!(titles(&index, "running"), ["Rivers"]);
+
+    index.update(&Article::new(
+        "Rivers",
+        "Table of Contents",
+        "Flowing water.",
+    ));
+    assert!(titles(&index, "running").is_empty());
+    assert_eq!(titles(&index, "flowing"), ["Rivers"]);
+    assert_eq!(titles(&index, "water"), ["Lakes", "Rivers"]);
+
+    // An article not indexed yet is added
+    index.update(&Article::new("Oceans", "Table of Contents", "Salt water."));
+    assert_eq!(titles(&index, "water"), ["Lakes", "Oceans", "Rivers"]);
+}
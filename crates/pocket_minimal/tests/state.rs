@@ -0,0 +1,141 @@
+//! State saved by past versions is still read, and state that can't be is backed up.
+#![cfg(feature = "persistence")]
+
+use std::collections::HashMap;
+
+use pocket_core::persistence::{backup_key, version_key, Persistence, Versioned, VERSION_KEY};
+use pocket_minimal::view::{About, Game, Scoreboard, View as _};
+use pocket_minimal::State;
+
+#[derive(Default)]
+struct Storage(HashMap<String, String>);
+
+impl eframe::Storage for Storage {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_owned(), value);
+    }
+
+    fn flush(&mut self) {}
+}
+
+/// Load `ron` saved without a version, and save it again as the current version.
+fn resave_unversioned(ron: &str) -> Storage {
+    let mut storage = Storage::default();
+    storage.0.insert(eframe::APP_KEY.to_owned(), ron.to_owned());
+
+    let mut persistence = Persistence::default();
+    let state: State = persistence.load(&storage).expect("the state is read");
+    persistence.save(&mut storage, &state);
+    storage
+}
+
+#[test]
+fn fixed_views_become_anchors() {
+    let fixture = include_str!("fixtures/state_v0_fixed_views.ron");
+    // Without migrating, the view shown is unreadable
+    assert!(State::read(State::version(), fixture).is_err());
+
+    let storage = resave_unversioned(fixture);
+    assert_eq!(storage.0[VERSION_KEY], State::version().to_string());
+
+    let ron = &storage.0[eframe::APP_KEY];
+    assert!(ron.contains("selected_view:\"Game\""), "{ron}");
+    assert!(ron.contains("backend_panel:(open:true"), "{ron}");
+    assert!(!ron.contains("about:"), "{ron}");
+}
+
+#[test]
+fn registered_views_are_kept() {
+    let storage = resave_unversioned(include_str!("fixtures/state_v0.ron"));
+
+    let ron = &storage.0[eframe::APP_KEY];
+    assert!(ron.contains("selected_view:\"Game\""), "{ron}");
+    assert!(ron.contains("inspection:true"), "{ron}");
+    // Run modes came later, with their defaults
+    assert!(ron.contains("run_mode:Reactive"), "{ron}");
+}
+
+#[test]
+fn unreadable_state_is_backed_up() {
+    let ron = "(selected_view:42)";
+    let mut storage = Storage::default();
+    storage.0.insert(eframe::APP_KEY.to_owned(), ron.to_owned());
+    storage
+        .0
+        .insert(VERSION_KEY.to_owned(), State::version().to_string());
+
+    let mut persistence = Persistence::default();
+    let state: Option<State> = persistence.load(&storage);
+    assert!(state.is_none());
+
+    persistence.save(&mut storage, &State::default());
+    assert_eq!(
+        storage.0[&backup_key(eframe::APP_KEY, State::version())],
+        ron
+    );
+}
+
+#[test]
+fn unreadable_unversioned_state_is_backed_up() {
+    for ron in ["(selected_view:42)", "not ron at all"] {
+        let mut storage = Storage::default();
+        storage.0.insert(eframe::APP_KEY.to_owned(), ron.to_owned());
+
+        let mut persistence = Persistence::default();
+        let state: Option<State> = persistence.load(&storage);
+        assert!(state.is_none(), "{ron}");
+
+        persistence.save(&mut storage, &State::default());
+        assert_eq!(storage.0[&backup_key(eframe::APP_KEY, 0)], ron);
+    }
+}
+
+#[test]
+fn views_are_saved_with_versions() {
+    let mut storage = Storage::default();
+    let mut persistence = Persistence::default();
+    About::default().save_state(&mut storage, "app/About", &mut persistence);
+    Game::default().save_state(&mut storage, "app/Game", &mut persistence);
+
+    assert_eq!(
+        storage.0[&version_key("app/About")],
+        About::version().to_string()
+    );
+    assert_eq!(
+        storage.0[&version_key("app/Game")],
+        Game::version().to_string()
+    );
+    assert_eq!(
+        storage.0[&version_key("app/Game/scoreboard")],
+        Scoreboard::version().to_string()
+    );
+
+    // And read back
+    let mut game = Game::default();
+    game.restore_state(&storage, "app/Game", &mut persistence);
+    assert_eq!(game.scoreboard(), &Scoreboard::default());
+}
+
+#[test]
+fn unreadable_view_state_is_backed_up() {
+    let ron = "(boards:42)";
+    let mut storage = Storage::default();
+    storage
+        .0
+        .insert("app/Game/scoreboard".to_owned(), ron.to_owned());
+
+    let mut persistence = Persistence::default();
+    let mut game = Game::default();
+    game.restore_state(&storage, "app/Game", &mut persistence);
+    game.save_state(&mut storage, "app/Game", &mut persistence);
+
+    // Saved before there were versions
+    assert_eq!(storage.0[&backup_key("app/Game/scoreboard", 0)], ron);
+    assert_ne!(storage.0["app/Game/scoreboard"], ron);
+    // Only the unreadable key is backed up
+    assert!(!storage.0.contains_key(&backup_key("app/Game", 0)));
+}
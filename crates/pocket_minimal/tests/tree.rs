@@ -0,0 +1,84 @@
+//! The table of contents must not depend on the order articles are loaded in.
+
+use std::collections::BTreeSet;
+
+use pocket_minimal::{Node, Orphans, Tree};
+
+fn node<'a>(title: &'a str, parent: &'a str, order: Option<i32>) -> Node<'a> {
+    Node {
+        title,
+        parent,
+        order,
+    }
+}
+
+fn titles(tree: &Tree) -> Vec<&str> {
+    tree.numbered()
+        .into_iter()
+        .map(|(_, tree)| tree.root.as_str())
+        .collect()
+}
+
+#[test]
+fn children_before_parents_are_attached_in_order() {
+    let nodes = [
+        node("1.1", "1", None),
+        node("2", "Root", Some(2)),
+        node("1", "Root", Some(1)),
+        node("Appendix", "Root", None),
+        node("1.0", "1", Some(0)),
+    ];
+    let (tree, orphans) = Tree::from_parents("Root", nodes.clone());
+    assert_eq!(titles(&tree), ["1", "1.0", "1.1", "2", "Appendix"]);
+    assert!(orphans.is_empty());
+
+    // Same ids, whatever order the nodes come in
+    let (reversed, _) = Tree::from_parents("Root", nodes.into_iter().rev());
+    assert_eq!(tree.ids(), reversed.ids());
+}
+
+#[test]
+fn orphans_are_reported() {
+    let nodes = [
+        node("A", "Root", None),
+        node("Lost", "Nowhere", None),
+        node("X", "Y", None),
+        node("Y", "X", None),
+    ];
+    let (tree, orphans) = Tree::from_parents("Root", nodes);
+    assert_eq!(titles(&tree), ["A"]);
+    assert_eq!(
+        orphans,
+        Orphans {
+            missing_parent: vec!["Lost".to_owned()],
+            unreachable: vec!["X".to_owned(), "Y".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn duplicate_titles_are_all_attached() {
+    let nodes = [
+        node("A", "Root", Some(1)),
+        node("Twin", "Root", Some(2)),
+        node("Twin", "Root", Some(3)),
+        node("Twin", "A", None),
+        node("Child", "Twin", None),
+    ];
+    let (tree, orphans) = Tree::from_parents("Root", nodes.clone());
+    assert!(orphans.is_empty(), "{orphans:?}");
+    // The children of "Twin" go below the first one, depth first
+    assert_eq!(titles(&tree), ["A", "Twin", "Child", "Twin", "Twin"]);
+
+    // Siblings sharing a title get the ids they would get from `Tree::new`
+    let siblings = Tree::new("Root", vec!["A", "Twin", "Twin"]);
+    assert_eq!(
+        tree.children.iter().map(|c| c.id).collect::<Vec<_>>(),
+        siblings.children.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    let ids = tree.ids();
+    assert_eq!(ids.iter().collect::<BTreeSet<_>>().len(), ids.len());
+
+    let (reversed, _) = Tree::from_parents("Root", nodes.into_iter().rev());
+    assert_eq!(tree.ids(), reversed.ids());
+}